@@ -1,10 +1,246 @@
 fn main() {
     linker_be_nice();
+    git_hash();
+    assets();
     println!("cargo:rustc-link-arg=-Tdefmt.x");
     // make sure linkall.x is the last linker script (otherwise might cause problems with flip-link)
     println!("cargo:rustc-link-arg=-Tlinkall.x");
 }
 
+// Used by `version::GIT_HASH`, which falls back to "unknown" when git isn't available
+fn git_hash() {
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/index");
+
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    if let Some(output) = output {
+        let hash = String::from_utf8_lossy(&output.stdout);
+        println!("cargo:rustc-env=BADGE_GIT_HASH={}", hash.trim());
+    }
+}
+
+// Used by `display::assets`: every PNG in `assets/` becomes a sprite constant, and every BDF
+// file a font
+fn assets() {
+    println!("cargo:rerun-if-changed=assets");
+
+    let mut paths: Vec<_> = std::fs::read_dir("assets")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+
+    let mut sprites = String::new();
+    let mut fonts = String::new();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .unwrap()
+            .to_string_lossy()
+            .chars()
+            .map(|character| {
+                if character.is_ascii_alphanumeric() {
+                    character.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => {
+                let (width, rgba) = read_png(&path);
+                sprites += &sprite_source(&path, &name, width, &rgba);
+            }
+            Some("bdf") => fonts += &font_source(&path, &name),
+            _ => continue,
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    // Imported here rather than in the module, where they'd be unused without any assets
+    let mut source = String::new();
+    if !sprites.is_empty() {
+        source += "use embedded_graphics::pixelcolor::Rgb565;\n\nuse super::Sprite;\n\n";
+        source += &sprites;
+    }
+    if !fonts.is_empty() {
+        source += "use super::{BitmapFont, BitmapGlyph};\n\n";
+        source += &fonts;
+    }
+
+    let out = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("assets.rs");
+    std::fs::write(out, source).expect("Unable to write the assets");
+}
+
+// A `BitmapFont` constant from a BDF file. Characters are taken to be Unicode, as they are in
+// most BDF fonts made these days.
+fn font_source(path: &std::path::Path, name: &str) -> String {
+    let text = std::fs::read_to_string(path).expect("Unable to open asset");
+    let bad = |line: &str| -> ! { panic!("Unable to read {}: bad line {line:?}", path.display()) };
+    let numbers = |line: &str| -> Vec<i32> {
+        line.split_whitespace()
+            .skip(1)
+            .map(|number| number.parse().unwrap_or_else(|_| bad(line)))
+            .collect()
+    };
+
+    let (mut ascent, mut descent) = (0, 0);
+    // Character, width, height, x and y offset, advance, and the pixel rows
+    let mut glyphs: Vec<(char, i32, i32, i32, i32, i32, Vec<u8>)> = Vec::new();
+    let mut glyph = None;
+    let mut in_bitmap = false;
+    for line in text.lines().map(str::trim) {
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "FONT_ASCENT" => ascent = numbers(line)[0],
+            "FONT_DESCENT" => descent = numbers(line)[0],
+            "ENCODING" => {
+                let encoding = numbers(line)[0];
+                let character = u32::try_from(encoding).ok().and_then(char::from_u32);
+                glyph = character.map(|character| (character, 0, 0, 0, 0, 0, Vec::new()));
+            }
+            "DWIDTH" => {
+                if let Some(glyph) = &mut glyph {
+                    glyph.5 = numbers(line)[0];
+                }
+            }
+            "BBX" => {
+                if let Some(glyph) = &mut glyph
+                    && let [width, height, x_offset, y_offset] = numbers(line)[..]
+                {
+                    (glyph.1, glyph.2, glyph.3, glyph.4) = (width, height, x_offset, y_offset);
+                }
+            }
+            "BITMAP" => in_bitmap = true,
+            "ENDCHAR" => {
+                in_bitmap = false;
+                glyphs.extend(glyph.take());
+            }
+            _ if in_bitmap => {
+                if let Some(glyph) = &mut glyph {
+                    let row = (0..line.len())
+                        .step_by(2)
+                        .map(|index| u8::from_str_radix(&line[index..index + 2], 16));
+                    for byte in row {
+                        glyph.6.push(byte.unwrap_or_else(|_| bad(line)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    glyphs.sort_by_key(|glyph| glyph.0);
+    glyphs.dedup_by_key(|glyph| glyph.0);
+
+    let mut bitmap = Vec::new();
+    let mut entries = Vec::new();
+    for (character, width, height, x_offset, y_offset, advance, rows) in glyphs {
+        entries.push(format!(
+            "BitmapGlyph::new({character:?}, {width}, {height}, {x_offset}, {y_offset}, {advance}, {})",
+            bitmap.len(),
+        ));
+        bitmap.extend(rows);
+    }
+    let bitmap: Vec<_> = bitmap.iter().map(u8::to_string).collect();
+    format!(
+        "/// From `{}`.\npub const {name}: BitmapFont<'static> = BitmapFont::new(&[{}], &[{}], {ascent}, {descent});\n",
+        path.display(),
+        entries.join(", "),
+        bitmap.join(", "),
+    )
+}
+
+// The width of the image and its pixels as RGBA
+fn read_png(path: &std::path::Path) -> (u32, Vec<[u8; 4]>) {
+    let file = std::fs::File::open(path).expect("Unable to open asset");
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .unwrap_or_else(|error| panic!("Unable to read {}: {error}", path.display()));
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .unwrap_or_else(|error| panic!("Unable to read {}: {error}", path.display()));
+    let bytes = &buffer[..info.buffer_size()];
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => bytes.chunks(4).map(|p| [p[0], p[1], p[2], p[3]]).collect(),
+        png::ColorType::Rgb => bytes.chunks(3).map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => {
+            bytes.chunks(2).map(|p| [p[0], p[0], p[0], p[1]]).collect()
+        }
+        png::ColorType::Grayscale => bytes.iter().map(|&gray| [gray, gray, gray, 255]).collect(),
+        png::ColorType::Indexed => unreachable!("expanded by the decoder"),
+    };
+    (info.width, rgba)
+}
+
+// A `Sprite` constant: indexed when there are few enough colours, with pixels more than half
+// transparent left out
+fn sprite_source(path: &std::path::Path, name: &str, width: u32, rgba: &[[u8; 4]]) -> String {
+    let rgb565 = |[red, green, blue, _]: [u8; 4]| (red >> 3, green >> 2, blue >> 3);
+    let color = |(red, green, blue): (u8, u8, u8)| format!("Rgb565::new({red}, {green}, {blue})");
+    let pixels: Vec<_> = rgba
+        .iter()
+        .map(|&pixel| (pixel[3] >= 128).then(|| rgb565(pixel)))
+        .collect();
+
+    let mut palette: Vec<_> = pixels.iter().flatten().copied().collect();
+    palette.sort();
+    palette.dedup();
+
+    let mut source = format!("/// From `{}`.\n", path.display());
+    if palette.len() < 256 {
+        // Index 255 is past the end of the palette, so left out
+        let indices: Vec<_> = pixels
+            .iter()
+            .map(|pixel| match pixel {
+                Some(pixel) => palette.binary_search(pixel).unwrap().to_string(),
+                None => "255".to_string(),
+            })
+            .collect();
+        let palette: Vec<_> = palette.into_iter().map(color).collect();
+        source += &format!(
+            "pub const {name}: Sprite<'static> = Sprite::indexed({width}, &[{}], &[{}]);\n",
+            indices.join(", "),
+            palette.join(", "),
+        );
+        return source;
+    }
+
+    // Too many colours for a palette: key out a colour the image doesn't use
+    let key = (0..=u16::MAX)
+        .map(|raw| {
+            (
+                (raw >> 11) as u8,
+                (raw >> 5 & 0x3f) as u8,
+                (raw & 0x1f) as u8,
+            )
+        })
+        .find(|key| palette.binary_search(key).is_err())
+        .expect("Unable to find a colour to key out");
+    let colors: Vec<_> = pixels
+        .iter()
+        .map(|pixel| color(pixel.unwrap_or(key)))
+        .collect();
+    let with_key = if pixels.contains(&None) {
+        format!(".with_key({})", color(key))
+    } else {
+        String::new()
+    };
+    source += &format!(
+        "pub const {name}: Sprite<'static> = Sprite::raw({width}, &[{}]){with_key};\n",
+        colors.join(", "),
+    );
+    source
+}
+
 fn linker_be_nice() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
@@ -0,0 +1,115 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    Config, MAX_HEAP_SIZE,
+    safety::SafeDisplay,
+    settings::SettingsStore,
+    theme,
+    version::{Slot, Version},
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, iso_8859_1::FONT_10X20},
+    prelude::*,
+    primitives::Rectangle,
+    text::Text,
+};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    // Initialise the hardware and the heap with our badge options!
+    let mut peripherals = badge_firmware::init(Config::default().with_heap_size(MAX_HEAP_SIZE));
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Read the version before the settings store takes over the flash for good
+    let version = Version::read(&ESP_APP_DESC, peripherals.FLASH.reborrow());
+    info!("{}", version);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let mut display: SafeDisplay<Display<'_>> = SafeDisplay::new(resources.display.into());
+
+    match SettingsStore::new(peripherals.FLASH) {
+        Ok(mut store) => theme::apply_settings(&store.load()),
+        Err(error) => warn!("Unable to load settings: {:?}", error),
+    }
+    let theme = theme::current();
+
+    let slot = match version.slot {
+        Some(Slot::Factory) => "factory".into(),
+        Some(Slot::Ota(index)) => alloc::format!("ota_{}", index),
+        Some(Slot::Test) => "test".into(),
+        None => "unknown".into(),
+    };
+    let lines = [
+        (
+            alloc::format!("{} {}", version.project_name, version.version),
+            theme.text,
+        ),
+        (alloc::format!("git {}", version.git_hash), theme.text),
+        (
+            alloc::format!("built {} {}", version.build_date, version.build_time),
+            theme.text_dim,
+        ),
+        (
+            alloc::format!("IDF {}", version.idf_version),
+            theme.text_dim,
+        ),
+        (alloc::format!("slot {}", slot), theme.text_dim),
+        (
+            alloc::format!("ID {}", version.badge_id_string()),
+            theme.text,
+        ),
+    ];
+
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            theme.background,
+        )
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+
+    // Note: text coordinates are for the *bottom* left point, and the font is 20 pixels tall
+    for (y, (text, color)) in (24..).step_by(26).zip(lines.iter()) {
+        let text_style = MonoTextStyle::new(&FONT_10X20, *color);
+        Text::new(text, Point::new(8, y), text_style)
+            .draw(&mut display)
+            .map(|_| ())
+            .unwrap_or_else(|_| warn!("Unable to draw text"));
+    }
+
+    loop {
+        Timer::after(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,183 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_futures::select::{Either, select};
+use embassy_time::{Duration, Timer};
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    Config, MAX_HEAP_SIZE,
+    input::{Button, ButtonEvent, ButtonEventKind, ButtonEvents, ButtonSet},
+    safety::SafeDisplay,
+    settings::SettingsStore,
+    theme,
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, iso_8859_1::FONT_10X20},
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::Text,
+};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// How often to look at the raw pin levels. This has to be longer than the debounce time, or
+// debouncing would be cut short every time and never finish.
+const POLL_INTERVAL: Duration = Duration::from_millis(40);
+
+// For the sake of readability: the display resolution is 320x170. The buttons are laid out
+// three by three, with a status line at the bottom.
+const CELL_WIDTH: i32 = 106;
+const CELL_HEIGHT: i32 = 50;
+const INDICATOR_SIZE: u32 = 16;
+const STATUS_TOP: i32 = 3 * CELL_HEIGHT;
+
+fn cell_origin(button: Button) -> Point {
+    let index = button as i32;
+    Point::new((index % 3) * CELL_WIDTH + 4, (index / 3) * CELL_HEIGHT)
+}
+
+fn draw_label(display: &mut SafeDisplay<Display<'_>>, button: Button) {
+    let text_style = MonoTextStyle::new(&FONT_10X20, theme::current().text);
+    // Note: text coordinates are for the *bottom* left point
+    Text::new(
+        button.name(),
+        cell_origin(button) + Point::new(0, 18),
+        text_style,
+    )
+    .draw(display)
+    .map(|_| ())
+    .unwrap_or_else(|_| warn!("Unable to draw text"));
+}
+
+// The left square shows the raw pin, the right one what's left after debouncing
+fn draw_indicators(
+    display: &mut SafeDisplay<Display<'_>>,
+    button: Button,
+    raw: bool,
+    debounced: bool,
+) {
+    let theme = theme::current();
+    let top_left = cell_origin(button) + Point::new(0, 24);
+    for (offset, down) in [(0, raw), (INDICATOR_SIZE as i32 + 8, debounced)] {
+        let style = if down {
+            PrimitiveStyle::with_fill(theme.widget)
+        } else {
+            PrimitiveStyle::with_stroke(theme.border, 2)
+        };
+        let square = Rectangle::new(
+            top_left + Point::new(offset, 0),
+            Size::new(INDICATOR_SIZE, INDICATOR_SIZE),
+        );
+        display
+            .fill_solid(&square, theme.background)
+            .and_then(|_| square.into_styled(style).draw(display))
+            .unwrap_or_else(|_| warn!("Unable to draw indicator"));
+    }
+}
+
+fn draw_status(display: &mut SafeDisplay<Display<'_>>, text: &str) {
+    let theme = theme::current();
+    display
+        .fill_solid(
+            &Rectangle::new(
+                Point::new(0, STATUS_TOP),
+                Size::new(320, 170 - STATUS_TOP as u32),
+            ),
+            theme.background,
+        )
+        .unwrap_or_else(|_| warn!("Unable to clear status"));
+    let text_style = MonoTextStyle::new(&FONT_10X20, theme.text_dim);
+    Text::new(text, Point::new(4, 166), text_style)
+        .draw(display)
+        .map(|_| ())
+        .unwrap_or_else(|_| warn!("Unable to draw text"));
+}
+
+fn describe(event: ButtonEvent) -> alloc::string::String {
+    let kind = match event.kind {
+        ButtonEventKind::Pressed => "pressed",
+        ButtonEventKind::Released => "released",
+        ButtonEventKind::Held(_) => "held",
+        ButtonEventKind::Repeated => "repeated",
+    };
+    alloc::format!("{} {}", event.button.name(), kind)
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    // Initialise the hardware and the heap with our badge options!
+    let peripherals = badge_firmware::init(Config::default().with_heap_size(MAX_HEAP_SIZE));
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let mut display: SafeDisplay<Display<'_>> = SafeDisplay::new(resources.display.into());
+    let mut buttons: Buttons = resources.buttons.into();
+
+    // Use the saved theme, but a broken settings store shouldn't stop us testing the buttons
+    match SettingsStore::new(peripherals.FLASH) {
+        Ok(mut store) => theme::apply_settings(&store.load()),
+        Err(error) => warn!("Unable to load settings: {:?}", error),
+    }
+
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            theme::current().background,
+        )
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+    for button in Button::ALL {
+        draw_label(&mut display, button);
+        draw_indicators(&mut display, button, false, false);
+    }
+    draw_status(&mut display, "Left raw, right debounced");
+    info!("Button test ready");
+
+    let mut events = ButtonEvents::new(&mut buttons);
+    let mut shown = (ButtonSet::EMPTY, ButtonSet::EMPTY);
+    loop {
+        // Waiting for an event can be cut short safely, so look at the raw levels in between
+        if let Either::First(event) = select(events.next(), Timer::after(POLL_INTERVAL)).await {
+            info!("{}", event);
+            draw_status(&mut display, &describe(event));
+        }
+
+        // Only redraw the buttons that changed, the display is slow compared to the pins
+        let state = (events.raw_state(), events.state());
+        for button in Button::ALL {
+            let before = (shown.0.contains(button), shown.1.contains(button));
+            let now = (state.0.contains(button), state.1.contains(button));
+            if before != now {
+                draw_indicators(&mut display, button, now.0, now.1);
+            }
+        }
+        shown = state;
+    }
+}
@@ -0,0 +1,153 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Instant, Timer};
+use esp_hal::{clock::CpuClock, timer::timg::TimerGroup};
+use esp_println as _;
+
+use badge_firmware::{
+    Config, MAX_HEAP_SIZE,
+    display::{Splash, UnicodeTextStyle},
+    safety::{SafeDisplay, SafeLeds},
+    settings::{Settings, SettingsStore},
+    theme::{self, Font},
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Text},
+};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// One full breath in and out
+const BREATHE_PERIOD: Duration = Duration::from_secs(6);
+// The LEDs don't need to move fast, and every update costs power
+const BREATHE_STEP: Duration = Duration::from_millis(100);
+
+fn scale(color: Rgb<Srgb, u8>, level: f32) -> Rgb<Srgb, u8> {
+    Rgb::new(
+        (color.red as f32 * level) as u8,
+        (color.green as f32 * level) as u8,
+        (color.blue as f32 * level) as u8,
+    )
+}
+
+fn draw_name_tag(display: &mut SafeDisplay<Display<'_>>, settings: &Settings) {
+    // For the sake of readability: the display resolution is 320x170
+    const BAND_HEIGHT: u32 = 56;
+    let theme = theme::current();
+
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            theme.background,
+        )
+        .and_then(|_| {
+            display.fill_solid(
+                &Rectangle::new(Point::new(0, 0), Size::new(320, BAND_HEIGHT)),
+                theme.highlight,
+            )
+        })
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+
+    let name = match settings.card.name.as_str() {
+        "" => "Disobey 2026",
+        name => name,
+    };
+    // Note: text coordinates are for the *bottom* left point, or bottom centre when centred
+    let lines = [
+        ("HELLO my name is", 36, theme.highlight_text),
+        (name, 108, theme.text),
+        (settings.card.handle.as_str(), 144, theme.text_dim),
+    ];
+    for (text, y, color) in lines {
+        let text_style = UnicodeTextStyle::new(Font::Large, color);
+        Text::with_alignment(text, Point::new(160, y), text_style, Alignment::Center)
+            .draw(display)
+            .map(|_| ())
+            .unwrap_or_else(|_| warn!("Unable to draw text"));
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    // A name tag doesn't need much processing power, so save some battery
+    let peripherals = badge_firmware::init(
+        Config::default()
+            .with_cpu_clock(CpuClock::_80MHz)
+            .with_heap_size(MAX_HEAP_SIZE),
+    );
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let mut leds = SafeLeds::new(resources.leds.into());
+    let mut display: SafeDisplay<Display<'_>> = SafeDisplay::new(resources.display.into());
+
+    let settings = match SettingsStore::new(peripherals.FLASH) {
+        Ok(mut store) => store.load(),
+        Err(error) => {
+            warn!("Unable to load settings: {:?}", error);
+            Settings::default()
+        }
+    };
+    theme::apply_settings(&settings);
+
+    // Show who made the firmware before the name tag takes over the screen
+    Splash::new()
+        .with_title("Lanyard")
+        .with_version(ESP_APP_DESC.version())
+        .show(&mut display)
+        .await;
+
+    // The display keeps its picture, so draw the name tag once and leave it alone. The radio
+    // is never started and there are no other tasks, so the CPU sleeps between LED steps.
+    draw_name_tag(&mut display, &settings);
+    info!("Lanyard mode: {}", settings.card);
+
+    let start = Instant::now();
+    let mut last_color = None;
+    loop {
+        let elapsed = Instant::now().duration_since(start).as_millis();
+        let phase =
+            (elapsed % BREATHE_PERIOD.as_millis()) as f32 / BREATHE_PERIOD.as_millis() as f32;
+        // Ease in and out with a cosine, and keep a faint glow at the bottom of the breath
+        let level = 0.05 + 0.95 * (1.0 - libm::cosf(phase * 2.0 * core::f32::consts::PI)) / 2.0;
+        let color = scale(settings.led_color, level);
+
+        // Only talk to the LEDs when there's something new to show
+        if last_color != Some(color) {
+            leds.fill(color);
+            leds.update().await;
+            last_color = Some(color);
+        }
+        Timer::after(BREATHE_STEP).await;
+    }
+}
@@ -0,0 +1,193 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_time::Duration;
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    Config, MAX_HEAP_SIZE,
+    input::{Button, ButtonEventKind, ButtonEvents, ButtonSet},
+    led::{
+        LED_COUNT,
+        correction::{self, Correction},
+    },
+    safety::{SafeDisplay, SafeLeds},
+    settings::{Settings, SettingsStore},
+    theme,
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, iso_8859_1::FONT_10X20},
+    prelude::*,
+    primitives::Rectangle,
+    text::Text,
+};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// A mid grey shows tints best, full white is too bright to compare comfortably
+const TEST_COLOR: Rgb<Srgb, u8> = Rgb::new(128, 128, 128);
+const STEP: u8 = 5;
+
+const CHANNELS: [&str; 3] = ["Red", "Green", "Blue"];
+
+fn channel_mut(strength: &mut Rgb<Srgb, u8>, channel: usize) -> &mut u8 {
+    match channel {
+        0 => &mut strength.red,
+        1 => &mut strength.green,
+        _ => &mut strength.blue,
+    }
+}
+
+fn draw(
+    display: &mut SafeDisplay<Display<'_>>,
+    index: usize,
+    channel: usize,
+    strength: Rgb<Srgb, u8>,
+    can_save: bool,
+) {
+    let theme = theme::current();
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            theme.background,
+        )
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+
+    let values = [strength.red, strength.green, strength.blue];
+    let lines = [
+        (
+            alloc::format!("LED {} of {}", index + 1, LED_COUNT),
+            theme.text,
+        ),
+        (
+            alloc::format!("{}: {}", CHANNELS[channel], values[channel]),
+            theme.highlight_text,
+        ),
+        (
+            alloc::format!("R {} G {} B {}", values[0], values[1], values[2]),
+            theme.text_dim,
+        ),
+        ("</>: LED  A: channel".into(), theme.text_dim),
+        if can_save {
+            ("Up/Down: adjust  Start: save".into(), theme.text_dim)
+        } else {
+            ("Up/Down: adjust  Can't save".into(), theme.error)
+        },
+        ("Hold Select: only this LED".into(), theme.text_dim),
+    ];
+    // Note: text coordinates are for the *bottom* left point
+    for (line, (text, color)) in lines.iter().enumerate() {
+        let text_style = MonoTextStyle::new(&FONT_10X20, *color);
+        Text::new(text, Point::new(4, 24 + line as i32 * 26), text_style)
+            .draw(display)
+            .map(|_| ())
+            .unwrap_or_else(|_| warn!("Unable to draw text"));
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    // Initialise the hardware and the heap with our badge options!
+    let peripherals = badge_firmware::init(Config::default().with_heap_size(MAX_HEAP_SIZE));
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let mut leds = SafeLeds::new(resources.leds.into());
+    let mut display: SafeDisplay<Display<'_>> = SafeDisplay::new(resources.display.into());
+    let mut buttons: Buttons = resources.buttons.into();
+
+    // Without the settings the calibration can still be tried out, just not saved
+    let mut store = SettingsStore::new(peripherals.FLASH)
+        .inspect_err(|error| warn!("Unable to load settings: {:?}", error))
+        .ok();
+    let mut settings = store
+        .as_mut()
+        .map_or_else(Settings::default, SettingsStore::load);
+    theme::apply_settings(&settings);
+    let mut calibration: Correction = settings.led_correction;
+    correction::set(calibration);
+    info!("LED calibration ready");
+
+    let mut events = ButtonEvents::new(&mut buttons).with_auto_repeat(
+        ButtonSet::of(&[Button::Up, Button::Down]),
+        Duration::from_millis(400),
+        Duration::from_millis(80),
+    );
+    let (mut index, mut channel, mut solo) = (0, 0, false);
+    loop {
+        let can_save = store.is_some();
+        draw(
+            &mut display,
+            index,
+            channel,
+            calibration.get(index),
+            can_save,
+        );
+        for led in 0..LED_COUNT {
+            let lit = !solo || led == index;
+            leds.set(led, if lit { TEST_COLOR } else { Rgb::new(0, 0, 0) });
+        }
+        leds.update().await;
+
+        let event = events.next().await;
+        let mut strength = calibration.get(index);
+        match (event.button, event.kind) {
+            (Button::Select, ButtonEventKind::Pressed) => solo = true,
+            (Button::Select, ButtonEventKind::Released) => solo = false,
+            (_, ButtonEventKind::Pressed | ButtonEventKind::Repeated) => match event.button {
+                Button::Left => index = (index + LED_COUNT - 1) % LED_COUNT,
+                Button::Right => index = (index + 1) % LED_COUNT,
+                Button::A => channel = (channel + 1) % CHANNELS.len(),
+                Button::Up => {
+                    let value = channel_mut(&mut strength, channel);
+                    *value = value.saturating_add(STEP);
+                    calibration = calibration.with(index, strength);
+                }
+                Button::Down => {
+                    let value = channel_mut(&mut strength, channel);
+                    *value = value.saturating_sub(STEP);
+                    calibration = calibration.with(index, strength);
+                }
+                Button::Start => {
+                    settings.led_correction = calibration;
+                    match store.as_mut().map(|store| store.save(&settings)) {
+                        Some(Ok(())) => info!("Saved LED calibration"),
+                        Some(Err(error)) => warn!("Unable to save settings: {:?}", error),
+                        None => warn!("No settings to save the LED calibration in"),
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        correction::set(calibration);
+    }
+}
@@ -0,0 +1,299 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::{info, warn};
+use embassy_executor::{Spawner, task};
+use embassy_futures::select::{Either, select};
+use embassy_sync::{
+    blocking_mutex::{Mutex, raw::CriticalSectionRawMutex},
+    signal::Signal,
+};
+use embassy_time::Instant;
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    Config, MAX_HEAP_SIZE,
+    espnow::{self, BROADCAST_ADDRESS, EspNow},
+    input::{self, Button, ButtonEventKind, ButtonSubscriber},
+    led, motion,
+    palette::RAINBOW,
+    safety::{self, SafeDisplay, SafeLeds},
+    settings::SettingsStore,
+    tempo::{BeatClock, TapTempo, beat_from_micros, bpm_to_period},
+    theme,
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, iso_8859_1::FONT_10X20},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::Text,
+};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// The beat everything dances to, shared between all the tasks
+static CLOCK: Mutex<CriticalSectionRawMutex, Cell<BeatClock>> = Mutex::new(Cell::new(
+    BeatClock::new(Instant::from_ticks(0), bpm_to_period(120)),
+));
+// Tells the radio task to share our tempo with nearby badges
+static TEMPO_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+// Whether we send and follow the beat over ESP-NOW
+static SYNC_ENABLED: AtomicBool = AtomicBool::new(true);
+
+// Every beat message starts with this tag so we can ignore other ESP-NOW traffic
+const MESSAGE_TAG: [u8; 4] = *b"DBPM";
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Relative heights of the visualizer bars, shifted by one every beat
+const BAR_PATTERN: [f32; 8] = [0.6, 1.0, 0.4, 0.8, 0.3, 0.9, 0.5, 0.7];
+
+fn clock() -> BeatClock {
+    CLOCK.lock(|clock| clock.get())
+}
+
+fn beat_color(beat: u64) -> Rgb<Srgb, u8> {
+    // One colour per beat, cycling
+    RAINBOW.get(beat as usize)
+}
+
+fn to_rgb565(color: Rgb<Srgb, u8>) -> Rgb565 {
+    Rgb565::new(color.red >> 3, color.green >> 2, color.blue >> 3)
+}
+
+#[task]
+async fn led_task(leds: &'static mut SafeLeds<'static>) {
+    loop {
+        // The display task draws the same frame at the same time, so the two never drift apart
+        let now = motion::frame_start(motion::next_frame().await);
+        let clock = clock();
+
+        // Flash on the beat and fade out towards the next one
+        let color = beat_color(clock.beat(now));
+        let fade = 1.0 - clock.phase(now);
+        let level = fade * fade;
+        leds.fill(Rgb::new(
+            (color.red as f32 * level) as u8,
+            (color.green as f32 * level) as u8,
+            (color.blue as f32 * level) as u8,
+        ));
+        leds.update().await;
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "This still works on the hardware with no issues"
+)]
+#[task]
+async fn display_task(display: &'static mut SafeDisplay<Display<'static>>) {
+    // For the sake of readability: the display resolution is 320x170
+    const STROBE_HEIGHT: u32 = 44;
+    const BAR_WIDTH: u32 = 40;
+    const BAR_GAP: u32 = 4;
+    const BARS_TOP: i32 = STROBE_HEIGHT as i32 + 10;
+    const BARS_HEIGHT: u32 = 170 - BARS_TOP as u32;
+    // How long the strobe band stays lit after each beat
+    const STROBE_PHASE: f32 = 0.15;
+
+    let theme = theme::current();
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            theme.background,
+        )
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+
+    let mut last_beat = u64::MAX;
+    let mut strobe_lit = false;
+    loop {
+        let now = motion::frame_start(motion::next_frame().await);
+        let clock = clock();
+        let beat = clock.beat(now);
+        let phase = clock.phase(now);
+        let color = to_rgb565(beat_color(beat));
+
+        // Light up the strobe band on the beat, and only redraw it when it changes
+        let strobe_on = phase < STROBE_PHASE;
+        if beat != last_beat || strobe_on != strobe_lit {
+            let band = Rectangle::new(Point::new(0, 0), Size::new(320, STROBE_HEIGHT));
+            let background = if strobe_on { color } else { theme.background };
+            display
+                .fill_solid(&band, background)
+                .unwrap_or_else(|_| warn!("Unable to draw strobe"));
+
+            let text = alloc::format!("{} BPM", clock.bpm());
+            let text_style = MonoTextStyle::new(&FONT_10X20, theme.text);
+            Text::new(&text, Point::new(10, 28), text_style)
+                .draw(display)
+                .map(|_| ())
+                .unwrap_or_else(|_| warn!("Unable to draw tempo"));
+
+            last_beat = beat;
+            strobe_lit = strobe_on;
+        }
+
+        // Bars jump up on the beat and fall back down until the next one
+        for (i, x) in (0..BAR_PATTERN.len()).zip((0..).step_by((BAR_WIDTH + BAR_GAP) as usize)) {
+            let pattern = BAR_PATTERN[(i + beat as usize) % BAR_PATTERN.len()];
+            let height = (BARS_HEIGHT as f32 * pattern * (1.0 - phase)) as u32;
+            let empty = Rectangle::new(
+                Point::new(x, BARS_TOP),
+                Size::new(BAR_WIDTH, BARS_HEIGHT - height),
+            );
+            let filled = Rectangle::new(
+                Point::new(x, BARS_TOP + (BARS_HEIGHT - height) as i32),
+                Size::new(BAR_WIDTH, height),
+            );
+            display
+                .fill_solid(&empty, theme.background)
+                .and_then(|_| display.fill_solid(&filled, color))
+                .unwrap_or_else(|_| warn!("Unable to draw bar"));
+        }
+    }
+}
+
+#[task]
+async fn espnow_task(esp_now: &'static mut EspNow<'static>) {
+    loop {
+        let event = select(TEMPO_CHANGED.wait(), esp_now.receive_async()).await;
+        if !SYNC_ENABLED.load(Ordering::Relaxed) {
+            continue;
+        }
+        match event {
+            Either::First(()) => {
+                // Send our beat period and how far into the current beat we are
+                let now = Instant::now();
+                let clock = clock();
+                let period = clock.period().as_micros() as u32;
+                let since_beat = now.saturating_duration_since(clock.last_beat(now));
+                let mut message = [0u8; 12];
+                message[..4].copy_from_slice(&MESSAGE_TAG);
+                message[4..8].copy_from_slice(&period.to_le_bytes());
+                message[8..].copy_from_slice(&(since_beat.as_micros() as u32).to_le_bytes());
+                esp_now
+                    .send_async(&BROADCAST_ADDRESS, &message)
+                    .await
+                    .unwrap_or_else(|_| warn!("Unable to send beat"));
+            }
+            Either::Second(received) => {
+                let data = received.data();
+                if data.len() != 12 || data[..4] != MESSAGE_TAG {
+                    continue;
+                }
+                // Anyone can send these, so only tempos we could have tapped ourselves count
+                let Some((period, since_beat)) = beat_from_micros(
+                    u32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+                    u32::from_le_bytes([data[8], data[9], data[10], data[11]]),
+                ) else {
+                    warn!("Ignoring a beat out of range from a nearby badge");
+                    continue;
+                };
+                let now = Instant::now();
+                let Some(last_beat) = now.checked_sub(since_beat) else {
+                    continue;
+                };
+                CLOCK.lock(|clock| {
+                    let mut synced = clock.get();
+                    synced.set_period(now, period);
+                    synced.align(last_beat);
+                    clock.set(synced);
+                });
+                info!("Synced to {} BPM from a nearby badge", clock().bpm());
+            }
+        }
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    // Initialise the hardware and the heap with our badge options!
+    let peripherals = badge_firmware::init(Config::default().with_heap_size(MAX_HEAP_SIZE));
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    // Strobing is exactly what the flash limiter is for, so everything goes through it
+    let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+    let buttons = mk_static!(Buttons, resources.buttons.into());
+    let display = mk_static!(
+        SafeDisplay<Display<'static>>,
+        SafeDisplay::new(resources.display.into())
+    );
+    let esp_now = mk_static!(EspNow<'static>, espnow::init(peripherals.WIFI));
+
+    // Pick up the theme, flash limit, button mapping and LED calibration, but party on with the
+    // defaults if that fails
+    match SettingsStore::new(peripherals.FLASH) {
+        Ok(mut store) => {
+            let settings = store.load();
+            safety::apply_settings(&settings);
+            theme::apply_settings(&settings);
+            input::remap::apply_settings(&settings);
+            led::correction::apply_settings(&settings);
+        }
+        Err(error) => warn!("Unable to load settings: {:?}", error),
+    }
+    info!("Party mode! Tap B along to the beat, Select toggles sync");
+
+    let mut subscriber: ButtonSubscriber = input::spawn(&spawner, buttons).subscriber();
+    spawner.must_spawn(led_task(leds));
+    spawner.must_spawn(display_task(display));
+    spawner.must_spawn(espnow_task(esp_now));
+
+    let mut tap_tempo = TapTempo::new();
+    loop {
+        let event = subscriber.next_message_pure().await;
+        if event.kind != ButtonEventKind::Pressed {
+            continue;
+        }
+        match event.button {
+            Button::B => {
+                // Each tap lands on a beat, so the tapped tempo also sets the phase
+                let now = Instant::now();
+                if let Some(period) = tap_tempo.tap(now) {
+                    CLOCK.lock(|clock| {
+                        let mut tapped = clock.get();
+                        tapped.set_period(now, period);
+                        tapped.align(now);
+                        clock.set(tapped);
+                    });
+                    info!("Tempo {} BPM", clock().bpm());
+                    TEMPO_CHANGED.signal(());
+                }
+            }
+            Button::Select => {
+                let enabled = !SYNC_ENABLED.fetch_xor(true, Ordering::Relaxed);
+                info!("Beat sync {}", if enabled { "on" } else { "off" });
+            }
+            _ => {}
+        }
+    }
+}
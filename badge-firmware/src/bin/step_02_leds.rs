@@ -13,6 +13,7 @@ use embassy_time::{Duration, Timer};
 use esp_hal::timer::timg::TimerGroup;
 use esp_println as _;
 
+use badge_firmware::safety::SafeLeds;
 use disobey2026badge::*;
 use palette::{encoding::Srgb, rgb::Rgb};
 
@@ -34,7 +35,7 @@ esp_bootloader_esp_idf::esp_app_desc!();
 #[esp_rtos::main]
 async fn main(_spawner: Spawner) -> ! {
     // Initialise the hardware with our badge options!
-    let peripherals = disobey2026badge::init();
+    let peripherals = badge_firmware::init_default();
 
     // Split the peripherals into more usable resources
     let resources = disobey2026badge::split_resources!(peripherals);
@@ -56,7 +57,7 @@ async fn main(_spawner: Spawner) -> ! {
         Rgb::new(80, 0, 80),
     ];
     let mut rainbow_iter = RAINBOW.iter().cycle();
-    let mut leds: disobey2026badge::Leds<'_> = resources.leds.into();
+    let mut leds = SafeLeds::new(resources.leds.into());
     info!("Initialised LEDs");
 
     loop {
@@ -13,8 +13,8 @@ use embassy_time::{Duration, Timer};
 use esp_hal::timer::timg::TimerGroup;
 use esp_println as _;
 
+use badge_firmware::{led::Rainbow, safety::SafeLeds};
 use disobey2026badge::*;
-use palette::{encoding::Srgb, rgb::Rgb};
 
 #[panic_handler]
 fn panic(_: &core::panic::PanicInfo) -> ! {
@@ -27,25 +27,16 @@ extern crate alloc;
 // For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
 esp_bootloader_esp_idf::esp_app_desc!();
 
-// Set the palette for our LEDs
-const RAINBOW: [Rgb<Srgb, u8>; 6] = [
-    Rgb::new(80, 0, 0),
-    Rgb::new(80, 80, 0),
-    Rgb::new(0, 80, 0),
-    Rgb::new(0, 80, 80),
-    Rgb::new(0, 0, 80),
-    Rgb::new(80, 0, 80),
-];
+// Set up the rainbow for our LEDs
+const RAINBOW: Rainbow = Rainbow {
+    period: Duration::from_secs(6),
+    saturation: 1.0,
+    value: 0.3,
+};
 
 #[task]
-async fn led_task(leds: &'static mut Leds<'static>) {
-    let mut rainbow_iter = RAINBOW.iter().cycle();
-    loop {
-        let color = *rainbow_iter.next().unwrap();
-        leds.fill(color);
-        leds.update().await;
-        Timer::after(Duration::from_secs(1)).await;
-    }
+async fn led_task(leds: &'static mut SafeLeds<'static>) {
+    leds.effect(RAINBOW).await;
 }
 
 #[allow(
@@ -55,7 +46,7 @@ async fn led_task(leds: &'static mut Leds<'static>) {
 #[esp_rtos::main]
 async fn main(spawner: Spawner) -> ! {
     // Initialise the hardware with our badge options!
-    let peripherals = disobey2026badge::init();
+    let peripherals = badge_firmware::init_default();
 
     // Split the peripherals into more usable resources
     let resources = disobey2026badge::split_resources!(peripherals);
@@ -66,7 +57,7 @@ async fn main(spawner: Spawner) -> ! {
     // Start the real-time operating system using the default timer group
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     esp_rtos::start(timg0.timer0);
-    let leds = mk_static!(Leds<'static>, resources.leds.into());
+    let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
     info!("Initialised LEDs");
 
     spawner.must_spawn(led_task(leds));
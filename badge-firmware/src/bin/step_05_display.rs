@@ -9,23 +9,29 @@
 
 use defmt::{info, warn};
 use embassy_executor::{Spawner, task};
-use embassy_futures::select::select_array;
-use embassy_sync::{
-    blocking_mutex::raw::CriticalSectionRawMutex,
-    pubsub::{PubSubChannel, Publisher, Subscriber},
-};
+use embassy_futures::select::{Either, select};
+use embassy_time::{Duration, Ticker};
 use esp_hal::timer::timg::TimerGroup;
 use esp_println as _;
 
+use badge_firmware::{
+    display::{Framebuffer, TextBox},
+    input::{
+        BUTTON_CHANNEL, Button, ButtonEvent, ButtonEventKind, ButtonEvents, ButtonPublisher,
+        ButtonSet, ButtonSubscriber,
+    },
+    motion::{FRAME_TIME, Motion},
+    palette::BUTTONS,
+    safety::SafeLeds,
+};
 use disobey2026badge::*;
 use embedded_graphics::{
     mono_font::{MonoTextStyle, iso_8859_1::FONT_10X20},
     pixelcolor::Rgb565,
     prelude::*,
     primitives::{Circle, Line, PrimitiveStyle, Rectangle, StyledDrawable},
-    text::Text,
+    text::Alignment,
 };
-use palette::{encoding::Srgb, rgb::Rgb};
 
 #[panic_handler]
 fn panic(_: &core::panic::PanicInfo) -> ! {
@@ -34,94 +40,49 @@ fn panic(_: &core::panic::PanicInfo) -> ! {
 
 extern crate alloc;
 
-#[derive(Clone, Copy, defmt::Format)]
-enum ButtonPressEvent {
-    Up,
-    Down,
-    Left,
-    Right,
-    Stick,
-    A,
-    B,
-    Start,
-    Select,
-}
-
-static BUTTON_CHANNEL: PubSubChannel<CriticalSectionRawMutex, ButtonPressEvent, 8, 3, 1> =
-    PubSubChannel::new();
-type ButtonSubscriber = Subscriber<'static, CriticalSectionRawMutex, ButtonPressEvent, 8, 3, 1>;
-type ButtonPublisher = Publisher<'static, CriticalSectionRawMutex, ButtonPressEvent, 8, 3, 1>;
-
 // This creates a default app-descriptor required by the esp-idf bootloader.
 // For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
 esp_bootloader_esp_idf::esp_app_desc!();
 
-// Set the palette for our LEDs
-const PALETTE: [Rgb<Srgb, u8>; 9] = [
-    Rgb::new(80, 0, 0),
-    Rgb::new(80, 80, 0),
-    Rgb::new(0, 80, 0),
-    Rgb::new(0, 80, 80),
-    Rgb::new(0, 0, 80),
-    Rgb::new(80, 0, 80),
-    Rgb::new(80, 80, 80),
-    Rgb::new(0, 0, 0),
-    Rgb::new(120, 60, 30),
-];
-
 #[task]
-async fn led_task(mut subscriber: ButtonSubscriber, leds: &'static mut Leds<'static>) {
+async fn led_task(mut subscriber: ButtonSubscriber, leds: &'static mut SafeLeds<'static>) {
     loop {
         let event = subscriber.next_message_pure().await;
         // This is purposefully verbose for the sake of simplicity here.
         // Normally we would use something like the `num_enum` crate instead.
-        let color = match event {
-            ButtonPressEvent::Up => PALETTE[0],
-            ButtonPressEvent::Down => PALETTE[1],
-            ButtonPressEvent::Left => PALETTE[2],
-            ButtonPressEvent::Right => PALETTE[3],
-            ButtonPressEvent::Stick => PALETTE[4],
-            ButtonPressEvent::A => PALETTE[5],
-            ButtonPressEvent::B => PALETTE[6],
-            ButtonPressEvent::Start => PALETTE[7],
-            ButtonPressEvent::Select => PALETTE[8],
+        let color = match event.button {
+            Button::Up => BUTTONS.get(0),
+            Button::Down => BUTTONS.get(1),
+            Button::Left => BUTTONS.get(2),
+            Button::Right => BUTTONS.get(3),
+            Button::Stick => BUTTONS.get(4),
+            Button::A => BUTTONS.get(5),
+            Button::B => BUTTONS.get(6),
+            Button::Start => BUTTONS.get(7),
+            Button::Select => BUTTONS.get(8),
         };
         leds.fill(color);
         leds.update().await;
     }
 }
 
-#[allow(
-    clippy::large_stack_frames,
-    reason = "This still works on the hardware with no issues"
-)]
 #[task]
 async fn button_task(publisher: ButtonPublisher, buttons: &'static mut Buttons) {
+    // Holding left or right keeps pushing the owl, so it speeds up as long as you hold on
+    let mut events = ButtonEvents::new(buttons).with_auto_repeat(
+        ButtonSet::of(&[Button::Left, Button::Right]),
+        Duration::from_millis(200),
+        Duration::from_millis(60),
+    );
     loop {
-        match select_array([
-            Buttons::debounce_press(&mut buttons.up),
-            Buttons::debounce_press(&mut buttons.down),
-            Buttons::debounce_press(&mut buttons.left),
-            Buttons::debounce_press(&mut buttons.right),
-            Buttons::debounce_press(&mut buttons.stick),
-            Buttons::debounce_press(&mut buttons.a),
-            Buttons::debounce_press(&mut buttons.b),
-            Buttons::debounce_press(&mut buttons.start),
-            Buttons::debounce_press(&mut buttons.select),
-        ])
-        .await
-        {
-            ((), 0) => publisher.publish(ButtonPressEvent::Up).await,
-            ((), 1) => publisher.publish(ButtonPressEvent::Down).await,
-            ((), 2) => publisher.publish(ButtonPressEvent::Left).await,
-            ((), 3) => publisher.publish(ButtonPressEvent::Right).await,
-            ((), 4) => publisher.publish(ButtonPressEvent::Stick).await,
-            ((), 5) => publisher.publish(ButtonPressEvent::A).await,
-            ((), 6) => publisher.publish(ButtonPressEvent::B).await,
-            ((), 7) => publisher.publish(ButtonPressEvent::Start).await,
-            ((), 8) => publisher.publish(ButtonPressEvent::Select).await,
-            _ => unreachable!(),
+        let event = events.next().await;
+        if !matches!(
+            event.kind,
+            ButtonEventKind::Pressed | ButtonEventKind::Repeated
+        ) {
+            continue;
         }
+        publisher.publish(event).await;
     }
 }
 
@@ -144,16 +105,25 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
     const OWL_MIN_X: i32 = 0;
     const OWL_MAX_X: i32 = 320 - OWL_BODY_DIAMETER as i32;
     const OWL_Y: i32 = 10;
+    // The box the whole owl fits in
+    const OWL_WIDTH: i32 = OWL_BODY_DIAMETER as i32;
+    const OWL_HEIGHT: i32 = (OWL_BODY_DIAMETER + OWL_HEAD_DIAMETER) as i32;
+    // How much one press speeds up the owl, in pixels per frame
+    const OWL_PUSH: f32 = 2.0;
     let mut owl_x: i32 = 160 - OWL_BODY_DIAMETER as i32 / 2;
+    let mut owl_motion = Motion::new(owl_x, OWL_MIN_X, OWL_MAX_X);
 
     const OWL_STYLE: PrimitiveStyle<Rgb565> = PrimitiveStyle::with_stroke(Rgb565::WHITE, 1);
 
-    // Note: text coordinates are for the *bottom* left point
-    const TEXT_Y: i32 = 169;
+    // The text goes in a box along the bottom, as high as the 10x20 font
+    const TEXT_AREA: Rectangle = Rectangle::new(Point::new(0, 150), Size::new(320, 20));
     const TEXT_COLOR: Rgb565 = Rgb565::WHITE;
 
+    // Draw into a framebuffer, so each frame only shows once it's complete
+    let mut screen = Framebuffer::new(display);
+
     // Blank the display
-    display
+    screen
         .fill_solid(
             &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
             Rgb565::BLACK,
@@ -161,42 +131,67 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
         .unwrap_or_else(|_| warn!("Unable to blank display"));
 
     // Draw the text at the bottom
-    let text = "HELLO I AM AN OWL";
-    let text_x: i32 = 160 - (text.len() as i32 * 10) / 2;
-    let text_pos = Point::new(text_x, TEXT_Y);
     let text_style = MonoTextStyle::new(&FONT_10X20, TEXT_COLOR);
-    Text::new(text, text_pos, text_style)
-        .draw(display)
+    TextBox::new("HELLO I AM AN OWL", TEXT_AREA, text_style)
+        .with_alignment(Alignment::Center)
+        .draw(&mut screen)
         .unwrap_or_else(|_| {
             warn!("Unable to draw text :(");
-            text_pos
+            0
         });
+    screen
+        .present()
+        .unwrap_or_else(|_| warn!("Unable to update display"));
 
+    let mut ticker = Ticker::every(FRAME_TIME);
     loop {
-        let event = subscriber.next_message_pure().await;
-        let old_owl_x = owl_x;
-        match event {
-            ButtonPressEvent::Left => {
-                owl_x = (owl_x.saturating_sub(1)).max(OWL_MIN_X);
+        // Button presses push the owl around, and every frame it glides a bit further
+        match select(ticker.next(), subscriber.next_message_pure()).await {
+            Either::First(()) => {}
+            Either::Second(ButtonEvent {
+                button: Button::Left,
+                ..
+            }) => {
+                owl_motion.push(-OWL_PUSH);
+                continue;
             }
-            ButtonPressEvent::Right => {
-                owl_x = (owl_x.saturating_add(1)).min(OWL_MAX_X);
+            Either::Second(ButtonEvent {
+                button: Button::Right,
+                ..
+            }) => {
+                owl_motion.push(OWL_PUSH);
+                continue;
             }
-            _ => {}
+            Either::Second(_) => continue,
         }
 
+        let old_owl_x = owl_x;
+        owl_x = owl_motion.step();
         if owl_x == old_owl_x {
             continue;
         }
 
-        // Clear old owl position
-        let clear_area = Rectangle::new(
-            Point::new(owl_x, OWL_Y),
-            Size::new(OWL_BODY_DIAMETER, OWL_BODY_DIAMETER + OWL_HEAD_DIAMETER),
+        // Erase only the strip the owl moved out of. The rest of its old place is inside the
+        // new owl's box, which is cleared and drawn over anyway.
+        let (vacated_left, vacated_right) = if owl_x > old_owl_x {
+            (old_owl_x, owl_x.min(old_owl_x + OWL_WIDTH))
+        } else {
+            ((owl_x + OWL_WIDTH).max(old_owl_x), old_owl_x + OWL_WIDTH)
+        };
+        let vacated = Rectangle::with_corners(
+            Point::new(vacated_left, OWL_Y),
+            Point::new(vacated_right - 1, OWL_Y + OWL_HEIGHT - 1),
         );
-        display
-            .fill_solid(&clear_area, Rgb565::BLACK)
+        screen
+            .fill_solid(&vacated, Rgb565::BLACK)
             .unwrap_or_else(|_| warn!("Unable to clear old owl"));
+        let owl_area = Rectangle::new(
+            Point::new(owl_x, OWL_Y),
+            Size::new(OWL_WIDTH as u32, OWL_HEIGHT as u32),
+        );
+        screen
+            .fill_solid(&owl_area, Rgb565::BLACK)
+            .unwrap_or_else(|_| warn!("Unable to clear owl"));
 
         // Draw new owl
         let owl_head_middle_x = owl_x
@@ -206,7 +201,7 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
             Point::new(owl_x, OWL_Y + OWL_HEAD_DIAMETER as i32),
             OWL_BODY_DIAMETER,
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl body"));
 
         Circle::new(
@@ -216,7 +211,7 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
             ),
             OWL_HEAD_DIAMETER,
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl head"));
 
         Circle::new(
@@ -226,36 +221,42 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
             ),
             OWL_EYE_DIAMETER,
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl left eye"));
 
         Circle::new(
             Point::new(owl_head_middle_x + OWL_EYE_DISTANCE_X, OWL_EYE_Y),
             OWL_EYE_DIAMETER,
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl right eye"));
 
         Line::new(
             Point::new(owl_head_middle_x - OWL_BEAK_DISTANCE_X, OWL_BEAK_Y),
             Point::new(owl_head_middle_x, OWL_BEAK_Y + OWL_BEAK_DISTANCE_Y),
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl beak left side"));
 
         Line::new(
             Point::new(owl_head_middle_x + OWL_BEAK_DISTANCE_X, OWL_BEAK_Y),
             Point::new(owl_head_middle_x, OWL_BEAK_Y + OWL_BEAK_DISTANCE_Y),
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl beak right side"));
 
         Line::new(
             Point::new(owl_head_middle_x - OWL_BEAK_DISTANCE_X, OWL_BEAK_Y),
             Point::new(owl_head_middle_x + OWL_BEAK_DISTANCE_X, OWL_BEAK_Y),
         )
-        .draw_styled(&OWL_STYLE, display)
+        .draw_styled(&OWL_STYLE, &mut screen)
         .unwrap_or_else(|_| warn!("Unable to draw owl beak top"));
+
+        // Show the finished frame. Only the strip and the owl's box were drawn on, so only
+        // they are sent to the display.
+        screen
+            .present()
+            .unwrap_or_else(|_| warn!("Unable to update display"));
     }
 }
 
@@ -266,7 +267,7 @@ async fn display_task(mut subscriber: ButtonSubscriber, display: &'static mut Di
 #[esp_rtos::main]
 async fn main(spawner: Spawner) -> ! {
     // Initialise the hardware with our badge options!
-    let peripherals = disobey2026badge::init();
+    let peripherals = badge_firmware::init_default();
 
     // Split the peripherals into more usable resources
     let resources = disobey2026badge::split_resources!(peripherals);
@@ -277,18 +278,19 @@ async fn main(spawner: Spawner) -> ! {
     // Start the real-time operating system using the default timer group
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     esp_rtos::start(timg0.timer0);
-    let leds = mk_static!(Leds<'static>, resources.leds.into());
+    let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
     let buttons = mk_static!(Buttons, resources.buttons.into());
     let display = mk_static!(Display, resources.display.into());
     info!("Initialised LEDs");
 
+    // Our own button task publishes to the library's channel, so we don't start its task too
     let mut subscriber = BUTTON_CHANNEL.subscriber().unwrap();
     spawner.must_spawn(led_task(BUTTON_CHANNEL.subscriber().unwrap(), leds));
     spawner.must_spawn(display_task(BUTTON_CHANNEL.subscriber().unwrap(), display));
     spawner.must_spawn(button_task(BUTTON_CHANNEL.publisher().unwrap(), buttons));
 
     loop {
-        let message = subscriber.next_message_pure().await;
-        info!("Main received message: {:?}", message);
+        let event = subscriber.next_message_pure().await;
+        info!("Main received event: {:?}", event);
     }
 }
@@ -0,0 +1,121 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::{Spawner, task};
+use embassy_futures::select::{Either, select};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    espnow::{self, BROADCAST_ADDRESS, EspNow},
+    input::{self, Button, ButtonEvent, ButtonEventKind, ButtonSubscriber},
+    palette::RAINBOW,
+    safety::SafeLeds,
+};
+use disobey2026badge::*;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// The latest colour for the LED task to show, whether it came from us or another badge
+static LED_SIGNAL: Signal<CriticalSectionRawMutex, Rgb<Srgb, u8>> = Signal::new();
+
+// Every message we send starts with this tag so we can ignore other ESP-NOW traffic
+const MESSAGE_TAG: [u8; 4] = *b"DB26";
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[task]
+async fn led_task(leds: &'static mut SafeLeds<'static>) {
+    loop {
+        let color = LED_SIGNAL.wait().await;
+        leds.fill(color);
+        leds.update().await;
+    }
+}
+
+#[task]
+async fn espnow_task(mut subscriber: ButtonSubscriber, esp_now: &'static mut EspNow<'static>) {
+    let mut palette_iter = RAINBOW.cycle();
+    loop {
+        let event = select(subscriber.next_message_pure(), esp_now.receive_async()).await;
+        match event {
+            Either::First(ButtonEvent {
+                button: Button::A,
+                kind: ButtonEventKind::Pressed,
+                ..
+            }) => {
+                let color = palette_iter.next().unwrap();
+                let mut message = [0u8; 7];
+                message[..4].copy_from_slice(&MESSAGE_TAG);
+                message[4..].copy_from_slice(&[color.red, color.green, color.blue]);
+                esp_now
+                    .send_async(&BROADCAST_ADDRESS, &message)
+                    .await
+                    .unwrap_or_else(|_| warn!("Unable to send colour"));
+                LED_SIGNAL.signal(color);
+            }
+            Either::First(_) => {}
+            Either::Second(received) => match received.data() {
+                [tag @ .., red, green, blue] if tag == MESSAGE_TAG => {
+                    info!("Received colour from {:?}", received.info.src_address);
+                    LED_SIGNAL.signal(Rgb::new(*red, *green, *blue));
+                }
+                _ => warn!("Ignoring unknown message"),
+            },
+        }
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    // Initialise the hardware with our badge options!
+    let peripherals = badge_firmware::init_default();
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Reclaim heap from the first-stage bootloader
+    esp_alloc::heap_allocator!(#[esp_hal::ram(reclaimed)] size: 73744);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+    let buttons = mk_static!(Buttons, resources.buttons.into());
+    info!("Initialised LEDs");
+
+    // The radio needs both the heap and the scheduler, so it comes up last
+    let esp_now = mk_static!(EspNow<'static>, espnow::init(peripherals.WIFI));
+    info!("Initialised ESP-NOW");
+
+    // The library's button task publishes every button event for us
+    let subscribers = input::spawn(&spawner, buttons);
+    let mut subscriber = subscribers.subscriber();
+    spawner.must_spawn(led_task(leds));
+    spawner.must_spawn(espnow_task(subscribers.subscriber(), esp_now));
+
+    loop {
+        let message = subscriber.next_message_pure().await;
+        info!("Main received message: {:?}", message);
+    }
+}
@@ -0,0 +1,105 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use defmt::{info, warn};
+use embassy_executor::{Spawner, task};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    input::{self, Button, ButtonEventKind, ButtonSubscriber},
+    palette::BUTTONS,
+    safety::{self, SafeLeds},
+    settings::{Settings, SettingsStore},
+    theme,
+};
+use disobey2026badge::*;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// The colour the LED task should show
+static LED_SIGNAL: Signal<CriticalSectionRawMutex, Rgb<Srgb, u8>> = Signal::new();
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[task]
+async fn led_task(leds: &'static mut SafeLeds<'static>) {
+    loop {
+        let color = LED_SIGNAL.wait().await;
+        leds.fill(color);
+        leds.update().await;
+    }
+}
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    // Initialise the hardware with our badge options!
+    let peripherals = badge_firmware::init_default();
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Reclaim heap from the first-stage bootloader
+    esp_alloc::heap_allocator!(#[esp_hal::ram(reclaimed)] size: 73744);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+    let buttons = mk_static!(Buttons, resources.buttons.into());
+    info!("Initialised LEDs");
+
+    // Load whatever we saved last time, or the defaults on the very first boot
+    let mut store = SettingsStore::new(peripherals.FLASH).unwrap();
+    let mut settings: Settings = store.load();
+    safety::apply_settings(&settings);
+    theme::apply_settings(&settings);
+    info!("Loaded settings");
+
+    // Start from the saved colour if it's in our palette
+    let mut index = BUTTONS.position(settings.led_color).unwrap_or(0);
+    LED_SIGNAL.signal(settings.led_color);
+
+    let mut subscriber: ButtonSubscriber = input::spawn(&spawner, buttons).subscriber();
+    spawner.must_spawn(led_task(leds));
+
+    loop {
+        let event = subscriber.next_message_pure().await;
+        if event.kind != ButtonEventKind::Pressed {
+            continue;
+        }
+        match event.button {
+            Button::Left => index = BUTTONS.previous(index),
+            Button::Right => index = BUTTONS.next(index),
+            Button::A => {
+                settings.led_color = BUTTONS.get(index);
+                match store.save(&settings) {
+                    Ok(()) => info!("Saved colour {}", index),
+                    Err(error) => warn!("Unable to save settings: {:?}", error),
+                }
+                continue;
+            }
+            _ => continue,
+        }
+        LED_SIGNAL.signal(BUTTONS.get(index));
+    }
+}
@@ -0,0 +1,143 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+#![deny(clippy::large_stack_frames)]
+
+use core::f32::consts::TAU;
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_futures::select::{Either, select};
+use embassy_time::Ticker;
+use esp_hal::timer::timg::TimerGroup;
+use esp_println as _;
+
+use badge_firmware::{
+    display::{
+        Framebuffer, Transform,
+        wireframe::{CUBE, OWL},
+    },
+    input::{Button, ButtonEventKind, ButtonEvents},
+    motion::FRAME_TIME,
+    ui::TimingOverlay,
+};
+use disobey2026badge::*;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+extern crate alloc;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// How much faster or slower one press spins the mesh, in radians per frame
+const SPIN_STEP: f32 = 0.01;
+const MAX_SPIN: f32 = 0.2;
+
+#[allow(
+    clippy::large_stack_frames,
+    reason = "it's not unusual to allocate larger buffers etc. in main"
+)]
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    // Initialise the hardware with our badge options!
+    let peripherals = badge_firmware::init_default();
+
+    // Split the peripherals into more usable resources
+    let resources = disobey2026badge::split_resources!(peripherals);
+
+    // Reclaim heap from the first-stage bootloader
+    esp_alloc::heap_allocator!(#[esp_hal::ram(reclaimed)] size: 73744);
+
+    // Start the real-time operating system using the default timer group
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+    let buttons = mk_static!(Buttons, resources.buttons.into());
+    let display = mk_static!(Display, resources.display.into());
+    let mut events = ButtonEvents::new(buttons);
+    info!("Initialised the display");
+
+    let mut screen = Framebuffer::new(display);
+    screen
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 0), Size::new(320, 170)),
+            Rgb565::BLACK,
+        )
+        .unwrap_or_else(|_| warn!("Unable to blank display"));
+
+    let style = PrimitiveStyle::with_stroke(Rgb565::CSS_GOLD, 1);
+    let mut timing = TimingOverlay::new();
+    let mut mesh = OWL;
+    // The angles around each axis, and how much they change every frame
+    let mut angles = [0.0f32; 3];
+    let mut spin = [0.013, 0.03, 0.0];
+    // What the previous frame drew on, to clear before drawing the next one
+    let mut drawn = Rectangle::zero();
+
+    let mut ticker = Ticker::every(FRAME_TIME);
+    loop {
+        // Left and right spin the mesh around the vertical axis, up and down around the
+        // horizontal one, and A switches between the owl and a cube
+        match select(ticker.next(), events.next()).await {
+            Either::First(()) => {}
+            Either::Second(event) => {
+                if event.kind == ButtonEventKind::Pressed {
+                    match event.button {
+                        Button::Left => spin[1] -= SPIN_STEP,
+                        Button::Right => spin[1] += SPIN_STEP,
+                        Button::Up => spin[0] += SPIN_STEP,
+                        Button::Down => spin[0] -= SPIN_STEP,
+                        Button::A => {
+                            mesh = if mesh.vertices() == OWL.vertices() {
+                                CUBE
+                            } else {
+                                OWL
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+        }
+
+        timing.begin_frame();
+        for (angle, spin) in angles.iter_mut().zip(&mut spin) {
+            *spin = spin.clamp(-MAX_SPIN, MAX_SPIN);
+            *angle = (*angle + *spin) % TAU;
+        }
+
+        // Only the area the previous frame drew on needs clearing, and only it and the new
+        // one are sent to the display
+        screen
+            .fill_solid(&drawn, Rgb565::BLACK)
+            .unwrap_or_else(|_| warn!("Unable to clear the previous frame"));
+        let transform = Transform::new(angles[0], angles[1], angles[2]);
+        drawn = mesh
+            .draw(&mut screen, &transform, style)
+            .unwrap_or_else(|_| {
+                warn!("Unable to draw the mesh");
+                Rectangle::zero()
+            });
+
+        timing
+            .draw(&mut screen)
+            .unwrap_or_else(|_| warn!("Unable to draw the timing"));
+        timing
+            .measure_flush(|| screen.present())
+            .unwrap_or_else(|_| warn!("Unable to update display"));
+    }
+}
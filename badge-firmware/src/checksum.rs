@@ -0,0 +1,12 @@
+/// CRC-8 with polynomial 0x07, used to catch corrupted frames and flash records.
+pub(crate) fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
@@ -0,0 +1,90 @@
+//! The time of day, for showing the badge as a watch.
+//!
+//! The badge has no battery-backed clock, so it doesn't know the time until it's told with
+//! [`set_time`], for example from a [`Keyboard`](crate::ui::Keyboard) or a message from another
+//! badge. From then on [`now`] keeps counting with the system timer until the badge is reset:
+//!
+//! ```ignore
+//! clock::set_time(TimeOfDay::new(13, 37, 0));
+//! // ...and later, anywhere
+//! if let Some(time) = clock::now() {
+//!     info!("It's {}", time);
+//! }
+//! ```
+
+use core::{cell::Cell, fmt};
+
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use embassy_time::Instant;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+const MILLIS_PER_DAY: u64 = SECONDS_PER_DAY as u64 * 1000;
+
+// The time of day when the system timer started, in milliseconds from midnight
+static OFFSET: Mutex<CriticalSectionRawMutex, Cell<Option<u64>>> = Mutex::new(Cell::new(None));
+
+/// A time of day on the 24 hour clock, to the second.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, defmt::Format)]
+pub struct TimeOfDay {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl TimeOfDay {
+    pub const MIDNIGHT: Self = Self::new(0, 0, 0);
+
+    /// Out of range values carry over into the next field, and past midnight start from it
+    /// again, so 25:61:00 is 02:01:00.
+    pub const fn new(hours: u8, minutes: u8, seconds: u8) -> Self {
+        Self::from_seconds(hours as u32 * 3600 + minutes as u32 * 60 + seconds as u32)
+    }
+
+    /// The time `seconds` after midnight.
+    pub const fn from_seconds(seconds: u32) -> Self {
+        let seconds = seconds % SECONDS_PER_DAY;
+        Self {
+            hours: (seconds / 3600) as u8,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+        }
+    }
+
+    /// How many seconds after midnight this is.
+    pub const fn as_seconds(&self) -> u32 {
+        self.hours as u32 * 3600 + self.minutes as u32 * 60 + self.seconds as u32
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.hours, self.minutes, self.seconds
+        )
+    }
+}
+
+/// Set the clock to `time`, from which [`now`] counts on.
+pub fn set_time(time: TimeOfDay) {
+    let since_boot = Instant::now().as_millis() % MILLIS_PER_DAY;
+    let offset = (time.as_seconds() as u64 * 1000 + MILLIS_PER_DAY - since_boot) % MILLIS_PER_DAY;
+    OFFSET.lock(|cell| cell.set(Some(offset)));
+}
+
+/// The time of day, or `None` if the clock hasn't been set since the badge started.
+pub fn now() -> Option<TimeOfDay> {
+    millis_of_day().map(|millis| TimeOfDay::from_seconds((millis / 1000) as u32))
+}
+
+/// Milliseconds since midnight, for showing the time more smoothly than a second at a time, or
+/// `None` if the clock hasn't been set.
+pub fn millis_of_day() -> Option<u64> {
+    let offset = OFFSET.lock(Cell::get)?;
+    Some((offset + Instant::now().as_millis()) % MILLIS_PER_DAY)
+}
+
+pub fn is_set() -> bool {
+    OFFSET.lock(Cell::get).is_some()
+}
@@ -0,0 +1,98 @@
+//! Colour blending, and random colours from the hardware random number generator.
+//!
+//! Blending the sRGB bytes of two saturated colours passes through a dull, dark middle: halfway
+//! from red to green comes out a muddy olive. [`Blend::Linear`] mixes the light itself, and
+//! [`Blend::Oklab`] mixes the colours as we see them, which keeps fades bright and even:
+//!
+//! ```ignore
+//! let halfway = Blend::Oklab.mix(palette::RED, palette::GREEN, 0.5);
+//! ```
+//!
+//! The ESP32-S3 has a true random number generator, good enough for effects and games without
+//! seeding anything. The random functions use it directly:
+//!
+//! ```ignore
+//! leds.fill(color::random());
+//! let confetti = color::weighted(&palette::RAINBOW, &[4, 1, 1, 1, 1, 4]);
+//! ```
+
+use esp_hal::rng::Rng;
+use palette::{FromColor, Mix, Oklab, encoding::Srgb, rgb::Rgb};
+
+use crate::{led, palette::Palette};
+
+// The value of random colours, about as bright as the named palette colours
+const RANDOM_VALUE: f32 = 0.3;
+
+/// How to blend two colours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum Blend {
+    /// The sRGB bytes as they are. Cheapest, but dull in the middle.
+    Srgb,
+    /// The amount of light, so the brightness changes evenly.
+    Linear,
+    /// In the Oklab colour space, so the colour changes evenly to the eye.
+    #[default]
+    Oklab,
+}
+
+impl Blend {
+    /// The colour `t` of the way from `from` to `to`, with `t` from 0.0 to 1.0.
+    pub fn mix(self, from: Rgb<Srgb, u8>, to: Rgb<Srgb, u8>, t: f32) -> Rgb<Srgb, u8> {
+        let t = t.clamp(0.0, 1.0);
+        let (from, to) = (from.into_format::<f32>(), to.into_format::<f32>());
+        let mixed = match self {
+            Blend::Srgb => from.mix(to, t),
+            Blend::Linear => Rgb::from_linear(from.into_linear().mix(to.into_linear(), t)),
+            Blend::Oklab => {
+                let mixed = Oklab::from_color(from).mix(Oklab::from_color(to), t);
+                Rgb::from_color(mixed)
+            }
+        };
+        mixed.into_format()
+    }
+}
+
+/// A random number from 0 up to but not including `below`.
+pub fn random_below(below: u32) -> u32 {
+    Rng::new().random() % below.max(1)
+}
+
+/// A random hue in degrees, from 0.0 up to 360.0.
+pub fn random_hue() -> f32 {
+    Rng::new().random() as f32 / u32::MAX as f32 * 360.0
+}
+
+/// A random fully saturated colour, as bright as the named colours in
+/// [`palette`](crate::palette).
+pub fn random() -> Rgb<Srgb, u8> {
+    led::hsv(random_hue(), 1.0, RANDOM_VALUE)
+}
+
+/// A random colour from `palette`, each as likely as the others.
+pub fn random_from(palette: &Palette) -> Rgb<Srgb, u8> {
+    palette.get(random_below(palette.len() as u32) as usize)
+}
+
+/// A random colour from `palette`, each as likely as its weight in `weights`. Colours without a
+/// weight are never picked, and if every weight is 0 all colours are as likely.
+pub fn weighted(palette: &Palette, weights: &[u8]) -> Rgb<Srgb, u8> {
+    let total: u32 = weights
+        .iter()
+        .take(palette.len())
+        .map(|&weight| weight as u32)
+        .sum();
+    if total == 0 {
+        return random_from(palette);
+    }
+
+    let mut pick = random_below(total);
+    for (index, &weight) in weights.iter().enumerate() {
+        match pick.checked_sub(weight as u32) {
+            Some(rest) => pick = rest,
+            None => return palette.get(index),
+        }
+    }
+    // The weights add up to more than the pick, so we never get here
+    palette.get(0)
+}
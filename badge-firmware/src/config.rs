@@ -0,0 +1,96 @@
+//! Hardware initialisation with configurable options.
+//!
+//! [`init_default`] sets up the badge like the BSP's `init()`, leaving the heap to the caller as
+//! the tutorial steps do. [`Config::with_heap_size`] sets it up too. The scheduler needs `TIMG0`,
+//! so it's always started by the caller, from the peripherals this returns:
+//!
+//! ```ignore
+//! let peripherals = badge_firmware::init(
+//!     Config::default()
+//!         .with_cpu_clock(CpuClock::_80MHz)
+//!         .with_heap_size(MAX_HEAP_SIZE),
+//! );
+//! let timg0 = TimerGroup::new(peripherals.TIMG0);
+//! esp_rtos::start(timg0.timer0);
+//! ```
+
+use core::mem::MaybeUninit;
+
+use defmt::info;
+use esp_hal::{clock::CpuClock, peripherals::Peripherals};
+
+/// The largest heap [`init`] can set up, matching the RAM reclaimed from the bootloader.
+pub const MAX_HEAP_SIZE: usize = 73744;
+
+/// Options for [`init`].
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    cpu_clock: CpuClock,
+    heap_size: usize,
+    boot_log: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cpu_clock: CpuClock::max(),
+            heap_size: 0,
+            boot_log: false,
+        }
+    }
+}
+
+impl Config {
+    /// CPU clock speed, the maximum by default.
+    pub fn with_cpu_clock(mut self, cpu_clock: CpuClock) -> Self {
+        self.cpu_clock = cpu_clock;
+        self
+    }
+
+    /// Set up a heap of this many bytes in RAM reclaimed from the bootloader, capped at
+    /// [`MAX_HEAP_SIZE`]. Zero, the default, leaves the heap to the caller.
+    pub fn with_heap_size(mut self, heap_size: usize) -> Self {
+        self.heap_size = heap_size.min(MAX_HEAP_SIZE);
+        self
+    }
+
+    /// Log a summary of the configuration once initialised. Off by default.
+    pub fn with_boot_log(mut self, boot_log: bool) -> Self {
+        self.boot_log = boot_log;
+        self
+    }
+}
+
+/// Initialise the hardware with the options in `config`.
+pub fn init(config: Config) -> Peripherals {
+    let peripherals = esp_hal::init(esp_hal::Config::default().with_cpu_clock(config.cpu_clock));
+
+    if config.heap_size > 0 {
+        #[esp_hal::ram(reclaimed)]
+        static mut HEAP: MaybeUninit<[u8; MAX_HEAP_SIZE]> = MaybeUninit::uninit();
+
+        // SAFETY: `esp_hal::init` panics if called twice, so we only ever get here once and
+        // nothing else has a reference to `HEAP`.
+        unsafe {
+            esp_alloc::HEAP.add_region(esp_alloc::HeapRegion::new(
+                (&raw mut HEAP).cast::<u8>(),
+                config.heap_size,
+                esp_alloc::MemoryCapability::Internal.into(),
+            ));
+        }
+    }
+
+    if config.boot_log {
+        info!(
+            "Initialised: CPU clock {:?}, heap {} bytes",
+            config.cpu_clock, config.heap_size
+        );
+    }
+
+    peripherals
+}
+
+/// Initialise the hardware with the default options, like the BSP's `init()`.
+pub fn init_default() -> Peripherals {
+    init(Config::default())
+}
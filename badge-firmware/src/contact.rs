@@ -0,0 +1,273 @@
+//! Point-to-point contact exchange.
+//!
+//! Two badges held together swap a [`ContactCard`] using a small symmetric handshake: both
+//! sides beacon `Hello` with their session, answer a `Hello` with their card for that session,
+//! and acknowledge a card for their own session, sending their card again until it's
+//! acknowledged. The exchange is complete once we have their card and they have acknowledged
+//! ours.
+//!
+//! The protocol only needs a short-range, line-of-sight byte link. It is written against the
+//! [`ContactLink`] trait so the same handshake can run over an IR transceiver driven by RMT once
+//! the badge has one. There is no such link here yet: the BSP has no IR support to build it on.
+
+use alloc::string::String;
+use embassy_time::{Duration, Instant, with_timeout};
+
+use crate::checksum::crc8;
+
+/// Maximum length of a single field of a [`ContactCard`] in bytes.
+pub const MAX_FIELD_LEN: usize = 32;
+
+/// Maximum size of an encoded frame in bytes.
+pub const MAX_FRAME_LEN: usize = 4 + 2 + MAX_FIELD_LEN * 2 + 1;
+
+const MAGIC: u8 = 0xD0;
+const HELLO_INTERVAL: Duration = Duration::from_millis(150);
+
+/// What we tell the other badge about ourselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContactCard {
+    pub name: String,
+    pub handle: String,
+}
+
+impl ContactCard {
+    /// Create a card, truncating both fields to [`MAX_FIELD_LEN`] bytes on a character boundary.
+    pub fn new(name: &str, handle: &str) -> Self {
+        Self {
+            name: String::from(truncate(name)),
+            handle: String::from(truncate(handle)),
+        }
+    }
+}
+
+impl defmt::Format for ContactCard {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{} ({})", self.name.as_str(), self.handle.as_str());
+    }
+}
+
+fn truncate(s: &str) -> &str {
+    let mut end = s.len().min(MAX_FIELD_LEN);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+#[repr(u8)]
+enum FrameKind {
+    Hello = 1,
+    Card = 2,
+    Ack = 3,
+}
+
+impl FrameKind {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Hello),
+            2 => Some(Self::Card),
+            3 => Some(Self::Ack),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded frame: `[MAGIC, kind, session, len, payload.., crc8]`.
+struct Frame<'a> {
+    kind: FrameKind,
+    session: u8,
+    payload: &'a [u8],
+}
+
+fn encode(kind: FrameKind, session: u8, payload: &[u8], out: &mut [u8; MAX_FRAME_LEN]) -> usize {
+    let len = 4 + payload.len();
+    out[0] = MAGIC;
+    out[1] = kind as u8;
+    out[2] = session;
+    out[3] = payload.len() as u8;
+    out[4..len].copy_from_slice(payload);
+    out[len] = crc8(&out[..len]);
+    len + 1
+}
+
+fn decode(frame: &[u8]) -> Option<Frame<'_>> {
+    let (&crc, body) = frame.split_last()?;
+    if body.len() < 4 || body[0] != MAGIC || crc8(body) != crc {
+        return None;
+    }
+    let payload = &body[4..];
+    if payload.len() != body[3] as usize {
+        return None;
+    }
+    Some(Frame {
+        kind: FrameKind::from_u8(body[1])?,
+        session: body[2],
+        payload,
+    })
+}
+
+fn encode_card(card: &ContactCard, out: &mut [u8]) -> usize {
+    let mut pos = 0;
+    for field in [card.name.as_bytes(), card.handle.as_bytes()] {
+        out[pos] = field.len() as u8;
+        out[pos + 1..pos + 1 + field.len()].copy_from_slice(field);
+        pos += 1 + field.len();
+    }
+    pos
+}
+
+fn decode_card(mut payload: &[u8]) -> Option<ContactCard> {
+    let mut fields = [String::new(), String::new()];
+    for field in &mut fields {
+        let (&len, rest) = payload.split_first()?;
+        let len = len as usize;
+        if len > MAX_FIELD_LEN || rest.len() < len {
+            return None;
+        }
+        *field = String::from(core::str::from_utf8(&rest[..len]).ok()?);
+        payload = &rest[len..];
+    }
+    let [name, handle] = fields;
+    Some(ContactCard { name, handle })
+}
+
+/// A short-range byte link between two badges, e.g. an IR transceiver.
+#[allow(
+    async_fn_in_trait,
+    reason = "links are used from a single executor, Send bounds are not needed"
+)]
+pub trait ContactLink {
+    type Error: defmt::Format;
+
+    /// Send one frame.
+    async fn send(&mut self, frame: &[u8]) -> Result<(), Self::Error>;
+
+    /// Wait for one frame and return its length. Frames that don't fit `buf` may be dropped.
+    async fn receive(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// Reasons a contact exchange can fail.
+#[derive(Debug, defmt::Format)]
+pub enum ExchangeError<E> {
+    /// No complete handshake happened before the deadline.
+    Timeout,
+    /// The underlying link failed.
+    Link(E),
+}
+
+/// Swap contact cards with the badge at the other end of `link`.
+///
+/// `session` should be random per attempt so stale frames from an earlier exchange with
+/// another badge are ignored: a card only counts if it answers a hello for our session, and an
+/// ack only if it's for the session our card answered. Returns the other badge's card once both
+/// sides have it.
+pub async fn exchange<L: ContactLink>(
+    link: &mut L,
+    own: &ContactCard,
+    session: u8,
+    timeout: Duration,
+) -> Result<ContactCard, ExchangeError<L::Error>> {
+    let deadline = Instant::now() + timeout;
+    let mut card_payload = [0u8; MAX_FRAME_LEN];
+    let card_len = encode_card(own, &mut card_payload);
+    let mut out = [0u8; MAX_FRAME_LEN];
+    let mut buf = [0u8; MAX_FRAME_LEN];
+
+    let mut theirs: Option<ContactCard> = None;
+    // The session of the hello our card last answered, which their ack has to be for
+    let mut answered: Option<u8> = None;
+    let mut acknowledged = false;
+
+    let card = loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(ExchangeError::Timeout);
+        }
+
+        // A hello asks the other side for their card, so keep beaconing until we have it
+        if theirs.is_none() {
+            let len = encode(FrameKind::Hello, session, &[], &mut out);
+            link.send(&out[..len]).await.map_err(ExchangeError::Link)?;
+        }
+        // They stop sending hellos once they have our card, so if their ack was lost only
+        // sending the card again gets another one
+        if let Some(answered) = answered
+            && !acknowledged
+        {
+            let len = encode(
+                FrameKind::Card,
+                answered,
+                &card_payload[..card_len],
+                &mut out,
+            );
+            link.send(&out[..len]).await.map_err(ExchangeError::Link)?;
+        }
+
+        let wait = HELLO_INTERVAL.min(deadline - now);
+        let Ok(received) = with_timeout(wait, link.receive(&mut buf)).await else {
+            continue;
+        };
+        let len = received.map_err(ExchangeError::Link)?;
+        let Some(frame) = decode(&buf[..len]) else {
+            continue;
+        };
+
+        match frame.kind {
+            FrameKind::Hello => {
+                let len = encode(
+                    FrameKind::Card,
+                    frame.session,
+                    &card_payload[..card_len],
+                    &mut out,
+                );
+                link.send(&out[..len]).await.map_err(ExchangeError::Link)?;
+                answered = Some(frame.session);
+            }
+            FrameKind::Card if frame.session == session => {
+                if let Some(card) = decode_card(frame.payload) {
+                    theirs = Some(card);
+                    let len = encode(FrameKind::Ack, session, &[], &mut out);
+                    link.send(&out[..len]).await.map_err(ExchangeError::Link)?;
+                }
+            }
+            FrameKind::Ack if Some(frame.session) == answered => acknowledged = true,
+            FrameKind::Card | FrameKind::Ack => {}
+        }
+
+        if acknowledged && let Some(card) = theirs.take() {
+            break card;
+        }
+    };
+
+    // Our final ack or card may have been lost, so keep answering the cards they send again
+    // and the hellos they send until they have ours for a moment
+    let linger_end = Instant::now() + HELLO_INTERVAL * 3;
+    while let Ok(received) = with_timeout(
+        linger_end.saturating_duration_since(Instant::now()),
+        link.receive(&mut buf),
+    )
+    .await
+    {
+        let len = received.map_err(ExchangeError::Link)?;
+        let Some(frame) = decode(&buf[..len]) else {
+            continue;
+        };
+        let len = match frame.kind {
+            FrameKind::Card if frame.session == session => {
+                encode(FrameKind::Ack, session, &[], &mut out)
+            }
+            FrameKind::Hello => encode(
+                FrameKind::Card,
+                frame.session,
+                &card_payload[..card_len],
+                &mut out,
+            ),
+            FrameKind::Card | FrameKind::Ack => continue,
+        };
+        link.send(&out[..len]).await.map_err(ExchangeError::Link)?;
+    }
+
+    Ok(card)
+}
@@ -0,0 +1,58 @@
+//! Drawing on the display without redrawing all of it.
+//!
+//! Sending a full screen over SPI takes long enough to see, and apps usually only change a small
+//! part of it per frame. A [`Framebuffer`] keeps a copy of the screen in RAM and sends only the
+//! areas drawn on since the last [`present`](Framebuffer::present), which also keeps a frame
+//! from showing before it's finished:
+//!
+//! ```ignore
+//! let display = mk_static!(Display, resources.display.into());
+//! let mut screen = Framebuffer::new(display);
+//! screen.clear(Rgb565::BLACK)?;
+//! Circle::new(Point::new(10, 10), 20).draw_styled(&style, &mut screen)?;
+//! screen.present().unwrap_or_else(|_| warn!("Unable to update the display"));
+//! ```
+//!
+//! The rest draws on either: images such as a [`Sprite`] or a [`Qoi`] and what's made of them,
+//! text that wraps or goes beyond one font with a [`TextBox`] and [`UnicodeTextStyle`], 3D
+//! [`wireframe`]s, see-through [`Layer`]s, [`Rotated`] drawing for portrait and a [`Splash`] at
+//! startup. Each is described where it's defined.
+
+use embedded_graphics::prelude::Size;
+
+pub mod assets;
+mod bitmap_font;
+mod flipbook;
+mod framebuffer;
+mod layer;
+pub mod qoi;
+mod rotation;
+mod screenshot;
+mod smooth;
+mod splash;
+mod sprite;
+mod text;
+mod tilemap;
+mod unicode;
+pub mod wireframe;
+
+pub use bitmap_font::{BitmapFont, BitmapGlyph, BitmapTextStyle};
+pub use flipbook::{Flipbook, FlipbookPlayer, Frame};
+pub use framebuffer::{Framebuffer, MAX_DIRTY_REGIONS};
+pub use layer::Layer;
+pub use qoi::{Qoi, QoiError};
+pub use rotation::{Rotated, Rotation};
+pub use screenshot::SCREENSHOT_CHORD;
+pub use splash::{Logo, Splash};
+pub use sprite::Sprite;
+pub use text::TextBox;
+pub use tilemap::{NO_TILE, TileMap, Tileset};
+pub use unicode::UnicodeTextStyle;
+pub use wireframe::{Mesh, Transform};
+
+/// The display width in pixels.
+pub const WIDTH: u32 = 320;
+/// The display height in pixels.
+pub const HEIGHT: u32 = 170;
+/// The display size in pixels.
+pub const SIZE: Size = Size::new(WIDTH, HEIGHT);
@@ -0,0 +1,20 @@
+//! Sprites and fonts made from the PNG and BDF files in the `assets` directory when the firmware
+//! is built.
+//!
+//! Each file becomes a [`Sprite`](super::Sprite) constant named after it in upper case, with
+//! anything but letters and digits turned into underscores, so `assets/owl-left.png` is
+//! `OWL_LEFT`:
+//!
+//! ```ignore
+//! assets::OWL_LEFT.blit(&mut screen, Point::new(x, y))?;
+//! ```
+//!
+//! Images of up to 255 colours become indexed sprites at one byte a pixel, bigger ones take two.
+//! Pixels more than half transparent are left out when drawing. Only the sprites an app uses
+//! end up in its flash.
+//!
+//! BDF files become [`BitmapFont`](super::BitmapFont) constants named the same way, to draw
+//! with a [`BitmapTextStyle`](super::BitmapTextStyle). The characters are taken to be Unicode,
+//! and `?` is drawn for any the font doesn't have.
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
@@ -0,0 +1,264 @@
+//! Proportional fonts other than the built-in ones, usually made from BDF files at build time.
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{
+        Baseline,
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+    },
+};
+
+/// Where a character's pixels are in a [`BitmapFont`], and how it sits on the line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapGlyph {
+    character: char,
+    width: u8,
+    height: u8,
+    // From the pen position to the bottom left corner of the pixels, up being positive as in BDF
+    x_offset: i8,
+    y_offset: i8,
+    // How far the pen moves after the character
+    advance: u8,
+    // The first byte of the pixels in the bitmap, rows padded to whole bytes
+    offset: u32,
+}
+
+impl BitmapGlyph {
+    pub const fn new(
+        character: char,
+        width: u8,
+        height: u8,
+        x_offset: i8,
+        y_offset: i8,
+        advance: u8,
+        offset: u32,
+    ) -> Self {
+        Self {
+            character,
+            width,
+            height,
+            x_offset,
+            y_offset,
+            advance,
+            offset,
+        }
+    }
+
+    pub const fn character(&self) -> char {
+        self.character
+    }
+
+    pub const fn advance(&self) -> u32 {
+        self.advance as u32
+    }
+}
+
+/// A font of one bit per pixel glyphs of any width.
+///
+/// BDF files in the `assets` directory become fonts when the firmware is built, see
+/// [`assets`](super::assets). Draw with them through a [`BitmapTextStyle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapFont<'a> {
+    // Sorted by character
+    glyphs: &'a [BitmapGlyph],
+    bitmap: &'a [u8],
+    ascent: u32,
+    descent: u32,
+}
+
+impl<'a> BitmapFont<'a> {
+    /// A font of `glyphs` in order of their character, with pixels in `bitmap`. `ascent` and
+    /// `descent` are how far the line reaches above and below the baseline.
+    pub const fn new(
+        glyphs: &'a [BitmapGlyph],
+        bitmap: &'a [u8],
+        ascent: u32,
+        descent: u32,
+    ) -> Self {
+        Self {
+            glyphs,
+            bitmap,
+            ascent,
+            descent,
+        }
+    }
+
+    /// The height of a line of text.
+    pub const fn line_height(&self) -> u32 {
+        self.ascent + self.descent
+    }
+
+    /// The glyph of `character`, or of `?` if the font doesn't have it.
+    pub fn glyph(&self, character: char) -> Option<&BitmapGlyph> {
+        let find = |character| {
+            self.glyphs
+                .binary_search_by_key(&character, |glyph| glyph.character)
+                .ok()
+                .map(|index| &self.glyphs[index])
+        };
+        find(character).or_else(|| find('?'))
+    }
+
+    /// How wide `text` is drawn.
+    pub fn width(&self, text: &str) -> u32 {
+        text.chars()
+            .filter_map(|character| self.glyph(character))
+            .map(BitmapGlyph::advance)
+            .sum()
+    }
+
+    // Whether the pixel at `x`, `y` from the top left of `glyph` is set
+    fn pixel(&self, glyph: &BitmapGlyph, x: u32, y: u32) -> bool {
+        let row_bytes = (glyph.width as u32).div_ceil(8);
+        let index = (glyph.offset + y * row_bytes + x / 8) as usize;
+        self.bitmap
+            .get(index)
+            .is_some_and(|byte| byte & 0x80 >> (x % 8) != 0)
+    }
+}
+
+/// Draws text in a [`BitmapFont`], wherever a `MonoTextStyle` would do.
+///
+/// ```ignore
+/// let style = BitmapTextStyle::new(&assets::HELVETICA_14, theme.text);
+/// Text::new("Hello", Point::new(10, 20), style).draw(&mut screen)?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitmapTextStyle<'a> {
+    font: &'a BitmapFont<'a>,
+    text_color: Option<Rgb565>,
+    background_color: Option<Rgb565>,
+}
+
+impl<'a> BitmapTextStyle<'a> {
+    pub fn new(font: &'a BitmapFont<'a>, color: Rgb565) -> Self {
+        Self {
+            font,
+            text_color: Some(color),
+            background_color: None,
+        }
+    }
+
+    /// Fill the background behind the text with `color`.
+    pub fn with_background(mut self, color: Rgb565) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    // The top of the line the text at `position` is on, as with `MonoTextStyle`
+    fn line_top(&self, position: Point, baseline: Baseline) -> i32 {
+        let height = self.font.line_height().saturating_sub(1);
+        let offset = match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => height,
+            Baseline::Middle => height / 2,
+            Baseline::Alphabetic => self.font.ascent.saturating_sub(1),
+        };
+        position.y - offset as i32
+    }
+
+    fn draw_glyph<D>(&self, glyph: &BitmapGlyph, pen: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let Some(color) = self.text_color else {
+            return Ok(());
+        };
+        // `pen` is on the bottom row of the letters, the one just above the baseline
+        let top_left = pen
+            + Point::new(
+                glyph.x_offset as i32,
+                -(glyph.y_offset as i32 + glyph.height as i32 - 1),
+            );
+        let pixels = (0..glyph.height as u32).flat_map(move |y| {
+            (0..glyph.width as u32)
+                .filter(move |&x| self.font.pixel(glyph, x, y))
+                .map(move |x| Pixel(top_left + Point::new(x as i32, y as i32), color))
+        });
+        target.draw_iter(pixels)
+    }
+}
+
+impl TextRenderer for BitmapTextStyle<'_> {
+    type Color = Rgb565;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let top = self.line_top(position, baseline);
+        let width = self.font.width(text);
+        if let Some(background) = self.background_color {
+            let area = Rectangle::new(
+                Point::new(position.x, top),
+                Size::new(width, self.font.line_height()),
+            );
+            target.fill_solid(&area, background)?;
+        }
+
+        let mut pen = Point::new(position.x, top + self.font.ascent as i32 - 1);
+        for character in text.chars() {
+            if let Some(glyph) = self.font.glyph(character) {
+                self.draw_glyph(glyph, pen, target)?;
+                pen.x += glyph.advance() as i32;
+            }
+        }
+        Ok(Point::new(pen.x, position.y))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        if let Some(background) = self.background_color {
+            let top = self.line_top(position, baseline);
+            let area = Rectangle::new(
+                Point::new(position.x, top),
+                Size::new(width, self.font.line_height()),
+            );
+            target.fill_solid(&area, background)?;
+        }
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let width = self.font.width(text);
+        TextMetrics {
+            bounding_box: Rectangle::new(
+                Point::new(position.x, self.line_top(position, baseline)),
+                Size::new(width, self.font.line_height()),
+            ),
+            next_position: position + Point::new(width as i32, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.line_height()
+    }
+}
+
+impl CharacterStyle for BitmapTextStyle<'_> {
+    type Color = Rgb565;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+}
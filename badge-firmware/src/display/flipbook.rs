@@ -0,0 +1,160 @@
+//! Animations made of sprites shown one after another, like a flipbook.
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+use super::Sprite;
+
+/// A sprite and how long it shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Frame<'a> {
+    pub sprite: Sprite<'a>,
+    pub duration: Duration,
+}
+
+impl<'a> Frame<'a> {
+    pub const fn new(sprite: Sprite<'a>, duration: Duration) -> Self {
+        Self { sprite, duration }
+    }
+}
+
+/// Frames shown in order, once or over and over.
+///
+/// ```ignore
+/// static BLINK: [Frame; 3] = [
+///     Frame::new(assets::OWL_OPEN, Duration::from_millis(2000)),
+///     Frame::new(assets::OWL_HALF, Duration::from_millis(60)),
+///     Frame::new(assets::OWL_SHUT, Duration::from_millis(120)),
+/// ];
+///
+/// let mut player = FlipbookPlayer::new(Flipbook::new(&BLINK).looping())
+///     .with_background(theme.background);
+/// loop {
+///     player.draw(&mut screen, Point::new(120, 40))?;
+///     screen.present()?;
+///     motion::next_frame().await;
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Flipbook<'a> {
+    frames: &'a [Frame<'a>],
+    looping: bool,
+}
+
+impl<'a> Flipbook<'a> {
+    /// Show `frames` once, then hold the last one.
+    pub const fn new(frames: &'a [Frame<'a>]) -> Self {
+        Self {
+            frames,
+            looping: false,
+        }
+    }
+
+    /// Start over from the first frame after the last one.
+    pub const fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// How long one round takes.
+    pub fn duration(&self) -> Duration {
+        self.frames
+            .iter()
+            .fold(Duration::from_ticks(0), |total, frame| {
+                total + frame.duration
+            })
+    }
+
+    /// The index of the frame showing `elapsed` into the animation, or `None` without frames.
+    pub fn frame_at(&self, elapsed: Duration) -> Option<usize> {
+        let last = self.frames.len().checked_sub(1)?;
+        let duration = self.duration();
+        let mut elapsed = elapsed;
+        if self.looping && duration.as_ticks() > 0 {
+            elapsed = Duration::from_ticks(elapsed.as_ticks() % duration.as_ticks());
+        }
+
+        let mut end = Duration::from_ticks(0);
+        for (index, frame) in self.frames.iter().enumerate() {
+            end += frame.duration;
+            if elapsed < end {
+                return Some(index);
+            }
+        }
+        Some(last)
+    }
+
+    /// Whether a flipbook playing once has shown its last frame for its full duration.
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        !self.looping && elapsed >= self.duration()
+    }
+
+    pub fn frames(&self) -> &'a [Frame<'a>] {
+        self.frames
+    }
+}
+
+/// Plays a [`Flipbook`] from when it was created, drawing a frame only when it's time for the
+/// next one.
+#[derive(Clone, Debug)]
+pub struct FlipbookPlayer<'a> {
+    flipbook: Flipbook<'a>,
+    start: Instant,
+    shown: Option<usize>,
+    background: Option<Rgb565>,
+}
+
+impl<'a> FlipbookPlayer<'a> {
+    pub fn new(flipbook: Flipbook<'a>) -> Self {
+        Self {
+            flipbook,
+            start: Instant::now(),
+            shown: None,
+            background: None,
+        }
+    }
+
+    /// Fill the area of the previous frame with `color` before drawing the next, for frames with
+    /// transparent pixels or of different sizes.
+    pub fn with_background(mut self, color: Rgb565) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Start over from the first frame.
+    pub fn restart(&mut self) {
+        self.start = Instant::now();
+        self.shown = None;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.flipbook.is_finished(Instant::now() - self.start)
+    }
+
+    /// Draw the frame due now with its top left corner at `point`, if it isn't the one drawn
+    /// last. Returns whether anything was drawn.
+    pub fn draw<D>(&mut self, target: &mut D, point: Point) -> Result<bool, D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let Some(index) = self.flipbook.frame_at(Instant::now() - self.start) else {
+            return Ok(false);
+        };
+        if self.shown == Some(index) {
+            return Ok(false);
+        }
+
+        let frames = self.flipbook.frames;
+        if let (Some(background), Some(shown)) = (self.background, self.shown) {
+            let area = Rectangle::new(point, frames[shown].sprite.size());
+            target.fill_solid(&area, background)?;
+        }
+        frames[index].sprite.blit(target, point)?;
+        self.shown = Some(index);
+        Ok(true)
+    }
+}
@@ -0,0 +1,291 @@
+//! A copy of the screen in RAM, sent to the display one changed area at a time.
+//!
+//! The framebuffer is the back buffer of a double-buffered display, with the screen itself as
+//! the front buffer: drawing only changes RAM, and [`present`](Framebuffer::present) copies the
+//! finished frame over. Clearing an area and redrawing it never shows half done, so nothing
+//! flickers.
+//!
+//! Presents changing a quarter of the screen or more go through a
+//! [`FlashLimiter`](crate::safety::FlashLimiter), like drawing on a
+//! [`SafeDisplay`](crate::safety::SafeDisplay) does.
+
+use embassy_time::Instant;
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+use palette::{encoding::Srgb, rgb::Rgb};
+use static_cell::ConstStaticCell;
+
+use super::{HEIGHT, SIZE, WIDTH};
+use crate::{
+    color::Blend,
+    safety::{self, Average, FlashLimiter, Shift},
+};
+
+/// How many separate changed areas are kept track of. Past this, the closest ones are merged.
+pub const MAX_DIRTY_REGIONS: usize = 4;
+
+const PIXELS: usize = (WIDTH * HEIGHT) as usize;
+
+// At two bytes a pixel this is bigger than the whole heap, so it lives in a static instead
+static BUFFER: ConstStaticCell<[Rgb565; PIXELS]> = ConstStaticCell::new([Rgb565::BLACK; PIXELS]);
+
+/// A [`DrawTarget`] drawing into RAM, with [`present`](Self::present) to show the changes on
+/// the display underneath.
+pub struct Framebuffer<'d, D> {
+    display: &'d mut D,
+    pixels: &'static mut [Rgb565; PIXELS],
+    dirty: [Rectangle; MAX_DIRTY_REGIONS],
+    dirty_count: usize,
+    limiter: FlashLimiter,
+}
+
+impl<'d, D> Framebuffer<'d, D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// A black framebuffer in front of `display`. The first present sends the whole screen.
+    ///
+    /// There is RAM for only one framebuffer, so this can only be called once.
+    pub fn new(display: &'d mut D) -> Self {
+        let mut framebuffer = Self {
+            display,
+            pixels: BUFFER.take(),
+            dirty: [Rectangle::zero(); MAX_DIRTY_REGIONS],
+            dirty_count: 0,
+            limiter: FlashLimiter::new(),
+        };
+        framebuffer.mark_dirty(Rectangle::new(Point::zero(), SIZE));
+        framebuffer
+    }
+
+    /// The display underneath, for anything that needs it directly. What's drawn on it shows
+    /// until the framebuffer is presented over it.
+    pub fn display(&mut self) -> &mut D {
+        self.display
+    }
+
+    /// The areas drawn on since the last present.
+    pub fn dirty_regions(&self) -> &[Rectangle] {
+        &self.dirty[..self.dirty_count]
+    }
+
+    /// Count `area` as changed, to be sent with the next present.
+    pub fn mark_dirty(&mut self, area: Rectangle) {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return;
+        }
+
+        // Overlapping areas are merged, so no pixel is sent twice. The merged area may overlap
+        // another one in turn, so it goes through here again.
+        if let Some(index) = self
+            .dirty_regions()
+            .iter()
+            .position(|region| !region.intersection(&area).is_zero_sized())
+        {
+            self.merge(index, area);
+            return;
+        }
+        if self.dirty_count < MAX_DIRTY_REGIONS {
+            self.dirty[self.dirty_count] = area;
+            self.dirty_count += 1;
+            return;
+        }
+
+        // Out of room: grow whichever area grows the least by taking this one in
+        let growth = |region: &Rectangle| area_of(&envelope(region, &area)) - area_of(region);
+        if let Some(index) = (0..self.dirty_count).min_by_key(|&index| growth(&self.dirty[index])) {
+            self.merge(index, area);
+        }
+    }
+
+    // Replace the dirty region at `index` with one covering it and `area`
+    fn merge(&mut self, index: usize, area: Rectangle) {
+        let merged = envelope(&self.dirty[index], &area);
+        self.dirty_count -= 1;
+        self.dirty[index] = self.dirty[self.dirty_count];
+        self.mark_dirty(merged);
+    }
+
+    /// Show the frame drawn so far, by sending the areas drawn on since the last present to the
+    /// display. Call this once a frame is complete.
+    pub fn present(&mut self) -> Result<(), D::Error> {
+        self.present_with(|_, color| color)
+    }
+
+    // Like present, with `compose` choosing the colour to send for each pixel from its point
+    // and its colour in the framebuffer
+    pub(super) fn present_with(
+        &mut self,
+        compose: impl Fn(Point, Rgb565) -> Rgb565,
+    ) -> Result<(), D::Error> {
+        let dirty = self.dirty;
+        self.send(&dirty[..self.dirty_count], compose)?;
+        self.dirty_count = 0;
+        Ok(())
+    }
+
+    /// Send exactly `area` to the display right away, whether it changed or not, leaving the
+    /// rest for the next present. For a part of the screen that has to show with as little
+    /// delay as possible, such as a score in a game, without waiting for the whole frame.
+    ///
+    /// Changed areas entirely within `area` count as sent. Ones only partly within it are
+    /// still sent whole with the next present.
+    pub fn flush_region(&mut self, area: Rectangle) -> Result<(), D::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        self.send(&[area], |_, color| color)?;
+
+        let mut index = 0;
+        while index < self.dirty_count {
+            if area.intersection(&self.dirty[index]) == self.dirty[index] {
+                self.dirty_count -= 1;
+                self.dirty[index] = self.dirty[self.dirty_count];
+            } else {
+                index += 1;
+            }
+        }
+        Ok(())
+    }
+
+    // Copy `regions` of the framebuffer to the display through `compose`, brightened or darkened
+    // as a whole if they add up to enough of the screen to flash
+    fn send(
+        &mut self,
+        regions: &[Rectangle],
+        compose: impl Fn(Point, Rgb565) -> Rgb565,
+    ) -> Result<(), D::Error> {
+        let pixels = &*self.pixels;
+        let compose = &compose;
+        let composed = |region: Rectangle| {
+            region.points().map(move |point| {
+                compose(
+                    point,
+                    pixels[(point.y as u32 * WIDTH + point.x as u32) as usize],
+                )
+            })
+        };
+
+        let shift = if safety::is_large(regions.iter().map(area_of).sum(), SIZE) {
+            let mut average = Average::default();
+            for &region in regions {
+                composed(region).for_each(|color| average.add(color));
+            }
+            self.limiter.limit_area(Instant::now(), &average)
+        } else {
+            Shift::NONE
+        };
+        for &region in regions {
+            let colors = composed(region).map(|color| shift.apply(color));
+            self.display.fill_contiguous(&region, colors)?;
+        }
+        Ok(())
+    }
+
+    /// The colour drawn at `point` so far, or `None` if it's off the screen.
+    pub fn pixel(&self, point: Point) -> Option<Rgb565> {
+        Self::index(point).map(|index| self.pixels[index])
+    }
+
+    // Mix `color` into the pixel at `point`, `coverage` from 0.0 (none) to 1.0 (all of it).
+    // The area has to be marked dirty separately.
+    pub(super) fn blend(&mut self, point: Point, color: Rgb565, coverage: f32) {
+        let Some(index) = Self::index(point) else {
+            return;
+        };
+        self.pixels[index] = if coverage >= 1.0 {
+            color
+        } else {
+            let from = self.pixels[index];
+            from_srgb(Blend::Linear.mix(to_srgb(from), to_srgb(color), coverage))
+        };
+    }
+
+    fn index(point: Point) -> Option<usize> {
+        let (x, y) = (point.x as u32, point.y as u32);
+        (point.x >= 0 && point.y >= 0 && x < WIDTH && y < HEIGHT)
+            .then_some((y * WIDTH + x) as usize)
+    }
+}
+
+// The smallest rectangle covering both
+pub(super) fn envelope(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    let (Some(a_end), Some(b_end)) = (a.bottom_right(), b.bottom_right()) else {
+        return if a.is_zero_sized() { *b } else { *a };
+    };
+    Rectangle::with_corners(
+        a.top_left.component_min(b.top_left),
+        a_end.component_max(b_end),
+    )
+}
+
+fn to_srgb(color: Rgb565) -> Rgb<Srgb, u8> {
+    // Repeat the top bits in the bottom ones, so full brightness stays full
+    Rgb::new(
+        color.r() << 3 | color.r() >> 2,
+        color.g() << 2 | color.g() >> 4,
+        color.b() << 3 | color.b() >> 2,
+    )
+}
+
+fn from_srgb(color: Rgb<Srgb, u8>) -> Rgb565 {
+    Rgb565::new(color.red >> 3, color.green >> 2, color.blue >> 3)
+}
+
+fn area_of(rectangle: &Rectangle) -> u32 {
+    rectangle.size.width * rectangle.size.height
+}
+
+impl<D> OriginDimensions for Framebuffer<'_, D> {
+    fn size(&self) -> Size {
+        SIZE
+    }
+}
+
+impl<D> DrawTarget for Framebuffer<'_, D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // Mark the box around everything drawn at once rather than every pixel on its own
+        let mut drawn: Option<(Point, Point)> = None;
+        for Pixel(point, color) in pixels {
+            let Some(index) = Self::index(point) else {
+                continue;
+            };
+            self.pixels[index] = color;
+            drawn = Some(match drawn {
+                Some((min, max)) => (min.component_min(point), max.component_max(point)),
+                None => (point, point),
+            });
+        }
+        if let Some((min, max)) = drawn {
+            self.mark_dirty(Rectangle::with_corners(min, max));
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        for y in area.rows() {
+            let start = (y as u32 * WIDTH) as usize;
+            let columns = area.columns();
+            self.pixels[start + columns.start as usize..start + columns.end as usize].fill(color);
+        }
+        self.mark_dirty(area);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+        self.mark_dirty(self.bounding_box());
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! See-through layers drawn over a [`Framebuffer`] as it's presented.
+//!
+//! Without layers, anything drawn over something else, such as a score over a game, has to be
+//! drawn again whenever what's underneath changes. Drawn on a [`Layer`] instead, it stays out
+//! of the framebuffer and is put on top of it only on the way to the display, so the game can
+//! draw whatever it wants underneath and the score shows over it all the same.
+
+use alloc::{vec, vec::Vec};
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+use super::{Framebuffer, framebuffer::envelope};
+
+/// An offscreen area to draw on, shown over a [`Framebuffer`] with
+/// [`present_layers`](Framebuffer::present_layers). Only what's drawn on a layer shows, the rest
+/// of it is see-through.
+///
+/// A layer only covers its own area of the display, and is drawn on with coordinates from its
+/// top left corner, so it can be [moved](Self::move_to) around without redrawing it. The pixels
+/// live on the heap, at a little over two bytes each: a strip along the top of the display for a
+/// HUD takes a few kilobytes, but the whole display wouldn't fit.
+///
+/// ```ignore
+/// let mut hud = Layer::new(Rectangle::new(Point::zero(), Size::new(320, 20)));
+/// let mut player = Layer::new(Rectangle::new(position, Size::new(16, 16)));
+/// player_sprite.blit(&mut player, Point::zero())?;
+/// loop {
+///     // ...draw the level on the screen, and the score on the HUD when it changes
+///     player.move_to(position);
+///     screen.present_layers(&mut [&mut player, &mut hud])?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Layer {
+    area: Rectangle,
+    pixels: Vec<Rgb565>,
+    // One bit for every pixel, set where something is drawn
+    opaque: Vec<u32>,
+    visible: bool,
+    // What has to be sent to the display again, in display coordinates
+    changed: Option<Rectangle>,
+}
+
+impl Layer {
+    /// A see-through layer over `area` of the display.
+    pub fn new(area: Rectangle) -> Self {
+        let len = (area.size.width * area.size.height) as usize;
+        Self {
+            area,
+            pixels: vec![Rgb565::BLACK; len],
+            opaque: vec![0; len.div_ceil(32)],
+            visible: true,
+            changed: None,
+        }
+    }
+
+    /// The area of the display the layer covers.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Move the layer to have its top left corner at `top_left`, keeping what's drawn on it.
+    pub fn move_to(&mut self, top_left: Point) {
+        if top_left == self.area.top_left {
+            return;
+        }
+        self.changed(self.area);
+        self.area.top_left = top_left;
+        self.changed(self.area);
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show or hide the layer, keeping what's drawn on it.
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible != self.visible {
+            self.visible = visible;
+            self.changed(self.area);
+        }
+    }
+
+    /// Make `area` of the layer see-through again, in layer coordinates.
+    pub fn erase(&mut self, area: &Rectangle) {
+        let area = area.intersection(&self.bounding_box());
+        for point in area.points() {
+            let index = self.index(point);
+            self.opaque[index / 32] &= !(1 << (index % 32));
+        }
+        self.changed(area.translate(self.area.top_left));
+    }
+
+    /// Make the whole layer see-through again.
+    pub fn erase_all(&mut self) {
+        self.opaque.fill(0);
+        self.changed(self.area);
+    }
+
+    /// The colour drawn on the layer at `point` of the display, or `None` where it's
+    /// see-through, hidden or doesn't reach.
+    pub fn pixel(&self, point: Point) -> Option<Rgb565> {
+        let point = point - self.area.top_left;
+        if !self.visible || !self.bounding_box().contains(point) {
+            return None;
+        }
+        let index = self.index(point);
+        (self.opaque[index / 32] & 1 << (index % 32) != 0).then(|| self.pixels[index])
+    }
+
+    // What has changed since this was last called, to send to the display
+    pub(super) fn take_changed(&mut self) -> Option<Rectangle> {
+        self.changed.take()
+    }
+
+    // Count `area` of the display as changed
+    fn changed(&mut self, area: Rectangle) {
+        self.changed = Some(
+            self.changed
+                .map_or(area, |changed| envelope(&changed, &area)),
+        );
+    }
+
+    // Where `point` in layer coordinates is kept, for a point within the layer
+    fn index(&self, point: Point) -> usize {
+        point.y as usize * self.area.size.width as usize + point.x as usize
+    }
+}
+
+impl OriginDimensions for Layer {
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}
+
+impl DrawTarget for Layer {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        let mut drawn: Option<(Point, Point)> = None;
+        for Pixel(point, color) in pixels {
+            if !bounds.contains(point) {
+                continue;
+            }
+            let index = self.index(point);
+            self.pixels[index] = color;
+            self.opaque[index / 32] |= 1 << (index % 32);
+            drawn = Some(match drawn {
+                Some((min, max)) => (min.component_min(point), max.component_max(point)),
+                None => (point, point),
+            });
+        }
+        if let Some((min, max)) = drawn {
+            self.changed(Rectangle::with_corners(min, max).translate(self.area.top_left));
+        }
+        Ok(())
+    }
+}
+
+impl<D> Framebuffer<'_, D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// Like [`present`](Self::present), with `layers` shown over the framebuffer, the first one
+    /// at the bottom. Areas that changed on the layers are sent too, so a layer that moved or
+    /// changed shows without redrawing anything under it.
+    ///
+    /// [`flush_region`](Self::flush_region) doesn't know about layers and sends what's under
+    /// them, so it's best not mixed with layers.
+    pub fn present_layers(&mut self, layers: &mut [&mut Layer]) -> Result<(), D::Error> {
+        for layer in layers.iter_mut() {
+            if let Some(area) = layer.take_changed() {
+                self.mark_dirty(area);
+            }
+        }
+        let layers = &*layers;
+        self.present_with(|point, color| {
+            layers
+                .iter()
+                .rev()
+                .find_map(|layer| layer.pixel(point))
+                .unwrap_or(color)
+        })
+    }
+}
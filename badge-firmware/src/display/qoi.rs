@@ -0,0 +1,179 @@
+//! Decoding images in the QOI format, straight from flash to the display.
+//!
+//! QOI compresses about as well as PNG for pixel art and screenshots, but decodes in a single
+//! pass with 256 bytes of state, so an image never needs to fit in RAM. Convert artwork with any
+//! tool that writes QOI, such as ImageMagick (`magick owl.png owl.qoi`), and draw it with
+//! embedded-graphics' `Image`:
+//!
+//! ```ignore
+//! let owl = Qoi::new(include_bytes!("../../assets/owl.qoi")).unwrap();
+//! Image::new(&owl, Point::new(10, 10)).draw(&mut screen)?;
+//! ```
+//!
+//! Pixels more than half transparent are left out, so images needn't be rectangles.
+
+use embedded_graphics::{
+    image::ImageDrawable, pixelcolor::Rgb565, prelude::*, primitives::Rectangle,
+};
+
+const MAGIC: &[u8; 4] = b"qoif";
+const HEADER_LEN: usize = 14;
+
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const TAG_MASK: u8 = 0xc0;
+
+/// Reasons a QOI image can't be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum QoiError {
+    /// The data doesn't start with a QOI header.
+    NotQoi,
+    /// The image is bigger than the display could ever show.
+    TooLarge,
+}
+
+/// A QOI image, decoded as it's drawn.
+#[derive(Clone, Copy, Debug)]
+pub struct Qoi<'a> {
+    size: Size,
+    data: &'a [u8],
+}
+
+impl<'a> Qoi<'a> {
+    /// Read the header of the QOI image in `data`. Data missing from the end of the image shows
+    /// as pixels left out.
+    pub fn new(data: &'a [u8]) -> Result<Self, QoiError> {
+        let (header, data) = data.split_at_checked(HEADER_LEN).ok_or(QoiError::NotQoi)?;
+        if !header.starts_with(MAGIC) {
+            return Err(QoiError::NotQoi);
+        }
+        let dimension = |at: usize| {
+            let value =
+                u32::from_be_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]]);
+            u16::try_from(value).map_err(|_| QoiError::TooLarge)
+        };
+        let size = Size::new(dimension(4)? as u32, dimension(8)? as u32);
+        Ok(Self { size, data })
+    }
+
+    // Every pixel in order, `None` for the transparent ones and any past the end of the data
+    fn pixels(&self) -> impl Iterator<Item = Option<Rgb565>> + '_ {
+        let mut decoder = Decoder::new(self.data);
+        (0..self.size.width * self.size.height).map(move |_| decoder.next())
+    }
+}
+
+impl OriginDimensions for Qoi<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl ImageDrawable for Qoi<'_> {
+    type Color = Rgb565;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let points = self.bounding_box().points();
+        target.draw_iter(
+            points
+                .zip(self.pixels())
+                .filter_map(|(point, color)| Some(Pixel(point, color?))),
+        )
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // The whole image has to be decoded to get to the area, but only the area is drawn
+        let area = area.intersection(&self.bounding_box());
+        let points = self.bounding_box().points();
+        target.draw_iter(
+            points
+                .zip(self.pixels())
+                .filter(|(point, _)| area.contains(*point))
+                .filter_map(|(point, color)| Some(Pixel(point - area.top_left, color?))),
+        )
+    }
+}
+
+// The QOI decoding state, see https://qoiformat.org/qoi-specification.pdf
+struct Decoder<'a> {
+    data: &'a [u8],
+    pixel: [u8; 4],
+    seen: [[u8; 4]; 64],
+    run: u8,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pixel: [0, 0, 0, 255],
+            seen: [[0; 4]; 64],
+            run: 0,
+        }
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.data.split_first()?;
+        self.data = rest;
+        Some(byte)
+    }
+
+    // The next pixel as RGBA, or `None` once the data runs out
+    fn decode(&mut self) -> Option<[u8; 4]> {
+        if self.run > 0 {
+            self.run -= 1;
+            return Some(self.pixel);
+        }
+
+        let [red, green, blue, alpha] = self.pixel;
+        let op = self.byte()?;
+        match op {
+            OP_RGB => self.pixel = [self.byte()?, self.byte()?, self.byte()?, alpha],
+            OP_RGBA => self.pixel = [self.byte()?, self.byte()?, self.byte()?, self.byte()?],
+            _ => match op & TAG_MASK {
+                OP_INDEX => self.pixel = self.seen[op as usize],
+                OP_DIFF => {
+                    let diff = |shift: u8| ((op >> shift) & 0x03).wrapping_sub(2);
+                    self.pixel = [
+                        red.wrapping_add(diff(4)),
+                        green.wrapping_add(diff(2)),
+                        blue.wrapping_add(diff(0)),
+                        alpha,
+                    ];
+                }
+                OP_LUMA => {
+                    let second = self.byte()?;
+                    let green_diff = (op & 0x3f).wrapping_sub(32);
+                    let red_diff = green_diff.wrapping_add(second >> 4).wrapping_sub(8);
+                    let blue_diff = green_diff.wrapping_add(second & 0x0f).wrapping_sub(8);
+                    self.pixel = [
+                        red.wrapping_add(red_diff),
+                        green.wrapping_add(green_diff),
+                        blue.wrapping_add(blue_diff),
+                        alpha,
+                    ];
+                }
+                // OP_RUN, which stores one less than the length of the run
+                _ => self.run = op & !TAG_MASK,
+            },
+        }
+
+        let [red, green, blue, alpha] = self.pixel.map(|channel| channel as usize);
+        self.seen[(red * 3 + green * 5 + blue * 7 + alpha * 11) % 64] = self.pixel;
+        Some(self.pixel)
+    }
+
+    fn next(&mut self) -> Option<Rgb565> {
+        let [red, green, blue, alpha] = self.decode()?;
+        (alpha >= 128).then(|| Rgb565::new(red >> 3, green >> 2, blue >> 3))
+    }
+}
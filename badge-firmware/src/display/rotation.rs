@@ -0,0 +1,188 @@
+//! Turning what's drawn round in steps of 90 degrees, for holding the badge sideways.
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+/// How far to turn the picture, clockwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum Rotation {
+    /// As the display is mounted, wider than high.
+    #[default]
+    Deg0,
+    /// Portrait, with the top of the picture on the right of the display.
+    Deg90,
+    /// Upside down.
+    Deg180,
+    /// Portrait, with the top of the picture on the left of the display.
+    Deg270,
+}
+
+impl Rotation {
+    /// Every rotation, in the order a picker should show them.
+    pub const ALL: [Rotation; 4] = [
+        Rotation::Deg0,
+        Rotation::Deg90,
+        Rotation::Deg180,
+        Rotation::Deg270,
+    ];
+
+    pub const fn degrees(self) -> u16 {
+        match self {
+            Rotation::Deg0 => 0,
+            Rotation::Deg90 => 90,
+            Rotation::Deg180 => 180,
+            Rotation::Deg270 => 270,
+        }
+    }
+
+    /// Whether the picture is on its side, swapping width and height.
+    pub const fn is_portrait(self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+
+    /// The rotation after turning another 90 degrees clockwise.
+    pub const fn next(self) -> Self {
+        match self {
+            Rotation::Deg0 => Rotation::Deg90,
+            Rotation::Deg90 => Rotation::Deg180,
+            Rotation::Deg180 => Rotation::Deg270,
+            Rotation::Deg270 => Rotation::Deg0,
+        }
+    }
+}
+
+/// A display with everything drawn on it turned by a [`Rotation`], in software.
+///
+/// Coordinates are in the turned picture: with [`Rotation::Deg90`], (0, 0) is still the top left
+/// corner as the user sees it, and the size is 170x320. Anything that uses
+/// [`OriginDimensions::size`] to lay itself out adjusts by itself.
+///
+/// When drawing through a [`Framebuffer`](super::Framebuffer), rotate in front of it, so the
+/// framebuffer keeps the layout of the display:
+///
+/// ```ignore
+/// let mut screen = Rotated::new(Framebuffer::new(display), Rotation::Deg90);
+/// Text::new("PORTRAIT", Point::new(10, 20), style).draw(&mut screen)?;
+/// screen.display().present()?;
+/// ```
+pub struct Rotated<D> {
+    display: D,
+    rotation: Rotation,
+}
+
+impl<D> Rotated<D>
+where
+    D: DrawTarget<Color = Rgb565> + OriginDimensions,
+{
+    pub fn new(display: D, rotation: Rotation) -> Self {
+        Self { display, rotation }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Turn what's drawn from now on. What's already on the display stays as it was.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// The display underneath, in its own coordinates.
+    pub fn display(&mut self) -> &mut D {
+        &mut self.display
+    }
+
+    fn mapping(&self) -> Mapping {
+        let size = self.display.size();
+        Mapping {
+            rotation: self.rotation,
+            right: size.width as i32 - 1,
+            bottom: size.height as i32 - 1,
+        }
+    }
+}
+
+// From the turned picture to the display, without borrowing the display
+#[derive(Clone, Copy)]
+struct Mapping {
+    rotation: Rotation,
+    right: i32,
+    bottom: i32,
+}
+
+impl Mapping {
+    fn point(self, point: Point) -> Point {
+        let (right, bottom) = (self.right, self.bottom);
+        match self.rotation {
+            Rotation::Deg0 => point,
+            Rotation::Deg90 => Point::new(right - point.y, point.x),
+            Rotation::Deg180 => Point::new(right - point.x, bottom - point.y),
+            Rotation::Deg270 => Point::new(point.y, bottom - point.x),
+        }
+    }
+
+    fn area(self, area: &Rectangle) -> Rectangle {
+        match area.bottom_right() {
+            Some(bottom_right) => {
+                Rectangle::with_corners(self.point(area.top_left), self.point(bottom_right))
+            }
+            None => Rectangle::zero(),
+        }
+    }
+}
+
+impl<D> OriginDimensions for Rotated<D>
+where
+    D: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        let size = self.display.size();
+        if self.rotation.is_portrait() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+}
+
+impl<D> DrawTarget for Rotated<D>
+where
+    D: DrawTarget<Color = Rgb565> + OriginDimensions,
+{
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mapping = self.mapping();
+        let pixels = pixels.into_iter();
+        self.display
+            .draw_iter(pixels.map(|Pixel(point, color)| Pixel(mapping.point(point), color)))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        // The pixels arrive row by row in the turned picture, which is only row by row on the
+        // display without a turn
+        if self.rotation == Rotation::Deg0 {
+            return self.display.fill_contiguous(area, colors);
+        }
+        let pixels = area
+            .points()
+            .zip(colors)
+            .map(|(point, color)| Pixel(point, color));
+        self.draw_iter(pixels)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.mapping().area(area);
+        self.display.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.clear(color)
+    }
+}
@@ -0,0 +1,214 @@
+//! Screenshots of a [`Framebuffer`], sent over the serial port as a PNG image.
+//!
+//! defmt only sends text, so the image is sent as base64 in log lines starting with
+//! `screenshot:`. With the output of `cargo run` saved to `log.txt`, this turns them back into a
+//! PNG file:
+//!
+//! ```text
+//! grep -o 'screenshot: [A-Za-z0-9+/=]*' log.txt | cut -d ' ' -f 2 | base64 -d > screenshot.png
+//! ```
+//!
+//! Apps usually take one on [`SCREENSHOT_CHORD`]:
+//!
+//! ```ignore
+//! let mut chords = ChordDetector::new(events, &[SCREENSHOT_CHORD]);
+//! if let ChordEvent::Chord(SCREENSHOT_CHORD) = chords.next().await {
+//!     screen.log_screenshot();
+//! }
+//! ```
+
+use defmt::info;
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+use super::{Framebuffer, HEIGHT, WIDTH};
+use crate::input::{Button, ButtonSet};
+
+/// The buttons to press together to take a screenshot, Select and A.
+pub const SCREENSHOT_CHORD: ButtonSet = ButtonSet::of(&[Button::Select, Button::A]);
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+// A filter type byte and three bytes a pixel for every row
+const RAW_LEN: u32 = HEIGHT * (1 + 3 * WIDTH);
+// The image data is stored uncompressed, in deflate blocks of at most this many bytes
+const MAX_BLOCK: u32 = 0xffff;
+const BLOCKS: u32 = RAW_LEN.div_ceil(MAX_BLOCK);
+// The zlib header, the block headers, the data and the checksum at the end
+const IDAT_LEN: u32 = 2 + 5 * BLOCKS + RAW_LEN + 4;
+// Bytes per line of base64, which makes lines of 76 characters
+const LINE_BYTES: usize = 57;
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<D> Framebuffer<'_, D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// Send what's drawn on the framebuffer, presented or not, to the log as a PNG image.
+    ///
+    /// This takes a few seconds, during which nothing else runs.
+    pub fn log_screenshot(&self) {
+        info!("screenshot of {}x{} pixels follows", WIDTH, HEIGHT);
+        let mut png = Png::new();
+        png.write(&SIGNATURE);
+
+        let mut header = [0; 13];
+        header[0..4].copy_from_slice(&WIDTH.to_be_bytes());
+        header[4..8].copy_from_slice(&HEIGHT.to_be_bytes());
+        // 8 bits per channel of RGB, no interlacing
+        header[8..10].copy_from_slice(&[8, 2]);
+        png.chunk(b"IHDR", &header);
+
+        png.begin_chunk(b"IDAT", IDAT_LEN);
+        // Deflate with a 32 kB window and no compression
+        png.write(&[0x78, 0x01]);
+        let mut data = Stored::new();
+        for y in 0..HEIGHT as i32 {
+            // No filter
+            data.write(&mut png, 0);
+            for x in 0..WIDTH as i32 {
+                let color = self.pixel(Point::new(x, y)).unwrap_or(Rgb565::BLACK);
+                for byte in [
+                    color.r() << 3 | color.r() >> 2,
+                    color.g() << 2 | color.g() >> 4,
+                    color.b() << 3 | color.b() >> 2,
+                ] {
+                    data.write(&mut png, byte);
+                }
+            }
+        }
+        png.write(&data.adler32().to_be_bytes());
+        png.end_chunk();
+
+        png.chunk(b"IEND", &[]);
+        png.finish();
+        info!("screenshot done");
+    }
+}
+
+// PNG chunks with their checksums, sent out as lines of base64 as they fill up
+struct Png {
+    crc: u32,
+    line: [u8; LINE_BYTES],
+    len: usize,
+}
+
+impl Png {
+    fn new() -> Self {
+        Self {
+            crc: 0,
+            line: [0; LINE_BYTES],
+            len: 0,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.crc ^= byte as u32;
+            for _ in 0..8 {
+                self.crc = if self.crc & 1 != 0 {
+                    self.crc >> 1 ^ 0xedb8_8320
+                } else {
+                    self.crc >> 1
+                };
+            }
+
+            self.line[self.len] = byte;
+            self.len += 1;
+            if self.len == LINE_BYTES {
+                self.send_line();
+            }
+        }
+    }
+
+    fn begin_chunk(&mut self, kind: &[u8; 4], len: u32) {
+        self.write(&len.to_be_bytes());
+        // The checksum covers the chunk type and data but not the length
+        self.crc = !0;
+        self.write(kind);
+    }
+
+    fn end_chunk(&mut self) {
+        let crc = !self.crc;
+        self.write(&crc.to_be_bytes());
+    }
+
+    fn chunk(&mut self, kind: &[u8; 4], data: &[u8]) {
+        self.begin_chunk(kind, data.len() as u32);
+        self.write(data);
+        self.end_chunk();
+    }
+
+    fn finish(&mut self) {
+        if self.len > 0 {
+            self.send_line();
+        }
+    }
+
+    fn send_line(&mut self) {
+        let mut text = [0; LINE_BYTES / 3 * 4];
+        let mut text_len = 0;
+        for group in self.line[..self.len].chunks(3) {
+            let bits = group
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, &byte)| bits | (byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                text[text_len + i] = if i <= group.len() {
+                    BASE64[(bits >> (18 - 6 * i) & 0x3f) as usize]
+                } else {
+                    b'='
+                };
+            }
+            text_len += 4;
+        }
+        // Only ASCII goes in, so this can't fail
+        let text = core::str::from_utf8(&text[..text_len]).unwrap_or_default();
+        info!("screenshot: {=str}", text);
+        self.len = 0;
+    }
+}
+
+// The image data as uncompressed deflate blocks, with the Adler-32 checksum zlib needs
+struct Stored {
+    left: u32,
+    block_left: u32,
+    a: u32,
+    b: u32,
+}
+
+impl Stored {
+    fn new() -> Self {
+        Self {
+            left: RAW_LEN,
+            block_left: 0,
+            a: 1,
+            b: 0,
+        }
+    }
+
+    fn write(&mut self, png: &mut Png, byte: u8) {
+        if self.block_left == 0 {
+            let len = self.left.min(MAX_BLOCK);
+            let last = self.left <= MAX_BLOCK;
+            let len_bytes = (len as u16).to_le_bytes();
+            let inverse_bytes = (!len as u16).to_le_bytes();
+            png.write(&[
+                last as u8,
+                len_bytes[0],
+                len_bytes[1],
+                inverse_bytes[0],
+                inverse_bytes[1],
+            ]);
+            self.block_left = len;
+        }
+        png.write(&[byte]);
+        self.block_left -= 1;
+        self.left -= 1;
+
+        self.a = (self.a + byte as u32) % 65521;
+        self.b = (self.b + self.a) % 65521;
+    }
+
+    fn adler32(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
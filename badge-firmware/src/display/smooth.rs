@@ -0,0 +1,93 @@
+//! Anti-aliased lines and circles, drawn on a [`Framebuffer`] so the edges can be blended into
+//! what's already there.
+//!
+//! Pixels along the edge of a shape get some of its colour depending on how much of them it
+//! covers, which smooths out the stair steps of diagonals and curves on a low resolution
+//! display. It's slower than drawing the usual way, so it's best kept for vector art that stays
+//! on screen.
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, Line, Rectangle},
+};
+
+use super::Framebuffer;
+
+impl<D> Framebuffer<'_, D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// Draw `line` anti-aliased, `width` pixels wide with round ends.
+    pub fn draw_smooth_line(&mut self, line: &Line, width: u32, color: Rgb565) {
+        let (start, end) = (line.start, line.end);
+        let half_width = width.max(1) as f32 / 2.0;
+        let delta = end - start;
+        let direction = (delta.x as f32, delta.y as f32);
+        let length_squared = direction.0 * direction.0 + direction.1 * direction.1;
+
+        let margin = Point::new_equal(libm::ceilf(half_width) as i32 + 1);
+        let area = Rectangle::with_corners(
+            start.component_min(end) - margin,
+            start.component_max(end) + margin,
+        );
+        self.cover(area, color, |x, y| {
+            // The distance to the closest point on the line
+            let (dx, dy) = (x - start.x as f32, y - start.y as f32);
+            let along = if length_squared > 0.0 {
+                ((dx * direction.0 + dy * direction.1) / length_squared).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance = libm::hypotf(dx - along * direction.0, dy - along * direction.1);
+            half_width + 0.5 - distance
+        });
+    }
+
+    /// Draw the outline of `circle` anti-aliased, `width` pixels wide inside its diameter.
+    pub fn draw_smooth_circle(&mut self, circle: &Circle, width: u32, color: Rgb565) {
+        let (center, radius) = center_and_radius(circle);
+        let half_width = width.max(1) as f32 / 2.0;
+        let middle = radius - half_width;
+        self.cover(bounds(circle), color, |x, y| {
+            let distance = libm::hypotf(x - center.0, y - center.1);
+            half_width + 0.5 - libm::fabsf(distance - middle)
+        });
+    }
+
+    /// Draw `circle` filled, with an anti-aliased edge.
+    pub fn draw_smooth_filled_circle(&mut self, circle: &Circle, color: Rgb565) {
+        let (center, radius) = center_and_radius(circle);
+        self.cover(bounds(circle), color, |x, y| {
+            radius + 0.5 - libm::hypotf(x - center.0, y - center.1)
+        });
+    }
+
+    // Blend `color` into every pixel of `area` as much as `coverage` says for its centre, from
+    // 0.0 or less for not at all to 1.0 or more for fully
+    fn cover(&mut self, area: Rectangle, color: Rgb565, coverage: impl Fn(f32, f32) -> f32) {
+        let area = area.intersection(&self.bounding_box());
+        for point in area.points() {
+            let amount = coverage(point.x as f32, point.y as f32);
+            if amount > 0.0 {
+                self.blend(point, color, amount);
+            }
+        }
+        self.mark_dirty(area);
+    }
+}
+
+// Where embedded-graphics puts the centre of a circle, and the radius out to its outer edge
+fn center_and_radius(circle: &Circle) -> ((f32, f32), f32) {
+    let offset = (circle.diameter as f32 - 1.0) / 2.0;
+    let center = (
+        circle.top_left.x as f32 + offset,
+        circle.top_left.y as f32 + offset,
+    );
+    (center, circle.diameter as f32 / 2.0)
+}
+
+// The circle's own box and a pixel around it for the blended edge
+fn bounds(circle: &Circle) -> Rectangle {
+    circle.bounding_box().offset(1)
+}
@@ -0,0 +1,189 @@
+//! A splash screen to show while an app starts up, so the badge isn't blank after a reset.
+
+use embassy_time::{Duration, Timer};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, Line, PrimitiveStyle, Rectangle},
+    text::Alignment,
+};
+
+use super::{HEIGHT, Sprite, TextBox, WIDTH};
+use crate::{theme, version::GIT_HASH};
+
+// The library version, for when the app doesn't give one of its own
+const LIBRARY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Room for the logo above the text, and the text below it
+const LOGO_AREA: Rectangle = Rectangle::new(Point::new(0, 10), Size::new(WIDTH, 100));
+const TITLE_AREA: Rectangle = Rectangle::new(Point::new(0, 116), Size::new(WIDTH, 20));
+const VERSION_AREA: Rectangle = Rectangle::new(Point::new(0, 142), Size::new(WIDTH, 20));
+
+/// What a [`Splash`] shows above the title.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Logo<'a> {
+    /// The owl of the tutorial.
+    #[default]
+    Owl,
+    Sprite(Sprite<'a>),
+    None,
+}
+
+/// A logo with the name and version of the firmware below it, in the colours of the current
+/// [`theme`].
+///
+/// Every binary decides for itself whether to show one and what's on it. Without any changes it
+/// shows the tutorial owl and the library version:
+///
+/// ```ignore
+/// let mut display: SafeDisplay<Display<'_>> = SafeDisplay::new(resources.display.into());
+/// Splash::new()
+///     .with_title("Snake")
+///     .with_version(ESP_APP_DESC.version())
+///     .show(&mut display)
+///     .await;
+/// ```
+///
+/// To let the user skip it with any button, `select` it with the next button event.
+#[derive(Clone, Copy, Debug)]
+pub struct Splash<'a> {
+    logo: Logo<'a>,
+    title: &'a str,
+    version: Option<&'a str>,
+    duration: Duration,
+}
+
+impl Default for Splash<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Splash<'a> {
+    pub const fn new() -> Self {
+        Self {
+            logo: Logo::Owl,
+            title: "Disobey 2026",
+            version: None,
+            duration: Duration::from_millis(1500),
+        }
+    }
+
+    pub const fn with_logo(mut self, logo: Logo<'a>) -> Self {
+        self.logo = logo;
+        self
+    }
+
+    /// The line below the logo, "Disobey 2026" unless set.
+    pub const fn with_title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// The version shown below the title, the library version and git commit unless set.
+    pub const fn with_version(mut self, version: &'a str) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// How long [`show`](Self::show) keeps the splash up, 1.5 seconds unless set.
+    pub const fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Draw the splash over the whole of `target`.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        target.fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT)),
+            theme.background,
+        )?;
+
+        match self.logo {
+            Logo::Owl => draw_owl(target, theme.widget)?,
+            Logo::Sprite(sprite) => {
+                let size = sprite.size();
+                let offset = Point::new(size.width as i32 / 2, size.height as i32 / 2);
+                sprite.blit(target, LOGO_AREA.center() - offset)?;
+            }
+            Logo::None => {}
+        }
+
+        TextBox::new(
+            self.title,
+            TITLE_AREA,
+            MonoTextStyle::new(theme.font.mono(), theme.text),
+        )
+        .with_alignment(Alignment::Center)
+        .draw(target)?;
+
+        let default_version;
+        let version = match self.version {
+            Some(version) => version,
+            None => {
+                default_version = alloc::format!("v{LIBRARY_VERSION} ({GIT_HASH})");
+                &default_version
+            }
+        };
+        TextBox::new(
+            version,
+            VERSION_AREA,
+            MonoTextStyle::new(theme.small_font.mono(), theme.text_dim),
+        )
+        .with_alignment(Alignment::Center)
+        .draw(target)?;
+        Ok(())
+    }
+
+    /// Draw the splash on `display` and wait for its duration.
+    pub async fn show<D>(&self, display: &mut D)
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        self.draw(display)
+            .unwrap_or_else(|_| defmt::warn!("Unable to draw the splash screen"));
+        Timer::after(self.duration).await;
+    }
+}
+
+// The owl of the tutorial, drawn in outline in the middle of the logo area
+fn draw_owl<D>(target: &mut D, color: Rgb565) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    const BODY_DIAMETER: u32 = 60;
+    const HEAD_DIAMETER: u32 = 40;
+    const EYE_DIAMETER: u32 = 10;
+
+    let style = PrimitiveStyle::with_stroke(color, 2);
+    let middle = LOGO_AREA.center().x;
+    let top = LOGO_AREA.top_left.y;
+    let head = Point::new(middle, top + HEAD_DIAMETER as i32 / 2);
+
+    Circle::with_center(
+        Point::new(
+            middle,
+            top + HEAD_DIAMETER as i32 + BODY_DIAMETER as i32 / 2,
+        ),
+        BODY_DIAMETER,
+    )
+    .into_styled(style)
+    .draw(target)?;
+    Circle::with_center(head, HEAD_DIAMETER)
+        .into_styled(style)
+        .draw(target)?;
+    for side in [-1, 1] {
+        Circle::with_center(head + Point::new(side * 8, -4), EYE_DIAMETER)
+            .into_styled(style)
+            .draw(target)?;
+        Line::new(head + Point::new(side * 5, 6), head + Point::new(0, 12))
+            .into_styled(style)
+            .draw(target)?;
+    }
+    Ok(())
+}
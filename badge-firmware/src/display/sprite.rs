@@ -0,0 +1,151 @@
+//! Small images drawn in one call, with one colour left out so they needn't be rectangles.
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pixels<'a> {
+    Raw(&'a [Rgb565]),
+    Indexed {
+        indices: &'a [u8],
+        palette: &'a [Rgb565],
+    },
+}
+
+/// An image stored row by row, either as colours or as indexes into a palette of up to 256
+/// colours, which takes half the space.
+///
+/// ```ignore
+/// static SHIP: Sprite = Sprite::indexed(8, &SHIP_PIXELS, &[Rgb565::MAGENTA, Rgb565::WHITE])
+///     .with_key(Rgb565::MAGENTA);
+///
+/// SHIP.blit(&mut screen, Point::new(x, y))?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sprite<'a> {
+    width: u32,
+    pixels: Pixels<'a>,
+    key: Option<Rgb565>,
+}
+
+impl<'a> Sprite<'a> {
+    /// A sprite `width` pixels wide, with as many rows as `pixels` fills.
+    pub const fn raw(width: u32, pixels: &'a [Rgb565]) -> Self {
+        Self {
+            width,
+            pixels: Pixels::Raw(pixels),
+            key: None,
+        }
+    }
+
+    /// A sprite `width` pixels wide, each pixel the colour at its index in `palette`. Indexes
+    /// past the end of the palette are left out like the key colour.
+    pub const fn indexed(width: u32, indices: &'a [u8], palette: &'a [Rgb565]) -> Self {
+        Self {
+            width,
+            pixels: Pixels::Indexed { indices, palette },
+            key: None,
+        }
+    }
+
+    /// Leave out the pixels of colour `key`, so whatever is behind them shows through.
+    pub const fn with_key(mut self, key: Rgb565) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn size(&self) -> Size {
+        let len = match self.pixels {
+            Pixels::Raw(pixels) => pixels.len(),
+            Pixels::Indexed { indices, .. } => indices.len(),
+        };
+        let width = self.width.max(1);
+        Size::new(self.width, len as u32 / width)
+    }
+
+    // The colour of the pixel at `index` in row order, `None` if it's left out
+    fn color(&self, index: usize) -> Option<Rgb565> {
+        let color = match self.pixels {
+            Pixels::Raw(pixels) => pixels.get(index).copied(),
+            Pixels::Indexed { indices, palette } => indices
+                .get(index)
+                .and_then(|&index| palette.get(index as usize).copied()),
+        };
+        color.filter(|&color| Some(color) != self.key)
+    }
+
+    // Whether every pixel is drawn
+    fn is_opaque(&self) -> bool {
+        self.key.is_none()
+            && match self.pixels {
+                Pixels::Raw(_) => true,
+                Pixels::Indexed { palette, .. } => palette.len() > u8::MAX as usize,
+            }
+    }
+
+    // The colour of each pixel in order, `None` for the ones left out
+    fn colors(&self) -> impl Iterator<Item = Option<Rgb565>> + '_ {
+        let pixels = self.size().width as usize * self.size().height as usize;
+        let raw = match self.pixels {
+            Pixels::Raw(pixels) => Some(pixels.iter().copied().map(Some)),
+            Pixels::Indexed { .. } => None,
+        };
+        let indexed = match self.pixels {
+            Pixels::Indexed { indices, palette } => Some(
+                indices
+                    .iter()
+                    .map(move |&index| palette.get(index as usize).copied()),
+            ),
+            Pixels::Raw(_) => None,
+        };
+        raw.into_iter()
+            .flatten()
+            .chain(indexed.into_iter().flatten())
+            .take(pixels)
+            .map(move |color| color.filter(|&color| Some(color) != self.key))
+    }
+
+    /// Draw the sprite with its top left corner at `point`.
+    pub fn blit<D>(&self, display: &mut D, point: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let area = Rectangle::new(point, self.size());
+        if self.is_opaque() {
+            // Every pixel is drawn, so the display can take them as one block
+            return display.fill_contiguous(&area, self.colors().flatten());
+        }
+        let points = area.points();
+        display.draw_iter(
+            points
+                .zip(self.colors())
+                .filter_map(|(point, color)| Some(Pixel(point, color?))),
+        )
+    }
+
+    /// Draw the `part` of the sprite within its own bounds, such as one tile of a sheet of them,
+    /// with the top left corner of the part at `point`.
+    pub fn blit_part<D>(
+        &self,
+        display: &mut D,
+        point: Point,
+        part: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let part = part.intersection(&Rectangle::new(Point::zero(), self.size()));
+        let width = self.size().width as usize;
+        let colors = part
+            .points()
+            .map(move |source| self.color(source.y as usize * width + source.x as usize));
+        let area = Rectangle::new(point, part.size);
+        if self.is_opaque() {
+            return display.fill_contiguous(&area, colors.flatten());
+        }
+        display.draw_iter(
+            area.points()
+                .zip(colors)
+                .filter_map(|(point, color)| Some(Pixel(point, color?))),
+        )
+    }
+}
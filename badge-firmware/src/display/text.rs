@@ -0,0 +1,157 @@
+//! Text wrapped to fit a box, for anything longer than a label.
+
+use embedded_graphics::{
+    geometry::AnchorPoint,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder, renderer::TextRenderer},
+};
+
+/// Text broken into lines at spaces so it fits the width of a box, and aligned within it.
+///
+/// Explicit line breaks are kept, and a word too long for a line on its own is broken wherever
+/// it has to be. Lines that don't fit the height of the box are left out.
+///
+/// ```ignore
+/// let style = MonoTextStyle::new(&FONT_6X10, theme.text);
+/// let used = TextBox::new(message, Rectangle::new(Point::new(10, 10), Size::new(300, 100)), style)
+///     .with_alignment(Alignment::Center)
+///     .draw(&mut screen)?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TextBox<'a, S> {
+    text: &'a str,
+    area: Rectangle,
+    style: S,
+    alignment: Alignment,
+}
+
+impl<'a, S> TextBox<'a, S>
+where
+    S: TextRenderer + Clone,
+{
+    /// `text` in `area`, drawn with `style` and aligned to the left.
+    pub fn new(text: &'a str, area: Rectangle, style: S) -> Self {
+        Self {
+            text,
+            area,
+            style,
+            alignment: Alignment::Left,
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The lines the text is broken into, whether they fit the height of the box or not.
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.text
+            .split('\n')
+            .flat_map(move |paragraph| Lines::new(paragraph, |line| self.fits(line)))
+    }
+
+    /// The height the text takes up in the box, in pixels.
+    pub fn height(&self) -> u32 {
+        let lines = self.lines().count() as u32;
+        (lines * self.style.line_height()).min(self.area.size.height)
+    }
+
+    /// Draw the text and return the height it took up, in pixels.
+    pub fn draw<D>(&self, target: &mut D) -> Result<u32, D::Error>
+    where
+        D: DrawTarget<Color = S::Color>,
+    {
+        let anchor = match self.alignment {
+            Alignment::Left => AnchorPoint::TopLeft,
+            Alignment::Center => AnchorPoint::TopCenter,
+            Alignment::Right => AnchorPoint::TopRight,
+        };
+        let text_style = TextStyleBuilder::new()
+            .alignment(self.alignment)
+            .baseline(Baseline::Top)
+            .build();
+
+        let line_height = self.style.line_height();
+        let mut position = self.area.anchor_point(anchor);
+        let mut height = 0;
+        for line in self.lines() {
+            if height + line_height > self.area.size.height {
+                break;
+            }
+            Text::with_text_style(line, position, self.style.clone(), text_style).draw(target)?;
+            position.y += line_height as i32;
+            height += line_height;
+        }
+        Ok(height)
+    }
+
+    fn fits(&self, line: &str) -> bool {
+        let metrics = self
+            .style
+            .measure_string(line, Point::zero(), Baseline::Top);
+        metrics.next_position.x <= self.area.size.width as i32
+    }
+}
+
+// The lines of a paragraph, each as many words as fit
+struct Lines<'a, F> {
+    rest: &'a str,
+    fits: F,
+    // An empty paragraph is still an empty line
+    started: bool,
+}
+
+impl<'a, F> Lines<'a, F>
+where
+    F: Fn(&str) -> bool,
+{
+    fn new(paragraph: &'a str, fits: F) -> Self {
+        Self {
+            rest: paragraph,
+            fits,
+            started: false,
+        }
+    }
+}
+
+impl<'a, F> Iterator for Lines<'a, F>
+where
+    F: Fn(&str) -> bool,
+{
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            let empty = !self.started;
+            self.started = true;
+            return empty.then_some("");
+        }
+        self.started = true;
+
+        // Add words while they fit, then break after the last one that did
+        let mut end = 0;
+        for (index, _) in self.rest.match_indices(' ').chain([(self.rest.len(), "")]) {
+            if index > end && !(self.fits)(&self.rest[..index]) {
+                break;
+            }
+            end = index;
+        }
+
+        // Not even the first word fits, so break it after as many characters as fit
+        if end == 0 {
+            end = self
+                .rest
+                .char_indices()
+                .map(|(index, character)| index + character.len_utf8())
+                .take_while(|&index| (self.fits)(&self.rest[..index]))
+                .last()
+                .unwrap_or_else(|| self.rest.chars().next().map_or(0, char::len_utf8));
+        }
+
+        let line = self.rest[..end].trim_end();
+        self.rest = self.rest[end..].trim_start_matches(' ');
+        Some(line)
+    }
+}
@@ -0,0 +1,217 @@
+//! Maps of tiles for game levels, drawn through a scrolling viewport.
+
+use alloc::{vec, vec::Vec};
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+use super::Sprite;
+
+/// The tile index for a place on the map without a tile, drawn in the background colour.
+pub const NO_TILE: u8 = u8::MAX;
+
+/// Tiles of one size, side by side in a sprite sheet. Tiles are numbered left to right and then
+/// top to bottom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tileset<'a> {
+    sheet: Sprite<'a>,
+    tile_size: Size,
+}
+
+impl<'a> Tileset<'a> {
+    pub const fn new(sheet: Sprite<'a>, tile_size: Size) -> Self {
+        Self { sheet, tile_size }
+    }
+
+    pub fn tile_size(&self) -> Size {
+        self.tile_size
+    }
+
+    fn columns(&self) -> u32 {
+        self.sheet.size().width / self.tile_size.width.max(1)
+    }
+
+    /// How many tiles there are.
+    pub fn len(&self) -> usize {
+        let rows = self.sheet.size().height / self.tile_size.height.max(1);
+        (self.columns() * rows) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Draw tile `index` with its top left corner at `point`. Returns `false` without drawing
+    /// anything if there's no such tile.
+    pub fn draw_tile<D>(&self, target: &mut D, index: u8, point: Point) -> Result<bool, D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        if index as usize >= self.len() {
+            return Ok(false);
+        }
+        let columns = self.columns();
+        let (column, row) = (index as u32 % columns, index as u32 / columns);
+        let part = Rectangle::new(
+            Point::new(
+                (column * self.tile_size.width) as i32,
+                (row * self.tile_size.height) as i32,
+            ),
+            self.tile_size,
+        );
+        self.sheet.blit_part(target, point, part)?;
+        Ok(true)
+    }
+}
+
+/// A grid of tiles bigger than the display, seen through a viewport that can scroll around it.
+///
+/// Drawing only draws what changed: the whole viewport after scrolling, and otherwise only the
+/// tiles changed with [`set`](Self::set) since the last draw.
+///
+/// ```ignore
+/// let tileset = Tileset::new(assets::TILES, Size::new(16, 16));
+/// let mut map = TileMap::new(tileset, &LEVEL_1, 64, Rectangle::new(Point::zero(), display::SIZE));
+/// loop {
+///     map.scroll_to(Point::new(player_x - 160, 0));
+///     map.draw(&mut screen)?;
+///     screen.present()?;
+///     motion::next_frame().await;
+/// }
+/// ```
+pub struct TileMap<'a> {
+    tileset: Tileset<'a>,
+    tiles: Vec<u8>,
+    columns: u32,
+    area: Rectangle,
+    background: Rgb565,
+    // The map pixel at the top left of the viewport
+    camera: Point,
+    // Where the camera was when last drawn, `None` before the first draw
+    drawn_camera: Option<Point>,
+    // One bit per tile set since the last draw
+    changed: Vec<u32>,
+}
+
+impl<'a> TileMap<'a> {
+    /// A map `columns` tiles wide of `tiles` in row order, shown in `area` of the display.
+    pub fn new(tileset: Tileset<'a>, tiles: &[u8], columns: u32, area: Rectangle) -> Self {
+        Self {
+            tileset,
+            tiles: tiles.to_vec(),
+            columns: columns.max(1),
+            area,
+            background: Rgb565::BLACK,
+            camera: Point::zero(),
+            drawn_camera: None,
+            changed: vec![0; tiles.len().div_ceil(32)],
+        }
+    }
+
+    /// The colour of places without a tile, and of tiles with transparent pixels behind them.
+    /// Black unless set.
+    pub fn with_background(mut self, color: Rgb565) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// The width and height of the whole map in tiles.
+    pub fn size(&self) -> Size {
+        Size::new(self.columns, self.tiles.len() as u32 / self.columns)
+    }
+
+    /// The size of the whole map in pixels.
+    pub fn pixel_size(&self) -> Size {
+        self.size().component_mul(self.tileset.tile_size())
+    }
+
+    /// The tile at column `x` and row `y`, or `None` outside the map.
+    pub fn get(&self, x: u32, y: u32) -> Option<u8> {
+        self.index(x, y).map(|index| self.tiles[index])
+    }
+
+    /// Change the tile at column `x` and row `y`, such as when a coin is picked up. Places
+    /// outside the map are ignored.
+    pub fn set(&mut self, x: u32, y: u32, tile: u8) {
+        let Some(index) = self.index(x, y) else {
+            return;
+        };
+        if self.tiles[index] != tile {
+            self.tiles[index] = tile;
+            self.changed[index / 32] |= 1 << (index % 32);
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        let index = (y * self.columns + x) as usize;
+        (x < self.columns && index < self.tiles.len()).then_some(index)
+    }
+
+    /// The map pixel at the top left of the viewport.
+    pub fn camera(&self) -> Point {
+        self.camera
+    }
+
+    /// Scroll so `camera` is the map pixel at the top left of the viewport, stopping at the edges
+    /// of the map.
+    pub fn scroll_to(&mut self, camera: Point) {
+        let most = self.pixel_size().saturating_sub(self.area.size);
+        self.camera = Point::new(
+            camera.x.clamp(0, most.width as i32),
+            camera.y.clamp(0, most.height as i32),
+        );
+    }
+
+    /// The map pixel at `point` on the display, if it's within the viewport.
+    pub fn map_point(&self, point: Point) -> Option<Point> {
+        self.area
+            .contains(point)
+            .then(|| point - self.area.top_left + self.camera)
+    }
+
+    /// Draw the viewport: all of it after scrolling or on the first draw, otherwise only the
+    /// tiles that changed.
+    pub fn draw<D>(&mut self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let full = self.drawn_camera != Some(self.camera);
+        let tile_size = self.tileset.tile_size();
+        let mut target = target.clipped(&self.area);
+
+        // A map smaller than the viewport leaves some of it without tiles
+        let map = Rectangle::new(self.area.top_left - self.camera, self.pixel_size());
+        if full && map.intersection(&self.area) != self.area {
+            target.fill_solid(&self.area, self.background)?;
+        }
+
+        // The tiles at least partly in view
+        let first = Point::new(
+            self.camera.x / tile_size.width.max(1) as i32,
+            self.camera.y / tile_size.height.max(1) as i32,
+        );
+        let last = (self.camera + self.area.size - Point::new(1, 1)).component_div(Point::new(
+            tile_size.width.max(1) as i32,
+            tile_size.height.max(1) as i32,
+        ));
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                let Some(index) = self.index(x as u32, y as u32) else {
+                    continue;
+                };
+                if !full && self.changed[index / 32] & 1 << (index % 32) == 0 {
+                    continue;
+                }
+                let point = self.area.top_left - self.camera
+                    + Point::new(x, y)
+                        .component_mul(Point::new(tile_size.width as i32, tile_size.height as i32));
+                target.fill_solid(&Rectangle::new(point, tile_size), self.background)?;
+                self.tileset
+                    .draw_tile(&mut target, self.tiles[index], point)?;
+            }
+        }
+
+        self.changed.fill(0);
+        self.drawn_camera = Some(self.camera);
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+//! Text in more than the ISO-8859-1 characters of a single font.
+
+use embedded_graphics::{
+    mono_font::{
+        MonoFont, MonoTextStyle, iso_8859_1, iso_8859_2, iso_8859_3, iso_8859_4, iso_8859_5,
+        iso_8859_7, iso_8859_9, iso_8859_10, iso_8859_13, iso_8859_14, iso_8859_15, iso_8859_16,
+    },
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{
+        Baseline,
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+    },
+};
+
+use crate::theme::Font;
+
+// The same size of font in every character set, most used first. ISO-8859-15 adds the euro sign,
+// the others Latin Extended-A, Cyrillic and Greek between them.
+macro_rules! fonts {
+    ($font:ident) => {
+        [
+            &iso_8859_1::$font,
+            &iso_8859_15::$font,
+            &iso_8859_2::$font,
+            &iso_8859_13::$font,
+            &iso_8859_4::$font,
+            &iso_8859_9::$font,
+            &iso_8859_3::$font,
+            &iso_8859_16::$font,
+            &iso_8859_10::$font,
+            &iso_8859_14::$font,
+            &iso_8859_5::$font,
+            &iso_8859_7::$font,
+        ]
+    };
+}
+
+static SMALL: [&MonoFont<'static>; 12] = fonts!(FONT_6X10);
+static MEDIUM: [&MonoFont<'static>; 12] = fonts!(FONT_8X13);
+static LARGE: [&MonoFont<'static>; 12] = fonts!(FONT_10X20);
+
+// Characters none of the fonts have, drawn as something close that they do have
+const SUBSTITUTES: [(char, char); 8] = [
+    ('\u{2018}', '\''),
+    ('\u{2019}', '\''),
+    ('\u{201c}', '"'),
+    ('\u{201d}', '"'),
+    ('\u{2013}', '-'),
+    ('\u{2014}', '-'),
+    ('\u{2212}', '-'),
+    ('\u{00a0}', ' '),
+];
+
+// Symbols none of the fonts have, as 5x7 bitmaps with the leftmost pixel in bit 4
+const SYMBOLS: [(char, [u8; 7]); 14] = [
+    (
+        '♥',
+        [
+            0b01010, 0b11111, 0b11111, 0b11111, 0b01110, 0b00100, 0b00000,
+        ],
+    ),
+    (
+        '★',
+        [
+            0b00100, 0b00100, 0b11111, 0b01110, 0b01110, 0b11011, 0b10001,
+        ],
+    ),
+    (
+        '✓',
+        [
+            0b00000, 0b00001, 0b00010, 0b10100, 0b01000, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '✗',
+        [
+            0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '←',
+        [
+            0b00100, 0b01000, 0b11111, 0b01000, 0b00100, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '→',
+        [
+            0b00100, 0b00010, 0b11111, 0b00010, 0b00100, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '↑',
+        [
+            0b00100, 0b01110, 0b10101, 0b00100, 0b00100, 0b00100, 0b00000,
+        ],
+    ),
+    (
+        '↓',
+        [
+            0b00100, 0b00100, 0b00100, 0b10101, 0b01110, 0b00100, 0b00000,
+        ],
+    ),
+    (
+        '☺',
+        [
+            0b01110, 0b10001, 0b11011, 0b10001, 0b11011, 0b10101, 0b01110,
+        ],
+    ),
+    (
+        '♪',
+        [
+            0b00110, 0b00101, 0b00100, 0b00100, 0b01100, 0b11100, 0b11000,
+        ],
+    ),
+    (
+        '•',
+        [
+            0b00000, 0b00000, 0b01110, 0b01110, 0b01110, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '…',
+        [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b10101,
+        ],
+    ),
+    (
+        '█',
+        [
+            0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111,
+        ],
+    ),
+    (
+        '⚡',
+        [
+            0b00010, 0b00100, 0b01000, 0b11111, 0b00010, 0b00100, 0b01000,
+        ],
+    ),
+];
+const SYMBOL_SIZE: Size = Size::new(5, 7);
+
+#[derive(Clone, Copy)]
+enum Glyph {
+    /// A character from a font, maybe not the one in the text.
+    Font(&'static MonoFont<'static>, char),
+    Symbol(&'static [u8; 7]),
+}
+
+/// A text style for UTF-8 text, drawing each character from whichever ISO-8859 font has it.
+///
+/// Besides ISO-8859-1 this covers Latin Extended-A, so names like "Łukasz" or "Dvořák" show up
+/// right, and Cyrillic and Greek. Curly quotes and dashes become straight ones, and a few
+/// symbols such as ♥, ★, ✓ and arrows are drawn from built-in bitmaps. Anything else is drawn as
+/// `?`. Works anywhere a `MonoTextStyle` does:
+///
+/// ```ignore
+/// let style = UnicodeTextStyle::new(theme.font, theme.text);
+/// Text::new("Hyvää päivää, Łukasz ♥", Point::new(10, 20), style).draw(&mut screen)?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnicodeTextStyle {
+    font: Font,
+    text_color: Option<Rgb565>,
+    background_color: Option<Rgb565>,
+}
+
+impl UnicodeTextStyle {
+    pub fn new(font: Font, color: Rgb565) -> Self {
+        Self {
+            font,
+            text_color: Some(color),
+            background_color: None,
+        }
+    }
+
+    /// Fill the background of every character with `color`.
+    pub fn with_background(mut self, color: Rgb565) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    fn fonts(&self) -> &'static [&'static MonoFont<'static>; 12] {
+        match self.font {
+            Font::Small => &SMALL,
+            Font::Medium => &MEDIUM,
+            Font::Large => &LARGE,
+        }
+    }
+
+    // The style to draw characters of `font` in
+    fn mono(&self, font: &'static MonoFont<'static>) -> MonoTextStyle<'static, Rgb565> {
+        let mut style = MonoTextStyle::new(font, Rgb565::BLACK);
+        style.text_color = self.text_color;
+        style.background_color = self.background_color;
+        style
+    }
+
+    // How to draw `c`
+    fn glyph(&self, c: char) -> Glyph {
+        let c = SUBSTITUTES
+            .iter()
+            .find(|&&(from, _)| from == c)
+            .map_or(c, |&(_, to)| to);
+        let fonts = self.fonts();
+        if c == '?' {
+            return Glyph::Font(fonts[0], c);
+        }
+        // Fonts give the question mark for characters they don't have
+        let font = fonts.iter().copied().find(|font| {
+            let mapping = font.glyph_mapping;
+            mapping.index(c) != mapping.index('?')
+        });
+        match (font, SYMBOLS.iter().find(|&&(symbol, _)| symbol == c)) {
+            (Some(font), _) => Glyph::Font(font, c),
+            (None, Some((_, rows))) => Glyph::Symbol(rows),
+            (None, None) => Glyph::Font(fonts[0], '?'),
+        }
+    }
+
+    fn draw_symbol<D>(&self, rows: &[u8; 7], cell: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let font = self.fonts()[0];
+        let size = font.character_size;
+        if let Some(background) = self.background_color {
+            target.fill_solid(&Rectangle::new(cell, size), background)?;
+        }
+        let Some(color) = self.text_color else {
+            return Ok(());
+        };
+
+        // As big as fits the character, sitting on the baseline
+        let scale = (size.width / SYMBOL_SIZE.width)
+            .min(size.height / SYMBOL_SIZE.height)
+            .max(1);
+        let left = (size.width as i32 - (SYMBOL_SIZE.width * scale) as i32) / 2;
+        let top = font.baseline as i32 + 1 - (SYMBOL_SIZE.height * scale) as i32;
+        let origin = cell + Point::new(left, top.max(0));
+        for (y, row) in rows.iter().enumerate() {
+            for x in 0..SYMBOL_SIZE.width {
+                if row >> (SYMBOL_SIZE.width - 1 - x) & 1 != 0 {
+                    let pixel = origin + Point::new((x * scale) as i32, (y as u32 * scale) as i32);
+                    target.fill_solid(&Rectangle::new(pixel, Size::new(scale, scale)), color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TextRenderer for UnicodeTextStyle {
+    type Color = Rgb565;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        mut position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let base = self.mono(self.fonts()[0]);
+        let advance = (base.font.character_size.width + base.font.character_spacing) as i32;
+        let mut buffer = [0; 4];
+
+        // Characters in a row from the same font are drawn in one go
+        let mut run: Option<(usize, &'static MonoFont<'static>)> = None;
+        for (index, c) in text.char_indices() {
+            let glyph = self.glyph(c);
+            if let (Glyph::Font(font, drawn), Some((_, run_font))) = (glyph, run)
+                && drawn == c
+                && core::ptr::eq(font, run_font)
+            {
+                continue;
+            }
+            if let Some((start, font)) = run.take() {
+                let run = &text[start..index];
+                position = self
+                    .mono(font)
+                    .draw_string(run, position, baseline, target)?;
+            }
+
+            match glyph {
+                Glyph::Font(font, drawn) if drawn == c => run = Some((index, font)),
+                Glyph::Font(font, drawn) => {
+                    let drawn = drawn.encode_utf8(&mut buffer);
+                    position = self
+                        .mono(font)
+                        .draw_string(drawn, position, baseline, target)?;
+                }
+                Glyph::Symbol(rows) => {
+                    let cell = position - Point::new(0, baseline_offset(&base, baseline));
+                    self.draw_symbol(rows, cell, target)?;
+                    position += Point::new(advance, 0);
+                }
+            }
+        }
+        if let Some((start, font)) = run {
+            position = self
+                .mono(font)
+                .draw_string(&text[start..], position, baseline, target)?;
+        }
+        Ok(position)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.mono(self.fonts()[0])
+            .draw_whitespace(width, position, baseline, target)
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        // Every character is one cell wide, whichever way it's drawn
+        self.mono(self.fonts()[0])
+            .measure_string(text, position, baseline)
+    }
+
+    fn line_height(&self) -> u32 {
+        self.fonts()[0].character_size.height
+    }
+}
+
+impl CharacterStyle for UnicodeTextStyle {
+    type Color = Rgb565;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+}
+
+// How far below the top of a character `baseline` is, as `MonoTextStyle` has it
+fn baseline_offset(style: &MonoTextStyle<'_, Rgb565>, baseline: Baseline) -> i32 {
+    let height = style.font.character_size.height.saturating_sub(1);
+    match baseline {
+        Baseline::Top => 0,
+        Baseline::Bottom => height as i32,
+        Baseline::Middle => (height / 2) as i32,
+        Baseline::Alphabetic => style.font.baseline as i32,
+    }
+}
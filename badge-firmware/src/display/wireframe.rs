@@ -0,0 +1,283 @@
+//! 3D meshes drawn as wireframes, with the maths done in fixed point.
+//!
+//! Each vertex goes through two steps to get on the display: it's rotated with a 3x3 matrix, then
+//! projected so that points further away end up closer to the middle. The rotation is worked out
+//! once per frame with floating point, and everything done per vertex uses integers only: the
+//! matrix holds numbers scaled up by 2^14, and the products are scaled back down with a shift.
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle, Rectangle},
+};
+
+/// The most vertices of a mesh that are drawn, the rest are left out.
+pub const MAX_VERTICES: usize = 64;
+
+// Fixed point numbers have this many bits after the binary point
+const FRACTION_BITS: u32 = 14;
+const ONE: f32 = (1 << FRACTION_BITS) as f32;
+
+/// A shape made of vertices joined by straight edges.
+///
+/// Vertices are `[x, y, z]` with x to the right, y down and z away from the viewer, like the
+/// display with depth added. Around ±100 fits the default [`Transform`] nicely.
+#[derive(Clone, Copy, Debug)]
+pub struct Mesh<'a> {
+    vertices: &'a [[i16; 3]],
+    edges: &'a [[u8; 2]],
+}
+
+impl<'a> Mesh<'a> {
+    /// `edges` join two `vertices` each, by index.
+    pub const fn new(vertices: &'a [[i16; 3]], edges: &'a [[u8; 2]]) -> Self {
+        Self { vertices, edges }
+    }
+
+    pub fn vertices(&self) -> &'a [[i16; 3]] {
+        self.vertices
+    }
+
+    pub fn edges(&self) -> &'a [[u8; 2]] {
+        self.edges
+    }
+
+    /// Draw the edges as seen through `transform`, and return the area drawn on, so it can be
+    /// cleared before the next frame. Edges with an end behind the viewer are left out.
+    pub fn draw<D>(
+        &self,
+        target: &mut D,
+        transform: &Transform,
+        style: PrimitiveStyle<Rgb565>,
+    ) -> Result<Rectangle, D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let mut points = [None; MAX_VERTICES];
+        for (point, &vertex) in points.iter_mut().zip(self.vertices) {
+            *point = transform.project(vertex);
+        }
+
+        let mut corners: Option<(Point, Point)> = None;
+        for &[start, end] in self.edges {
+            let (Some(Some(start)), Some(Some(end))) =
+                (points.get(start as usize), points.get(end as usize))
+            else {
+                continue;
+            };
+            let line = Line::new(*start, *end);
+            line.into_styled(style).draw(target)?;
+
+            let area = line.into_styled(style).bounding_box();
+            let bottom_right = area.bottom_right().unwrap_or(area.top_left);
+            corners = Some(match corners {
+                Some((min, max)) => (
+                    min.component_min(area.top_left),
+                    max.component_max(bottom_right),
+                ),
+                None => (area.top_left, bottom_right),
+            });
+        }
+        Ok(corners.map_or(Rectangle::zero(), |(min, max)| {
+            Rectangle::with_corners(min, max)
+        }))
+    }
+}
+
+/// Where a [`Mesh`] is seen from: how it's turned, how far away it is and where on the display
+/// its origin shows up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transform {
+    // The rotation, in fixed point
+    matrix: [[i32; 3]; 3],
+    center: Point,
+    distance: i32,
+    focal_length: i32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+}
+
+impl Transform {
+    /// Turned `x`, `y` and `z` radians around those axes, in that order, 400 away in the middle
+    /// of the display.
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        let (sin_x, cos_x) = (libm::sinf(x), libm::cosf(x));
+        let (sin_y, cos_y) = (libm::sinf(y), libm::cosf(y));
+        let (sin_z, cos_z) = (libm::sinf(z), libm::cosf(z));
+        // Z * Y * X, so the turn around x happens first
+        let rotation = [
+            [
+                cos_z * cos_y,
+                cos_z * sin_y * sin_x - sin_z * cos_x,
+                cos_z * sin_y * cos_x + sin_z * sin_x,
+            ],
+            [
+                sin_z * cos_y,
+                sin_z * sin_y * sin_x + cos_z * cos_x,
+                sin_z * sin_y * cos_x - cos_z * sin_x,
+            ],
+            [-sin_y, cos_y * sin_x, cos_y * cos_x],
+        ];
+
+        Self {
+            matrix: rotation.map(|row| row.map(|value| libm::roundf(value * ONE) as i32)),
+            center: Rectangle::new(Point::zero(), super::SIZE).center(),
+            distance: 400,
+            focal_length: 250,
+        }
+    }
+
+    /// Show the origin of the mesh at `center` instead of the middle of the display.
+    pub fn with_center(mut self, center: Point) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// How far the mesh is from the viewer. Further is smaller and looks flatter.
+    pub fn with_distance(mut self, distance: i32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// How much the view is zoomed in: things at this distance are drawn at their own size.
+    pub fn with_focal_length(mut self, focal_length: i32) -> Self {
+        self.focal_length = focal_length;
+        self
+    }
+
+    /// Where `vertex` shows up on the display, or `None` if it's behind the viewer.
+    pub fn project(&self, vertex: [i16; 3]) -> Option<Point> {
+        let [x, y, z] = self.matrix.map(|row| {
+            let sum: i32 = row
+                .iter()
+                .zip(vertex)
+                .map(|(&factor, value)| factor * value as i32)
+                .sum();
+            sum >> FRACTION_BITS
+        });
+
+        let depth = z + self.distance;
+        if depth <= 0 {
+            return None;
+        }
+        Some(self.center + Point::new(x * self.focal_length / depth, y * self.focal_length / depth))
+    }
+}
+
+/// A cube 120 on a side.
+pub const CUBE: Mesh<'static> = Mesh::new(
+    &[
+        [-60, -60, -60],
+        [60, -60, -60],
+        [60, 60, -60],
+        [-60, 60, -60],
+        [-60, -60, 60],
+        [60, -60, 60],
+        [60, 60, 60],
+        [-60, 60, 60],
+    ],
+    &[
+        [0, 1],
+        [1, 2],
+        [2, 3],
+        [3, 0],
+        [4, 5],
+        [5, 6],
+        [6, 7],
+        [7, 4],
+        [0, 4],
+        [1, 5],
+        [2, 6],
+        [3, 7],
+    ],
+);
+
+/// A low-poly owl about 200 tall: a boxy head with ears and a face, on a body that widens and
+/// tapers again towards the feet.
+pub const OWL: Mesh<'static> = Mesh::new(
+    &[
+        // The head, top and bottom
+        [-40, -90, -30],
+        [40, -90, -30],
+        [40, -90, 30],
+        [-40, -90, 30],
+        [-40, -30, -30],
+        [40, -30, -30],
+        [40, -30, 30],
+        [-40, -30, 30],
+        // Ear tips
+        [-35, -115, 0],
+        [35, -115, 0],
+        // The widest part of the body, then the bottom
+        [-55, 30, -40],
+        [55, 30, -40],
+        [55, 30, 40],
+        [-55, 30, 40],
+        [-30, 90, -25],
+        [30, 90, -25],
+        [30, 90, 25],
+        [-30, 90, 25],
+        // Eyes, on the front of the head
+        [-26, -65, -30],
+        [-18, -73, -30],
+        [-10, -65, -30],
+        [-18, -57, -30],
+        [10, -65, -30],
+        [18, -73, -30],
+        [26, -65, -30],
+        [18, -57, -30],
+        // The beak, sticking out
+        [-6, -52, -30],
+        [6, -52, -30],
+        [0, -40, -42],
+    ],
+    &[
+        [0, 1],
+        [1, 2],
+        [2, 3],
+        [3, 0],
+        [4, 5],
+        [5, 6],
+        [6, 7],
+        [7, 4],
+        [0, 4],
+        [1, 5],
+        [2, 6],
+        [3, 7],
+        [8, 0],
+        [8, 3],
+        [9, 1],
+        [9, 2],
+        [4, 10],
+        [5, 11],
+        [6, 12],
+        [7, 13],
+        [10, 11],
+        [11, 12],
+        [12, 13],
+        [13, 10],
+        [10, 14],
+        [11, 15],
+        [12, 16],
+        [13, 17],
+        [14, 15],
+        [15, 16],
+        [16, 17],
+        [17, 14],
+        [18, 19],
+        [19, 20],
+        [20, 21],
+        [21, 18],
+        [22, 23],
+        [23, 24],
+        [24, 25],
+        [25, 22],
+        [26, 27],
+        [27, 28],
+        [28, 26],
+    ],
+);
@@ -0,0 +1,42 @@
+//! ESP-NOW: connectionless, low-latency messages between nearby badges.
+//!
+//! ESP-NOW runs on top of the Wi-Fi radio but needs no access point: every badge on the same
+//! channel receives frames sent to [`BROADCAST_ADDRESS`].
+
+use esp_hal::peripherals::WIFI;
+use esp_radio::{
+    Controller,
+    wifi::{WifiController, WifiMode},
+};
+use static_cell::StaticCell;
+
+pub use esp_radio::esp_now::{BROADCAST_ADDRESS, EspNow, ReceivedData};
+
+/// The Wi-Fi channel every badge uses for ESP-NOW.
+pub const CHANNEL: u8 = 11;
+
+static RADIO: StaticCell<Controller<'static>> = StaticCell::new();
+static WIFI_CONTROLLER: StaticCell<WifiController<'static>> = StaticCell::new();
+
+/// Bring up the radio in station mode and return the ESP-NOW interface.
+///
+/// The scheduler must already be running (`esp_rtos::start`) and the heap set up, as the radio
+/// driver allocates. Can only be called once.
+pub fn init(wifi: WIFI<'static>) -> EspNow<'static> {
+    let radio = RADIO.init(esp_radio::init().expect("Unable to initialise the radio"));
+    let (controller, interfaces) =
+        esp_radio::wifi::new(radio, wifi, Default::default()).expect("Unable to initialise Wi-Fi");
+
+    // The controller has to stay alive for as long as we want to use the radio
+    let controller = WIFI_CONTROLLER.init(controller);
+    controller
+        .set_mode(WifiMode::Sta)
+        .expect("Unable to set Wi-Fi mode");
+    controller.start().expect("Unable to start Wi-Fi");
+
+    let esp_now = interfaces.esp_now;
+    esp_now
+        .set_channel(CHANNEL)
+        .expect("Unable to set ESP-NOW channel");
+    esp_now
+}
@@ -0,0 +1,619 @@
+//! Button input with press, release and hold events.
+//!
+//! `Buttons::debounce_press` only reports presses. [`ButtonEvents`] watches all the buttons at
+//! once and also reports releases, and keeps reporting [`ButtonEventKind::Held`] while a button
+//! stays down, which is what hold-to-repeat and drag behaviours need:
+//!
+//! ```ignore
+//! let mut events = ButtonEvents::new(buttons);
+//! loop {
+//!     match events.next().await {
+//!         ButtonEvent { button: Button::A, kind: ButtonEventKind::Pressed, .. } => ...,
+//!         ButtonEvent { button: Button::A, kind: ButtonEventKind::Held(time), .. } => ...,
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+//! Most apps don't drive [`ButtonEvents`] themselves but [`spawn`] the button task, which
+//! publishes every event to [`BUTTON_CHANNEL`] for any number of tasks to subscribe to. The
+//! submodules build on these events: clicks, chords, sequences and D-pad gestures are spotted
+//! by the detectors re-exported here, and the rest, from focus and remapping to recording and
+//! sleep, is described in each submodule. Game loops that poll once a frame can ask which
+//! buttons are down instead, and [`ButtonsExt`] adds a few helpers to the BSP's `Buttons`.
+
+use disobey2026badge::Buttons;
+use embassy_futures::select::{Either, select, select_array};
+use embassy_time::{Duration, Instant, Timer, with_timeout};
+use esp_hal::gpio::{Input, InputConfig, Pull};
+
+mod channel;
+mod chord;
+mod click;
+pub mod feedback;
+mod focus;
+mod gesture;
+pub mod idle;
+pub mod record;
+pub mod remap;
+mod sequence;
+mod wake;
+
+pub use channel::{
+    BUTTON_CHANNEL, ButtonPublisher, ButtonSubscriber, ButtonSubscriberFactory, CHANNEL_CAPACITY,
+    MAX_SUBSCRIBERS, button_state, spawn,
+};
+pub use chord::{ChordDetector, ChordEvent, DEFAULT_CHORD_WINDOW};
+pub use click::{Click, ClickDetector, DEFAULT_DOUBLE_CLICK_WINDOW};
+pub use focus::{Focus, FocusSubscriber};
+pub use gesture::{DEFAULT_GESTURE_WINDOW, Gesture, GestureDetector};
+pub use idle::{IDLE_CHANNEL, IdleEvent};
+pub use sequence::{KONAMI_CODE, SequenceMatcher};
+pub use wake::sleep_until_press;
+
+/// How long a pin has to stay at one level after changing before we believe it.
+pub const DEBOUNCE_TIME: Duration = Duration::from_millis(20);
+/// How often [`ButtonEventKind::Held`] repeats while a button stays down.
+pub const HOLD_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The buttons on the badge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    Stick,
+    A,
+    B,
+    Start,
+    Select,
+}
+
+impl Button {
+    /// Every button, in the same order as their discriminants.
+    pub const ALL: [Button; 9] = [
+        Button::Up,
+        Button::Down,
+        Button::Left,
+        Button::Right,
+        Button::Stick,
+        Button::A,
+        Button::B,
+        Button::Start,
+        Button::Select,
+    ];
+
+    /// A name to show on the display.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Button::Up => "Up",
+            Button::Down => "Down",
+            Button::Left => "Left",
+            Button::Right => "Right",
+            Button::Stick => "Stick",
+            Button::A => "A",
+            Button::B => "B",
+            Button::Start => "Start",
+            Button::Select => "Select",
+        }
+    }
+}
+
+// The D-pad directions in clockwise order
+const DPAD_CLOCKWISE: [Button; 4] = [Button::Up, Button::Right, Button::Down, Button::Left];
+
+/// A set of buttons, such as a chord.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct ButtonSet(u16);
+
+impl ButtonSet {
+    pub const EMPTY: Self = Self(0);
+    pub const ALL: Self = Self::of(&Button::ALL);
+
+    /// A set of the given buttons, e.g. `ButtonSet::of(&[Button::Start, Button::Select])`.
+    pub const fn of(buttons: &[Button]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < buttons.len() {
+            set = set.with(buttons[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// This set plus `button`.
+    pub const fn with(self, button: Button) -> Self {
+        Self(self.0 | 1 << button as u16)
+    }
+
+    pub const fn contains(self, button: Button) -> bool {
+        self.0 & 1 << button as u16 != 0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Whether every button in `other` is also in this set.
+    pub const fn is_superset(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, button: Button) {
+        *self = self.with(button);
+    }
+
+    pub fn remove(&mut self, button: Button) {
+        self.0 &= !(1 << button as u16);
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Button> {
+        Button::ALL
+            .into_iter()
+            .filter(move |&button| self.contains(button))
+    }
+}
+
+/// What happened to a button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ButtonEventKind {
+    Pressed,
+    Released,
+    /// Still down, for this long so far. Repeats every [`HOLD_INTERVAL`].
+    Held(Duration),
+    /// Still down, repeating the press. Only sent for buttons with auto-repeat, see
+    /// [`ButtonEvents::with_auto_repeat`].
+    Repeated,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct ButtonEvent {
+    pub button: Button,
+    pub kind: ButtonEventKind,
+    /// When it happened, so timing doesn't depend on how quickly the event was delivered.
+    pub at: Instant,
+}
+
+fn pin(buttons: &mut Buttons, button: Button) -> &mut Input<'static> {
+    match button {
+        Button::Up => &mut buttons.up,
+        Button::Down => &mut buttons.down,
+        Button::Left => &mut buttons.left,
+        Button::Right => &mut buttons.right,
+        Button::Stick => &mut buttons.stick,
+        Button::A => &mut buttons.a,
+        Button::B => &mut buttons.b,
+        Button::Start => &mut buttons.start,
+        Button::Select => &mut buttons.select,
+    }
+}
+
+// The pins in `Button::ALL` order
+fn pins(buttons: &mut Buttons) -> [&mut Input<'static>; 9] {
+    [
+        &mut buttons.up,
+        &mut buttons.down,
+        &mut buttons.left,
+        &mut buttons.right,
+        &mut buttons.stick,
+        &mut buttons.a,
+        &mut buttons.b,
+        &mut buttons.start,
+        &mut buttons.select,
+    ]
+}
+
+// Which buttons are down going by the pins alone, as mapped by `remap`
+fn read_pins(buttons: &Buttons, active_high: ButtonSet) -> ButtonSet {
+    let pins = [
+        &buttons.up,
+        &buttons.down,
+        &buttons.left,
+        &buttons.right,
+        &buttons.stick,
+        &buttons.a,
+        &buttons.b,
+        &buttons.start,
+        &buttons.select,
+    ];
+    Button::ALL
+        .into_iter()
+        .zip(pins)
+        .filter(|&(button, pin)| {
+            let edge = if active_high.contains(button) {
+                Edge::Rising
+            } else {
+                Edge::Falling
+            };
+            edge.is_at(pin)
+        })
+        .map(|(button, _)| remap::get(button))
+        .fold(ButtonSet::EMPTY, ButtonSet::with)
+}
+
+/// Which way a pin goes when its button is pressed.
+///
+/// The badge's own buttons pull their pins low, so a press is a falling edge. Some add-on
+/// hardware is active-high instead, pressed on the rising edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum Edge {
+    #[default]
+    Falling,
+    Rising,
+}
+
+impl Edge {
+    // Whether `pin` is at the level this edge goes to
+    fn is_at(self, pin: &Input<'static>) -> bool {
+        match self {
+            Edge::Falling => pin.is_low(),
+            Edge::Rising => pin.is_high(),
+        }
+    }
+}
+
+/// How [`ButtonEvents`] decides a pin has stopped bouncing.
+///
+/// After a change, the pin is sampled every `interval` until it reads the same `samples` times
+/// in a row. Noisy add-on buttons may want more samples, fast games fewer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Debounce {
+    pub interval: Duration,
+    pub samples: u8,
+}
+
+impl Debounce {
+    /// Four samples 5 ms apart, adding up to [`DEBOUNCE_TIME`].
+    pub const DEFAULT: Self = Self {
+        interval: Duration::from_millis(5),
+        samples: 4,
+    };
+
+    /// Believe the first reading, for the lowest latency with well-behaved buttons.
+    pub const NONE: Self = Self {
+        interval: Duration::from_ticks(0),
+        samples: 0,
+    };
+
+    // Wait until `pin` reads the same level enough times in a row
+    async fn settle(&self, pin: &mut Input<'static>) {
+        let mut level = pin.level();
+        let mut stable = 0;
+        while stable < self.samples {
+            Timer::after(self.interval).await;
+            let sample = pin.level();
+            if sample == level {
+                stable += 1;
+            } else {
+                level = sample;
+                stable = 0;
+            }
+        }
+    }
+}
+
+impl Default for Debounce {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Debounced press, release and hold events from all the buttons.
+///
+/// Between events this sleeps on the GPIO interrupts, and when a pin changes only that pin is
+/// debounced. Events report the button as mapped by [`remap`].
+pub struct ButtonEvents<'a> {
+    buttons: &'a mut Buttons,
+    // When each button went down, or `None` while it's up
+    pressed_at: [Option<Instant>; 9],
+    // When each button that's down next reports `Held`
+    next_hold: [Instant; 9],
+    // When each button that's down next reports `Repeated`, if it auto-repeats
+    next_repeat: [Instant; 9],
+    repeat_buttons: ButtonSet,
+    repeat_delay: Duration,
+    repeat_interval: Duration,
+    debounce: Debounce,
+    // Buttons pressed on the rising edge, by physical button
+    active_high: ButtonSet,
+}
+
+impl<'a> ButtonEvents<'a> {
+    /// Start watching the buttons. Any already held down are reported as pressed right away.
+    pub fn new(buttons: &'a mut Buttons) -> Self {
+        Self {
+            buttons,
+            pressed_at: [None; 9],
+            next_hold: [Instant::from_ticks(0); 9],
+            next_repeat: [Instant::from_ticks(0); 9],
+            repeat_buttons: ButtonSet::EMPTY,
+            repeat_delay: Duration::from_ticks(0),
+            repeat_interval: Duration::from_ticks(0),
+            debounce: Debounce::DEFAULT,
+            active_high: ButtonSet::EMPTY,
+        }
+    }
+
+    /// How to debounce the pins, [`Debounce::DEFAULT`] unless set.
+    pub fn with_debounce(mut self, debounce: Debounce) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Treat `buttons` as active-high, pressed on [`Edge::Rising`], for add-on hardware wired
+    /// that way. These are the physical buttons, before any [`remap`].
+    pub fn with_active_high(mut self, buttons: ButtonSet) -> Self {
+        self.active_high = buttons;
+        self
+    }
+
+    /// Repeat presses of `buttons` while they're held down, like keys on a keyboard: first
+    /// after `delay`, then every `interval`. Off by default.
+    ///
+    /// For example, auto-repeat on the D-pad lets a held direction keep moving something.
+    pub fn with_auto_repeat(
+        mut self,
+        buttons: ButtonSet,
+        delay: Duration,
+        interval: Duration,
+    ) -> Self {
+        self.repeat_buttons = buttons;
+        self.repeat_delay = delay;
+        self.repeat_interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Which buttons are down, going by the events reported so far.
+    pub fn state(&self) -> ButtonSet {
+        Button::ALL
+            .into_iter()
+            .filter(|&button| self.pressed_at[button as usize].is_some())
+            .map(remap::get)
+            .fold(ButtonSet::EMPTY, ButtonSet::with)
+    }
+
+    /// Which buttons are down according to the pins right now, before debouncing. Mostly for
+    /// diagnostics, next to [`state`](Self::state).
+    pub fn raw_state(&self) -> ButtonSet {
+        read_pins(self.buttons, self.active_high)
+    }
+
+    /// Wait for the next event.
+    ///
+    /// This is safe to cancel, e.g. with a timeout: a change that was still being debounced is
+    /// picked up again by the next call.
+    pub async fn next(&mut self) -> ButtonEvent {
+        loop {
+            if let Some(event) = self.poll_held(Instant::now()) {
+                return event;
+            }
+
+            // Sleep until a pin changes or a held button is due an event. The GPIO interrupts
+            // wake us up, and waiting for a level rather than an edge means a change that
+            // sneaks in before we start waiting isn't missed.
+            let next_deadline = Button::ALL
+                .into_iter()
+                .filter(|&button| self.pressed_at[button as usize].is_some())
+                .map(|button| self.next_deadline(button))
+                .min();
+            let pressed_at = self.pressed_at;
+            let active_high = self.active_high;
+            let mut index = 0;
+            let changes = select_array(pins(self.buttons).map(|pin| {
+                let pressed = pressed_at[index].is_some();
+                let high_when_released = !active_high.contains(Button::ALL[index]);
+                index += 1;
+                async move {
+                    if pressed == high_when_released {
+                        pin.wait_for_high().await;
+                    } else {
+                        pin.wait_for_low().await;
+                    }
+                }
+            }));
+            let changed = match next_deadline {
+                Some(at) => match select(changes, Timer::at(at)).await {
+                    Either::First(((), index)) => Some(Button::ALL[index]),
+                    Either::Second(()) => None,
+                },
+                None => Some(Button::ALL[changes.await.1]),
+            };
+
+            // Only the pin that changed needs debouncing, any others get their turn next time
+            if let Some(button) = changed {
+                self.debounce.settle(pin(self.buttons, button)).await;
+                if let Some(event) = self.update(button, Instant::now()) {
+                    return event;
+                }
+            }
+        }
+    }
+
+    /// Wait for the next press of any button, as for [`next`](Self::next). Auto-repeats count
+    /// as presses, releases and holds are skipped.
+    pub async fn next_press(&mut self) -> Button {
+        loop {
+            let event = self.next().await;
+            if matches!(
+                event.kind,
+                ButtonEventKind::Pressed | ButtonEventKind::Repeated
+            ) {
+                return event.button;
+            }
+        }
+    }
+
+    // When a button that's down next has something to report
+    fn next_deadline(&self, button: Button) -> Instant {
+        let i = button as usize;
+        if self.repeat_buttons.contains(remap::get(button)) {
+            self.next_hold[i].min(self.next_repeat[i])
+        } else {
+            self.next_hold[i]
+        }
+    }
+
+    // Report a press or release if the level of `button` differs from what we've reported
+    fn update(&mut self, button: Button, now: Instant) -> Option<ButtonEvent> {
+        let i = button as usize;
+        let edge = if self.active_high.contains(button) {
+            Edge::Rising
+        } else {
+            Edge::Falling
+        };
+        let kind = match (self.pressed_at[i], edge.is_at(pin(self.buttons, button))) {
+            (None, true) => {
+                self.pressed_at[i] = Some(now);
+                self.next_hold[i] = now + HOLD_INTERVAL;
+                self.next_repeat[i] = now + self.repeat_delay;
+                ButtonEventKind::Pressed
+            }
+            (Some(_), false) => {
+                self.pressed_at[i] = None;
+                ButtonEventKind::Released
+            }
+            _ => return None,
+        };
+        Some(ButtonEvent {
+            button: remap::get(button),
+            kind,
+            at: now,
+        })
+    }
+
+    // Report the first button that's down and due a `Held` or `Repeated` event
+    fn poll_held(&mut self, now: Instant) -> Option<ButtonEvent> {
+        for button in Button::ALL {
+            let i = button as usize;
+            let Some(at) = self.pressed_at[i] else {
+                continue;
+            };
+            let button = remap::get(button);
+            let kind = if self.repeat_buttons.contains(button) && now >= self.next_repeat[i] {
+                self.next_repeat[i] = now + self.repeat_interval;
+                ButtonEventKind::Repeated
+            } else if now >= self.next_hold[i] {
+                self.next_hold[i] = now + HOLD_INTERVAL;
+                ButtonEventKind::Held(now - at)
+            } else {
+                continue;
+            };
+            return Some(ButtonEvent {
+                button,
+                kind,
+                at: now,
+            });
+        }
+        None
+    }
+}
+
+/// How long a press lasted, see [`ButtonsExt::debounce_long_press`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Press {
+    /// Released before the threshold.
+    Short,
+    /// Still held at the threshold.
+    Long,
+}
+
+/// Extra button helpers for the BSP's [`Buttons`].
+#[allow(
+    async_fn_in_trait,
+    reason = "buttons are used from a single executor, Send bounds are not needed"
+)]
+pub trait ButtonsExt {
+    /// Wait for a press on `pin` and tell whether it was a tap or a hold.
+    ///
+    /// A hold resolves as soon as the button has been down for `threshold`, without waiting for
+    /// the release, so the app can react while the button is still held.
+    async fn debounce_long_press(pin: &mut Input<'static>, threshold: Duration) -> Press;
+
+    /// Wait for any button to be pressed and tell which one it was, as mapped by [`remap`].
+    /// Buttons already down when this is called count once they're pressed again.
+    ///
+    /// This is [`ButtonEvents::next_press`] on the default [`Debounce`], so it needs no more
+    /// stack than the events do. For other debouncing or active-high buttons, keep a
+    /// [`ButtonEvents`] configured for them and wait on that instead.
+    async fn next_press(&mut self) -> Button;
+
+    /// Like `Buttons::debounce_press`, but resolving on the chosen edge of `pin`: only once it
+    /// has stayed at the new level for [`DEBOUNCE_TIME`].
+    ///
+    /// [`Edge::Falling`] waits for a press of one of the badge's own buttons, or the release of
+    /// an active-high one. [`Edge::Rising`] is the other way round.
+    async fn debounce_edge(pin: &mut Input<'static>, edge: Edge);
+
+    /// Which buttons are down right now, as mapped by [`remap`].
+    ///
+    /// This reads the pins as they are without debouncing, which is fine for polling once a
+    /// frame: a bounce only shows up for a frame at most.
+    fn state(&self) -> ButtonSet;
+
+    /// Change the pull resistor on the pins of `buttons`, which are the physical buttons
+    /// before any [`remap`].
+    ///
+    /// Splitting the resources turns on the internal pull-ups for all the buttons. A board
+    /// reworked with external pull resistors can turn them off with [`Pull::None`], and
+    /// active-high add-on buttons usually want [`Pull::Down`] together with
+    /// [`ButtonEvents::with_active_high`].
+    fn set_pull(&mut self, buttons: ButtonSet, pull: Pull);
+}
+
+impl ButtonsExt for Buttons {
+    async fn debounce_long_press(pin: &mut Input<'static>, threshold: Duration) -> Press {
+        Buttons::debounce_press(pin).await;
+        let release = async {
+            pin.wait_for_high().await;
+            Timer::after(DEBOUNCE_TIME).await;
+        };
+        match with_timeout(threshold, release).await {
+            Ok(()) => Press::Short,
+            Err(_) => Press::Long,
+        }
+    }
+
+    async fn next_press(&mut self) -> Button {
+        let mut events = ButtonEvents::new(self);
+        // New events report the buttons already down as pressed, but those presses came
+        // before this was called
+        let mut held = events.raw_state();
+        loop {
+            let event = events.next().await;
+            match event.kind {
+                ButtonEventKind::Pressed if !held.contains(event.button) => return event.button,
+                ButtonEventKind::Released => held.remove(event.button),
+                _ => {}
+            }
+        }
+    }
+
+    async fn debounce_edge(pin: &mut Input<'static>, edge: Edge) {
+        loop {
+            match edge {
+                Edge::Falling => pin.wait_for_falling_edge().await,
+                Edge::Rising => pin.wait_for_rising_edge().await,
+            }
+            Timer::after(DEBOUNCE_TIME).await;
+            if edge.is_at(pin) {
+                return;
+            }
+        }
+    }
+
+    fn state(&self) -> ButtonSet {
+        read_pins(self, ButtonSet::EMPTY)
+    }
+
+    fn set_pull(&mut self, buttons: ButtonSet, pull: Pull) {
+        let config = InputConfig::default().with_pull(pull);
+        for button in buttons.iter() {
+            pin(self, button).apply_config(&config);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! A shared channel of button events, fed by a task the library provides.
+//!
+//! The same task also publishes to [`IDLE_CHANNEL`](super::IDLE_CHANNEL), see
+//! [`idle`](super::idle).
+
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use disobey2026badge::Buttons;
+use embassy_executor::{Spawner, task};
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex,
+    pubsub::{PubSubChannel, Publisher, Subscriber},
+};
+use embassy_time::{Duration, with_timeout};
+
+use super::{
+    ButtonEvent, ButtonEvents, ButtonSet, FocusSubscriber, feedback,
+    idle::{IDLE_CHANNEL, IdleEvent, idle_timeout},
+};
+
+/// How many events can wait for the slowest subscriber before older ones are dropped.
+pub const CHANNEL_CAPACITY: usize = 8;
+/// How many tasks can subscribe to [`BUTTON_CHANNEL`] at once.
+pub const MAX_SUBSCRIBERS: usize = 4;
+
+/// Every button event, published by the task started with [`spawn`].
+pub static BUTTON_CHANNEL: PubSubChannel<
+    CriticalSectionRawMutex,
+    ButtonEvent,
+    CHANNEL_CAPACITY,
+    MAX_SUBSCRIBERS,
+    1,
+> = PubSubChannel::new();
+
+// The buttons down as of the latest published event
+static STATE: AtomicU16 = AtomicU16::new(0);
+
+pub type ButtonSubscriber =
+    Subscriber<'static, CriticalSectionRawMutex, ButtonEvent, CHANNEL_CAPACITY, MAX_SUBSCRIBERS, 1>;
+pub type ButtonPublisher =
+    Publisher<'static, CriticalSectionRawMutex, ButtonEvent, CHANNEL_CAPACITY, MAX_SUBSCRIBERS, 1>;
+
+/// Start the button task, publishing the events from `buttons` to [`BUTTON_CHANNEL`].
+///
+/// Call this once, then get a subscriber for each task that wants button events:
+///
+/// ```ignore
+/// let buttons = mk_static!(Buttons, resources.buttons.into());
+/// let subscribers = input::spawn(&spawner, buttons);
+/// spawner.must_spawn(led_task(subscribers.subscriber()));
+/// let mut subscriber = subscribers.subscriber();
+/// ```
+pub fn spawn(spawner: &Spawner, buttons: &'static mut Buttons) -> ButtonSubscriberFactory {
+    spawner.must_spawn(button_task(BUTTON_CHANNEL.publisher().unwrap(), buttons));
+    ButtonSubscriberFactory { _private: () }
+}
+
+/// Hands out subscribers to [`BUTTON_CHANNEL`] once the button task is running, see [`spawn`].
+#[derive(Clone, Copy, Debug)]
+pub struct ButtonSubscriberFactory {
+    _private: (),
+}
+
+impl ButtonSubscriberFactory {
+    /// A new subscriber, getting every event from now on.
+    ///
+    /// Panics if all [`MAX_SUBSCRIBERS`] are taken.
+    pub fn subscriber(&self) -> ButtonSubscriber {
+        BUTTON_CHANNEL.subscriber().unwrap()
+    }
+
+    /// A new [`FocusSubscriber`], for a task that may claim focus or lose it to others.
+    ///
+    /// Panics if all [`MAX_SUBSCRIBERS`] are taken.
+    pub fn focus_subscriber(&self) -> FocusSubscriber {
+        FocusSubscriber::new(self.subscriber())
+    }
+}
+
+/// Which buttons are down, as of the latest event the button task published.
+///
+/// For game loops that poll once a frame instead of following the events.
+pub fn button_state() -> ButtonSet {
+    ButtonSet(STATE.load(Ordering::Relaxed))
+}
+
+#[task]
+async fn button_task(publisher: ButtonPublisher, buttons: &'static mut Buttons) {
+    let mut events = ButtonEvents::new(buttons);
+    let idle_publisher = IDLE_CHANNEL.immediate_publisher();
+    loop {
+        // Held buttons keep sending events, so this only times out with all the buttons up
+        let mut idle_for = Duration::from_ticks(0);
+        let event = loop {
+            let timeout = idle_timeout();
+            match with_timeout(timeout, events.next()).await {
+                Ok(event) => break event,
+                Err(_) => {
+                    idle_for += timeout;
+                    idle_publisher.publish_immediate(IdleEvent::Idle(idle_for));
+                }
+            }
+        };
+        if idle_for > Duration::from_ticks(0) {
+            idle_publisher.publish_immediate(IdleEvent::Wake);
+        }
+        STATE.store(events.state().0, Ordering::Relaxed);
+        feedback::run_hooks(event);
+        publisher.publish(event).await;
+    }
+}
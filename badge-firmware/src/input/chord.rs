@@ -0,0 +1,140 @@
+//! Button combinations pressed together, such as Start+Select.
+
+use embassy_time::{Duration, Instant, with_deadline};
+
+use super::{Button, ButtonEvent, ButtonEventKind, ButtonEvents, ButtonSet};
+
+/// How close together the presses of a chord have to be, unless configured.
+pub const DEFAULT_CHORD_WINDOW: Duration = Duration::from_millis(80);
+
+/// Either a regular button event or a completed chord.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ChordEvent {
+    Button(ButtonEvent),
+    Chord(ButtonSet),
+}
+
+/// Recognises chords among the button events.
+///
+/// A press of a button that is part of a chord is held back for a short window. If the rest of
+/// the chord follows in time, only [`ChordEvent::Chord`] is reported and the individual buttons
+/// stay quiet until released. Otherwise the held-back presses are passed on late.
+pub struct ChordDetector<'a> {
+    events: ButtonEvents<'a>,
+    chords: &'a [ButtonSet],
+    window: Duration,
+    // Presses held back while waiting for a chord to complete, oldest first
+    held: [(Button, Instant); 9],
+    held_len: usize,
+    // Whether the held presses are being passed on, one per call to `next`
+    flushing: bool,
+    // An event that arrived while presses were held back, passed on after them
+    deferred: Option<ButtonEvent>,
+    // Buttons used up by a chord, ignored until released
+    suppressed: ButtonSet,
+}
+
+impl<'a> ChordDetector<'a> {
+    pub fn new(events: ButtonEvents<'a>, chords: &'a [ButtonSet]) -> Self {
+        Self {
+            events,
+            chords,
+            window: DEFAULT_CHORD_WINDOW,
+            held: [(Button::Up, Instant::from_ticks(0)); 9],
+            held_len: 0,
+            flushing: false,
+            deferred: None,
+            suppressed: ButtonSet::EMPTY,
+        }
+    }
+
+    /// The longest time between the first and the last press of a chord.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Wait until `chord` is pressed, ignoring everything else.
+    pub async fn wait_for(&mut self, chord: ButtonSet) {
+        while self.next().await != ChordEvent::Chord(chord) {}
+    }
+
+    /// Wait for the next button event or chord.
+    pub async fn next(&mut self) -> ChordEvent {
+        loop {
+            if self.flushing {
+                if let Some(event) = self.pop_held() {
+                    return ChordEvent::Button(event);
+                }
+                self.flushing = false;
+                if let Some(event) = self.deferred.take() {
+                    return ChordEvent::Button(event);
+                }
+            }
+
+            let event = if self.held_len > 0 {
+                let deadline = self.held[0].1 + self.window;
+                match with_deadline(deadline, self.events.next()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        self.flushing = true;
+                        continue;
+                    }
+                }
+            } else {
+                self.events.next().await
+            };
+
+            if self.suppressed.contains(event.button) {
+                if event.kind == ButtonEventKind::Released {
+                    self.suppressed.remove(event.button);
+                }
+                continue;
+            }
+
+            let in_chord = self.chords.iter().any(|chord| chord.contains(event.button));
+            if event.kind != ButtonEventKind::Pressed || !in_chord {
+                if self.held_len == 0 {
+                    return ChordEvent::Button(event);
+                }
+                // Anything else happening means no chord is coming
+                self.deferred = Some(event);
+                self.flushing = true;
+                continue;
+            }
+
+            self.held[self.held_len] = (event.button, event.at);
+            self.held_len += 1;
+
+            let held = self.held_set();
+            if let Some(&chord) = self.chords.iter().find(|&&chord| chord == held) {
+                self.held_len = 0;
+                self.suppressed = self.suppressed.union(chord);
+                return ChordEvent::Chord(chord);
+            }
+            if !self.chords.iter().any(|chord| chord.is_superset(held)) {
+                self.flushing = true;
+            }
+        }
+    }
+
+    fn held_set(&self) -> ButtonSet {
+        self.held[..self.held_len]
+            .iter()
+            .fold(ButtonSet::EMPTY, |set, &(button, _)| set.with(button))
+    }
+
+    fn pop_held(&mut self) -> Option<ButtonEvent> {
+        if self.held_len == 0 {
+            return None;
+        }
+        let (button, at) = self.held[0];
+        self.held.copy_within(1..self.held_len, 0);
+        self.held_len -= 1;
+        Some(ButtonEvent {
+            button,
+            kind: ButtonEventKind::Pressed,
+            at,
+        })
+    }
+}
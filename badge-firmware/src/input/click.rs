@@ -0,0 +1,105 @@
+//! Single, double and triple clicks, or as many as you like.
+
+use embassy_time::{Duration, Instant, with_deadline};
+
+use super::{Button, ButtonEventKind, ButtonEvents};
+
+/// How long after one press the next one still counts towards the same click, unless
+/// configured.
+pub const DEFAULT_DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(300);
+
+/// A button pressed `count` times in quick succession: 1 for a single click, 2 for a double
+/// click and so on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Click {
+    pub button: Button,
+    pub count: u8,
+}
+
+/// Turns button presses into clicks with a press count.
+///
+/// A click is only reported once the window for another press has passed, so a double click
+/// never fires a single click first. The exception is reaching the most presses counted, see
+/// [`with_max_count`](Self::with_max_count), which is reported right away.
+///
+/// ```ignore
+/// let mut clicks = ClickDetector::new(events).with_max_count(3);
+/// loop {
+///     match clicks.next().await {
+///         Click { button: Button::A, count: 1 } => next_page(),
+///         Click { button: Button::A, count: 3 } => open_menu(),
+///         _ => {}
+///     }
+/// }
+/// ```
+pub struct ClickDetector<'a> {
+    events: ButtonEvents<'a>,
+    window: Duration,
+    max_count: u8,
+    // Presses waiting to find out whether more are coming, and when the latest one was
+    pending: Option<(Button, Instant, u8)>,
+}
+
+impl<'a> ClickDetector<'a> {
+    /// Count single and double clicks.
+    pub fn new(events: ButtonEvents<'a>) -> Self {
+        Self {
+            events,
+            window: DEFAULT_DOUBLE_CLICK_WINDOW,
+            max_count: 2,
+            pending: None,
+        }
+    }
+
+    /// The longest time between two presses of the same click.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Count up to `count` presses, 2 unless set. Every press counted adds a wait before the
+    /// click is reported, so only count as high as the app needs.
+    pub fn with_max_count(mut self, count: u8) -> Self {
+        self.max_count = count.max(1);
+        self
+    }
+
+    /// Wait for the next click.
+    pub async fn next(&mut self) -> Click {
+        loop {
+            // No point waiting for presses we wouldn't count
+            if let Some((button, _, count)) = self
+                .pending
+                .filter(|&(_, _, count)| count >= self.max_count)
+            {
+                self.pending = None;
+                return Click { button, count };
+            }
+
+            let event = match self.pending {
+                Some((button, at, count)) => {
+                    match with_deadline(at + self.window, self.events.next()).await {
+                        Ok(event) => event,
+                        Err(_) => {
+                            self.pending = None;
+                            return Click { button, count };
+                        }
+                    }
+                }
+                None => self.events.next().await,
+            };
+            if event.kind != ButtonEventKind::Pressed {
+                continue;
+            }
+
+            match self.pending.replace((event.button, event.at, 1)) {
+                Some((button, _, count)) if button == event.button => {
+                    self.pending = Some((button, event.at, count.saturating_add(1)));
+                }
+                // Another button interrupted the clicking, so the earlier click is complete
+                Some((button, _, count)) => return Click { button, count },
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! One place to give feedback on every button press, like a click sound or an LED blink.
+//!
+//! The hook runs in the button task started with [`spawn`](super::spawn), before the press is
+//! published, so it must be quick. Anything that takes longer is best handed to a task of its
+//! own, for example with a signal:
+//!
+//! ```ignore
+//! static BLINK: Signal<CriticalSectionRawMutex, Button> = Signal::new();
+//!
+//! input::feedback::set_press_hook(Some(|event| BLINK.signal(event.button)));
+//! ```
+
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+
+use super::{ButtonEvent, ButtonEventKind};
+
+/// A function called with every press.
+pub type PressHook = fn(ButtonEvent);
+
+static PRESS_HOOK: Mutex<CriticalSectionRawMutex, Cell<Option<PressHook>>> =
+    Mutex::new(Cell::new(None));
+
+/// Call `hook` on every press, including auto-repeated ones, or stop calling it with `None`.
+pub fn set_press_hook(hook: Option<PressHook>) {
+    PRESS_HOOK.lock(|cell| cell.set(hook));
+}
+
+// Called by the button task for every event it publishes
+pub(super) fn run_hooks(event: ButtonEvent) {
+    if !matches!(
+        event.kind,
+        ButtonEventKind::Pressed | ButtonEventKind::Repeated
+    ) {
+        return;
+    }
+    if let Some(hook) = PRESS_HOOK.lock(|cell| cell.get()) {
+        hook(event);
+    }
+}
@@ -0,0 +1,114 @@
+//! Input focus, so a dialog can take the buttons away from the rest of the app for a while.
+//!
+//! Every task reading [`BUTTON_CHANNEL`] through a [`FocusSubscriber`] gets all the events,
+//! until one of them claims focus on some buttons. From then on only the claiming subscriber
+//! gets the events of those buttons, until it drops the [`Focus`]:
+//!
+//! ```ignore
+//! let mut dialog = FocusSubscriber::new(BUTTON_CHANNEL.subscriber().unwrap());
+//! let focus = dialog.claim(ButtonSet::ALL);
+//! while dialog.next().await.button != Button::A {}
+//! drop(focus);
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::{BUTTON_CHANNEL, Button, ButtonEvent, ButtonSet, ButtonSubscriber};
+
+// Nobody has focus, so everybody gets the events
+const NOBODY: u32 = 0;
+
+// The subscriber with focus on each button
+static OWNERS: [AtomicU32; 9] = [const { AtomicU32::new(NOBODY) }; 9];
+// Wide enough that it never wraps round to NOBODY or to an ID still in use
+static NEXT_ID: AtomicU32 = AtomicU32::new(NOBODY + 1);
+
+/// A [`ButtonSubscriber`] that only gets the events it has focus for.
+pub struct FocusSubscriber {
+    subscriber: ButtonSubscriber,
+    id: u32,
+    // Buttons this subscriber doesn't want at all
+    ignored: ButtonSet,
+}
+
+impl FocusSubscriber {
+    pub fn new(subscriber: ButtonSubscriber) -> Self {
+        Self {
+            subscriber,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            ignored: ButtonSet::EMPTY,
+        }
+    }
+
+    /// Subscribe to [`BUTTON_CHANNEL`], or `None` if it has no subscriber slots left.
+    pub fn subscribe() -> Option<Self> {
+        BUTTON_CHANNEL.subscriber().ok().map(Self::new)
+    }
+
+    /// Stop getting the events of `buttons`, focus or not.
+    pub fn disable(&mut self, buttons: ButtonSet) {
+        self.ignored = self.ignored.union(buttons);
+    }
+
+    /// Get the events of `buttons` again after [`disable`](Self::disable).
+    pub fn enable(&mut self, buttons: ButtonSet) {
+        buttons
+            .iter()
+            .for_each(|button| self.ignored.remove(button));
+    }
+
+    /// Take focus on `buttons` until the returned [`Focus`] is dropped, when whoever had it
+    /// before gets it back. Release focus in the reverse order it was claimed.
+    ///
+    /// A button held down while focus changes hands may send its release to the new owner, so
+    /// don't rely on seeing a release for every press.
+    pub fn claim(&self, buttons: ButtonSet) -> Focus {
+        let mut previous = [NOBODY; 9];
+        for button in buttons.iter() {
+            previous[button as usize] = OWNERS[button as usize].swap(self.id, Ordering::Relaxed);
+        }
+        Focus {
+            id: self.id,
+            buttons,
+            previous,
+        }
+    }
+
+    /// Whether this subscriber gets the events of `button` right now.
+    pub fn has_focus(&self, button: Button) -> bool {
+        let owner = OWNERS[button as usize].load(Ordering::Relaxed);
+        !self.ignored.contains(button) && (owner == NOBODY || owner == self.id)
+    }
+
+    /// Wait for the next event this subscriber has focus for, skipping any others.
+    pub async fn next(&mut self) -> ButtonEvent {
+        loop {
+            let event = self.subscriber.next_message_pure().await;
+            if self.has_focus(event.button) {
+                return event;
+            }
+        }
+    }
+}
+
+/// Focus on some buttons, claimed with [`FocusSubscriber::claim`] and released when dropped.
+#[must_use = "focus is released as soon as this is dropped"]
+pub struct Focus {
+    id: u32,
+    buttons: ButtonSet,
+    previous: [u32; 9],
+}
+
+impl Drop for Focus {
+    fn drop(&mut self) {
+        for button in self.buttons.iter() {
+            // Leave it alone if someone else has claimed it since
+            let _ = OWNERS[button as usize].compare_exchange(
+                self.id,
+                self.previous[button as usize],
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Motions on the D-pad, for fighting-game-style inputs.
+
+use embassy_time::{Duration, Instant};
+
+use super::{Button, ButtonEvent, ButtonEventKind, DPAD_CLOCKWISE};
+
+/// How soon each direction of a gesture has to follow the previous one, unless configured.
+pub const DEFAULT_GESTURE_WINDOW: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Gesture {
+    /// The same direction pressed twice, like a dash.
+    DoubleTap(Button),
+    /// A quarter of a circle between two neighbouring directions, e.g. Down then Right.
+    QuarterCircle {
+        from: Button,
+        to: Button,
+        clockwise: bool,
+    },
+    /// A full circle, four quarter circles in a row the same way round.
+    Rotation { clockwise: bool },
+}
+
+/// Watches D-pad presses for [`Gesture`]s.
+///
+/// Like [`SequenceMatcher`](super::SequenceMatcher) it is fed events rather than reading them
+/// itself, so it works just as well on the [`BUTTON_CHANNEL`](super::BUTTON_CHANNEL) and the
+/// events still reach the app as usual:
+///
+/// ```ignore
+/// let mut gestures = GestureDetector::new();
+/// loop {
+///     let event = subscriber.next_message_pure().await;
+///     match gestures.feed(event) {
+///         Some(Gesture::QuarterCircle { clockwise: true, .. }) => info!("Hadouken!"),
+///         _ => {}
+///     }
+/// }
+/// ```
+///
+/// Every quarter circle is reported as it happens, except that the fourth one in a row is
+/// reported as the [`Gesture::Rotation`] it completes.
+#[derive(Clone, Debug)]
+pub struct GestureDetector {
+    window: Duration,
+    // The latest direction pressed and when
+    last: Option<(usize, Instant)>,
+    // Quarter circles in a row, positive clockwise
+    turns: i8,
+}
+
+impl Default for GestureDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureDetector {
+    pub const fn new() -> Self {
+        Self {
+            window: DEFAULT_GESTURE_WINDOW,
+            last: None,
+            turns: 0,
+        }
+    }
+
+    /// The longest time between two directions of a gesture.
+    pub const fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Feed in the next event. Returns the gesture it completes, if any.
+    ///
+    /// Only D-pad presses count, so everything else can be passed in as it comes.
+    pub fn feed(&mut self, event: ButtonEvent) -> Option<Gesture> {
+        if event.kind != ButtonEventKind::Pressed {
+            return None;
+        }
+        let to = DPAD_CLOCKWISE
+            .iter()
+            .position(|&button| button == event.button)?;
+
+        let previous = self
+            .last
+            .replace((to, event.at))
+            .filter(|&(_, at)| event.at.saturating_duration_since(at) <= self.window);
+        let Some((from, _)) = previous else {
+            self.turns = 0;
+            return None;
+        };
+
+        match (to + DPAD_CLOCKWISE.len() - from) % DPAD_CLOCKWISE.len() {
+            0 => {
+                // Start over, so a triple tap isn't two double taps
+                self.last = None;
+                self.turns = 0;
+                Some(Gesture::DoubleTap(event.button))
+            }
+            1 => Some(self.turn(from, to, true)),
+            3 => Some(self.turn(from, to, false)),
+            // Straight across to the opposite direction
+            _ => {
+                self.turns = 0;
+                None
+            }
+        }
+    }
+
+    /// Forget any progress, e.g. when a new round starts.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.turns = 0;
+    }
+
+    fn turn(&mut self, from: usize, to: usize, clockwise: bool) -> Gesture {
+        let step = if clockwise { 1 } else { -1 };
+        // Changing direction starts a new circle
+        if self.turns.signum() == -step {
+            self.turns = 0;
+        }
+        self.turns += step;
+
+        if self.turns.abs() == DPAD_CLOCKWISE.len() as i8 {
+            self.turns = 0;
+            return Gesture::Rotation { clockwise };
+        }
+        Gesture::QuarterCircle {
+            from: DPAD_CLOCKWISE[from],
+            to: DPAD_CLOCKWISE[to],
+            clockwise,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Telling when nobody has touched the buttons for a while.
+//!
+//! The button task started with [`spawn`](super::spawn) publishes an [`IdleEvent`] to
+//! [`IDLE_CHANNEL`] once the buttons have been left alone for the idle timeout, and again every
+//! timeout after that. The next button event is preceded by [`IdleEvent::Wake`]. A screensaver
+//! or auto-sleep can subscribe to that instead of keeping its own timer:
+//!
+//! ```ignore
+//! let mut idle = IDLE_CHANNEL.subscriber().unwrap();
+//! loop {
+//!     match idle.next_message_pure().await {
+//!         IdleEvent::Idle(time) if time >= Duration::from_secs(60) => screensaver.start(),
+//!         IdleEvent::Wake => screensaver.stop(),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, pubsub::PubSubChannel};
+use embassy_time::Duration;
+
+use super::MAX_SUBSCRIBERS;
+
+/// How long the buttons have to be left alone before the first [`IdleEvent::Idle`], unless
+/// configured with [`set_idle_timeout`].
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum IdleEvent {
+    /// No buttons for this long so far.
+    Idle(Duration),
+    /// A button was used after being idle.
+    Wake,
+}
+
+/// Idle and wake events, published by the button task.
+pub static IDLE_CHANNEL: PubSubChannel<CriticalSectionRawMutex, IdleEvent, 2, MAX_SUBSCRIBERS, 1> =
+    PubSubChannel::new();
+
+static TIMEOUT_MS: AtomicU32 = AtomicU32::new(DEFAULT_IDLE_TIMEOUT.as_millis() as u32);
+
+/// How long the buttons have to be left alone before they count as idle. Takes effect the next
+/// time the button task starts waiting.
+pub fn set_idle_timeout(timeout: Duration) {
+    let ms = timeout.as_millis().clamp(1, u32::MAX as u64) as u32;
+    TIMEOUT_MS.store(ms, Ordering::Relaxed);
+}
+
+pub fn idle_timeout() -> Duration {
+    Duration::from_millis(TIMEOUT_MS.load(Ordering::Relaxed) as u64)
+}
@@ -0,0 +1,161 @@
+//! Recording button events and playing them back.
+//!
+//! Played back events go out on [`BUTTON_CHANNEL`] like real ones, so apps can't tell the
+//! difference. Handy for attract modes at the booth and for reproducing bugs: record what
+//! someone did, [`Recording::encode`] it, and send the bytes over.
+
+use alloc::vec::Vec;
+
+use embassy_time::{Duration, Instant, Timer};
+
+use super::{BUTTON_CHANNEL, Button, ButtonEvent, ButtonEventKind, ButtonSubscriber};
+
+const ENCODED_EVENT_LEN: usize = 10;
+
+/// A button event and when it happened, relative to the start of the recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct RecordedEvent {
+    pub offset: Duration,
+    pub button: Button,
+    pub kind: ButtonEventKind,
+}
+
+/// Button events captured by a [`Recorder`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// How long the recording takes to play back.
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::from_ticks(0), |last| last.offset)
+    }
+
+    /// Pack the recording into bytes, for example to save it in flash or send it elsewhere.
+    ///
+    /// Each event takes 10 bytes: offset in ms (u32 LE), button, kind, and the held time in ms
+    /// (u32 LE, zero for other kinds).
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.events.len() * ENCODED_EVENT_LEN);
+        for recorded in &self.events {
+            let (kind, held) = match recorded.kind {
+                ButtonEventKind::Pressed => (0, 0),
+                ButtonEventKind::Released => (1, 0),
+                ButtonEventKind::Held(time) => (2, time.as_millis() as u32),
+                ButtonEventKind::Repeated => (3, 0),
+            };
+            out.extend_from_slice(&(recorded.offset.as_millis() as u32).to_le_bytes());
+            out.extend_from_slice(&[recorded.button as u8, kind]);
+            out.extend_from_slice(&held.to_le_bytes());
+        }
+        out
+    }
+
+    /// Unpack bytes made by [`encode`](Self::encode), or `None` if they don't make sense.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let chunks = bytes.chunks_exact(ENCODED_EVENT_LEN);
+        if !chunks.remainder().is_empty() {
+            return None;
+        }
+        let events = chunks
+            .map(|chunk| {
+                let offset = u32::from_le_bytes(chunk[0..4].try_into().unwrap());
+                let button = *Button::ALL.get(chunk[4] as usize)?;
+                let held = u32::from_le_bytes(chunk[6..10].try_into().unwrap());
+                let kind = match chunk[5] {
+                    0 => ButtonEventKind::Pressed,
+                    1 => ButtonEventKind::Released,
+                    2 => ButtonEventKind::Held(Duration::from_millis(held as u64)),
+                    3 => ButtonEventKind::Repeated,
+                    _ => return None,
+                };
+                Some(RecordedEvent {
+                    offset: Duration::from_millis(offset as u64),
+                    button,
+                    kind,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { events })
+    }
+}
+
+/// Collects button events into a [`Recording`], up to a maximum count.
+pub struct Recorder {
+    recording: Recording,
+    start: Instant,
+    max_events: usize,
+}
+
+impl Recorder {
+    /// Start a recording now, keeping at most `max_events` events.
+    pub fn new(max_events: usize) -> Self {
+        Self {
+            recording: Recording::default(),
+            start: Instant::now(),
+            max_events,
+        }
+    }
+
+    /// Add an event. Returns `false` once the recording is full.
+    pub fn push(&mut self, event: ButtonEvent) -> bool {
+        if self.recording.events.len() >= self.max_events {
+            return false;
+        }
+        self.recording.events.push(RecordedEvent {
+            offset: event.at.saturating_duration_since(self.start),
+            button: event.button,
+            kind: event.kind,
+        });
+        true
+    }
+
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+/// Record everything from `subscriber` until `stop` is pressed or `max_events` have been
+/// recorded. The events of the stop button itself are left out.
+pub async fn record(
+    subscriber: &mut ButtonSubscriber,
+    stop: Button,
+    max_events: usize,
+) -> Recording {
+    let mut recorder = Recorder::new(max_events);
+    loop {
+        let event = subscriber.next_message_pure().await;
+        if event.button == stop {
+            if event.kind == ButtonEventKind::Pressed {
+                return recorder.finish();
+            }
+            continue;
+        }
+        if !recorder.push(event) {
+            return recorder.finish();
+        }
+    }
+}
+
+/// Publish the events of `recording` to [`BUTTON_CHANNEL`] with their original timing.
+pub async fn play(recording: &Recording) {
+    // An immediate publisher doesn't take the publisher slot used by the button task
+    let publisher = BUTTON_CHANNEL.immediate_publisher();
+    let start = Instant::now();
+    for recorded in recording.events() {
+        let at = start + recorded.offset;
+        Timer::at(at).await;
+        publisher.publish_immediate(ButtonEvent {
+            button: recorded.button,
+            kind: recorded.kind,
+            at,
+        });
+    }
+}
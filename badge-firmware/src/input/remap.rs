@@ -0,0 +1,119 @@
+//! Remapping buttons, e.g. swapping A and B or turning the D-pad for left-handed use.
+//!
+//! [`ButtonEvents`](super::ButtonEvents) reports every event as the button it's mapped to, so
+//! apps and everything built on top of the events only ever see the remapped buttons. The
+//! mapping is the `remap` field of [`Settings`], applied with [`apply_settings`], and can be
+//! changed at runtime with [`set`]:
+//!
+//! ```ignore
+//! let remap = Remap::IDENTITY.swap(Button::A, Button::B).rotate_dpad(2);
+//! remap::set(remap);
+//! settings.remap = remap;
+//! store.save(&settings)?;
+//! ```
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use super::{Button, DPAD_CLOCKWISE};
+use crate::settings::Settings;
+
+/// Which button each physical button reports as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Remap {
+    // Indexed by the physical button
+    table: [Button; 9],
+}
+
+impl Remap {
+    /// Every button reports as itself.
+    pub const IDENTITY: Self = Self { table: Button::ALL };
+
+    /// The button that `physical` reports as.
+    pub const fn get(&self, physical: Button) -> Button {
+        self.table[physical as usize]
+    }
+
+    /// This mapping with whatever `a` and `b` report as swapped.
+    pub const fn swap(mut self, a: Button, b: Button) -> Self {
+        let mapped_a = self.table[a as usize];
+        self.table[a as usize] = self.table[b as usize];
+        self.table[b as usize] = mapped_a;
+        self
+    }
+
+    /// This mapping with the D-pad turned clockwise by `quarter_turns`, so with one quarter
+    /// turn pressing Up reports Right. Two quarter turns suit holding the badge upside down.
+    pub const fn rotate_dpad(mut self, quarter_turns: u8) -> Self {
+        let before = self;
+        let mut i = 0;
+        while i < DPAD_CLOCKWISE.len() {
+            let turned = DPAD_CLOCKWISE[(i + quarter_turns as usize) % DPAD_CLOCKWISE.len()];
+            self.table[DPAD_CLOCKWISE[i] as usize] = before.table[turned as usize];
+            i += 1;
+        }
+        self
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; 9] {
+        self.table.map(|button| button as u8)
+    }
+
+    /// `None` unless every button is mapped to a different one, so no button gets lost.
+    pub(crate) fn from_bytes(bytes: [u8; 9]) -> Option<Self> {
+        let mut table = Button::ALL;
+        let mut seen = 0u16;
+        for (mapped, byte) in table.iter_mut().zip(bytes) {
+            *mapped = *Button::ALL.get(byte as usize)?;
+            seen |= 1 << byte;
+        }
+        (seen == (1 << Button::ALL.len()) - 1).then_some(Self { table })
+    }
+}
+
+impl Default for Remap {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+// One atomic per physical button, holding the discriminant of the button it reports as
+static TABLE: [AtomicU8; 9] = [
+    AtomicU8::new(0),
+    AtomicU8::new(1),
+    AtomicU8::new(2),
+    AtomicU8::new(3),
+    AtomicU8::new(4),
+    AtomicU8::new(5),
+    AtomicU8::new(6),
+    AtomicU8::new(7),
+    AtomicU8::new(8),
+];
+
+/// Switch to the mapping chosen in the settings.
+pub fn apply_settings(settings: &Settings) {
+    set(settings.remap);
+}
+
+/// Switch to `remap` for every event from now on.
+///
+/// A button held down while the mapping changes may be released as a different button than it
+/// was pressed as, so prefer changing it from a menu rather than in the middle of a game.
+pub fn set(remap: Remap) {
+    for (entry, byte) in TABLE.iter().zip(remap.to_bytes()) {
+        entry.store(byte, Ordering::Relaxed);
+    }
+}
+
+/// The mapping in use.
+pub fn current() -> Remap {
+    Remap::from_bytes(TABLE.each_ref().map(|entry| entry.load(Ordering::Relaxed)))
+        .unwrap_or_default()
+}
+
+/// The button that `physical` currently reports as.
+pub fn get(physical: Button) -> Button {
+    Button::ALL
+        .get(TABLE[physical as usize].load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or(physical)
+}
@@ -0,0 +1,84 @@
+//! Matching a sequence of presses, for easter eggs and unlock codes.
+
+use super::{Button, ButtonEvent, ButtonEventKind};
+
+/// Up Up Down Down Left Right Left Right B A, everyone's favourite.
+pub const KONAMI_CODE: [Button; 10] = [
+    Button::Up,
+    Button::Up,
+    Button::Down,
+    Button::Down,
+    Button::Left,
+    Button::Right,
+    Button::Left,
+    Button::Right,
+    Button::B,
+    Button::A,
+];
+
+/// Watches presses for a particular sequence of buttons.
+///
+/// ```ignore
+/// let mut konami = SequenceMatcher::new(&KONAMI_CODE);
+/// loop {
+///     if konami.feed(events.next().await) {
+///         info!("30 extra lives!");
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SequenceMatcher<'a> {
+    sequence: &'a [Button],
+    // How many buttons of the sequence the latest presses match
+    progress: usize,
+}
+
+impl<'a> SequenceMatcher<'a> {
+    pub const fn new(sequence: &'a [Button]) -> Self {
+        Self {
+            sequence,
+            progress: 0,
+        }
+    }
+
+    /// Feed in the next event. Returns `true` when it completes the sequence.
+    ///
+    /// Only presses count, so releases and holds can be passed in as they come.
+    pub fn feed(&mut self, event: ButtonEvent) -> bool {
+        if event.kind != ButtonEventKind::Pressed {
+            return false;
+        }
+        self.press(event.button)
+    }
+
+    /// Feed in the next pressed button. Returns `true` when it completes the sequence.
+    pub fn press(&mut self, button: Button) -> bool {
+        if self.sequence.is_empty() {
+            return false;
+        }
+
+        // On a wrong press, the sequence may still have started partway through what came
+        // before, e.g. the third Up in Up Up Up Down...
+        let seen = &self.sequence[..self.progress];
+        self.progress = (0..=self.progress)
+            .rev()
+            .find(|&len| {
+                let tail = &seen[self.progress - len..];
+                len < self.sequence.len()
+                    && self.sequence[..len] == *tail
+                    && self.sequence[len] == button
+            })
+            .map_or(0, |len| len + 1);
+
+        if self.progress == self.sequence.len() {
+            self.progress = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Forget any progress, e.g. after a timeout.
+    pub fn reset(&mut self) {
+        self.progress = 0;
+    }
+}
@@ -0,0 +1,48 @@
+//! Sleeping until a button is pressed.
+//!
+//! Light sleep stops the CPU clocks but keeps the memory, so the app carries on where it left
+//! off, and the buttons' GPIO interrupts can wake it up. That makes it cheap enough to sleep
+//! whenever nothing is happening, for example on [`IdleEvent::Idle`](super::IdleEvent):
+//!
+//! ```ignore
+//! let mut rtc = Rtc::new(peripherals.LPWR);
+//! if let Some(button) = input::sleep_until_press(&mut buttons, &mut rtc) {
+//!     info!("Woken up by {}", button);
+//! }
+//! ```
+//!
+//! Waking from deep sleep would need the buttons' pins as RTC pins, but the BSP only hands them
+//! out as inputs, so only light sleep is supported.
+
+use defmt::warn;
+use disobey2026badge::Buttons;
+use esp_hal::{
+    gpio::WakeEvent,
+    rtc_cntl::{Rtc, sleep::GpioWakeupSource},
+};
+
+use super::{Button, ButtonsExt, pins};
+
+/// Light sleep until any button is pressed, and tell which one it was, as mapped by
+/// [`remap`](super::remap). `None` if it was released again before we got to look.
+///
+/// This returns straight away if a button is already down. Nothing else runs while the badge
+/// sleeps, including other tasks and timers, so only call it when the whole app can wait.
+pub fn sleep_until_press(buttons: &mut Buttons, rtc: &mut Rtc<'_>) -> Option<Button> {
+    // The buttons pull the pin low when pressed
+    for pin in pins(buttons) {
+        pin.wakeup_enable(true, WakeEvent::LowLevel)
+            .unwrap_or_else(|_| warn!("Unable to enable wakeup on a button"));
+    }
+    rtc.sleep_light(&[&GpioWakeupSource::new()]);
+    for pin in pins(buttons) {
+        pin.wakeup_enable(false, WakeEvent::LowLevel)
+            .unwrap_or_else(|_| warn!("Unable to disable wakeup on a button"));
+    }
+
+    // The state is already remapped, so take the first pressed button in that order
+    let state = buttons.state();
+    Button::ALL
+        .into_iter()
+        .find(|&button| state.contains(button))
+}
@@ -0,0 +1,164 @@
+//! Brightness and colour helpers for the LEDs.
+//!
+//! The brightness is shared by every task: [`set_brightness`] from a menu, and anything that
+//! shows colours on the LEDs runs them through [`scale`] first. [`SafeLeds`] does that for you,
+//! along with the flash limit, the [`power_budget`] and the [`correction`] of each LED, none of
+//! which the plain BSP `Leds` know about:
+//!
+//! ```ignore
+//! led::set_brightness(64);
+//! leds.fill(led::hsv(frame as f32 * 2.0, 1.0, 0.3));
+//! leds.update().await;
+//! ```
+//!
+//! For anything more than a single colour there are ready-made [`Effect`]s, keyframe
+//! [`Animation`]s and computed frames, each run on a task of their own, and the submodules add
+//! notification [`overlay`]s, [`zone`]s for tasks sharing the strip and the look picked in the
+//! [`settings`].
+//!
+//! [`SafeLeds`]: crate::safety::SafeLeds
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use palette::{FromColor, Hsl, Hsv, encoding::Srgb, rgb::Rgb};
+
+mod animation;
+pub mod correction;
+mod effect;
+mod frames;
+mod layout;
+pub mod overlay;
+mod power;
+pub mod settings;
+pub mod zone;
+
+pub use animation::{Animation, Colors, Easing, Keyframe, play, spawn_animation_task};
+pub use effect::{Breathe, Chase, Effect, Fire, HEAT_PALETTE, Rainbow, Sparkle};
+pub use frames::{FrameFn, spawn_frame_task};
+pub use layout::{LAYOUT, Position, position};
+pub use power::{
+    BATTERY_BRIGHTNESS, DEFAULT_POWER_BUDGET, LOW_BATTERY_BRIGHTNESS, LOW_BATTERY_MILLIVOLTS,
+    MILLIAMPS_PER_CHANNEL, PowerSource, estimate_draw, fit_power_budget, max_brightness,
+    power_budget, power_source, set_power_budget, set_power_source,
+};
+
+/// How many LEDs the badge has, numbered from 0 for `Leds::set`.
+pub const LED_COUNT: usize = 10;
+
+/// Gamma 2.2 correction: perceived brightness in, LED duty cycle out. This is the table in use
+/// unless changed with [`set_gamma_table`].
+///
+/// Our eyes are much more sensitive to changes in dim light, so halving the brightness setting
+/// should far more than halve the light. Going through this table keeps the steps between low
+/// brightness levels even instead of jumping between a few coarse levels.
+pub const GAMMA: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+/// No correction at all, the brightness setting is the duty cycle. Handy for measuring the LEDs
+/// themselves.
+pub const LINEAR: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+static BRIGHTNESS: AtomicU8 = AtomicU8::new(u8::MAX);
+static GAMMA_TABLE: [AtomicU8; 256] = {
+    let mut table = [const { AtomicU8::new(0) }; 256];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = AtomicU8::new(GAMMA[i]);
+        i += 1;
+    }
+    table
+};
+
+/// A table for a gamma curve other than 2.2, for LEDs that look better with another one. Pass it
+/// to [`set_gamma_table`].
+pub fn gamma_curve(gamma: f32) -> [u8; 256] {
+    core::array::from_fn(|i| libm::roundf(libm::powf(i as f32 / 255.0, gamma) * 255.0) as u8)
+}
+
+/// Go through `table` for the brightness from now on: the brightness setting as the index, the
+/// duty cycle as the value. See [`GAMMA`], [`LINEAR`] and [`gamma_curve`].
+pub fn set_gamma_table(table: &[u8; 256]) {
+    for (entry, &value) in GAMMA_TABLE.iter().zip(table) {
+        entry.store(value, Ordering::Relaxed);
+    }
+}
+
+/// The table in use, see [`set_gamma_table`].
+pub fn gamma_table() -> [u8; 256] {
+    GAMMA_TABLE
+        .each_ref()
+        .map(|entry| entry.load(Ordering::Relaxed))
+}
+
+/// Set the brightness of everything shown from now on, from 0 (off) to 255 (as bright as the
+/// colours themselves).
+pub fn set_brightness(level: u8) {
+    BRIGHTNESS.store(level, Ordering::Relaxed);
+}
+
+pub fn brightness() -> u8 {
+    BRIGHTNESS.load(Ordering::Relaxed)
+}
+
+/// `color` dimmed to the current brightness, or to the [`max_brightness`] on battery if that's
+/// lower.
+pub fn scale(color: Rgb<Srgb, u8>) -> Rgb<Srgb, u8> {
+    let level = brightness().min(max_brightness());
+    let factor = GAMMA_TABLE[level as usize].load(Ordering::Relaxed) as u16;
+    // Round rather than truncate, so dim colours don't lose a step
+    let channel = |value: u8| ((value as u16 * factor + 127) / 255) as u8;
+    Rgb::new(
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue),
+    )
+}
+
+/// Convert any colour `palette` can turn into sRGB, such as [`Hsv`] or [`Hsl`], for the LEDs.
+pub fn to_rgb<C>(color: C) -> Rgb<Srgb, u8>
+where
+    Rgb<Srgb, f32>: FromColor<C>,
+{
+    Rgb::<Srgb, f32>::from_color(color).into_format()
+}
+
+/// The colour with `hue` in degrees, and `saturation` and `value` from 0.0 to 1.0.
+pub fn hsv(hue: f32, saturation: f32, value: f32) -> Rgb<Srgb, u8> {
+    to_rgb(Hsv::<Srgb, f32>::new(hue, saturation, value))
+}
+
+/// The colour with `hue` in degrees, and `saturation` and `lightness` from 0.0 to 1.0.
+pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Rgb<Srgb, u8> {
+    to_rgb(Hsl::<Srgb, f32>::new(hue, saturation, lightness))
+}
+
+// The colour `t` of the way from `from` to `to`, with `t` from 0.0 to 1.0
+fn mix(from: Rgb<Srgb, u8>, to: Rgb<Srgb, u8>, t: f32) -> Rgb<Srgb, u8> {
+    let channel =
+        |from: u8, to: u8| libm::roundf(from as f32 + (to as f32 - from as f32) * t) as u8;
+    Rgb::new(
+        channel(from.red, to.red),
+        channel(from.green, to.green),
+        channel(from.blue, to.blue),
+    )
+}
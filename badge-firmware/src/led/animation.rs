@@ -0,0 +1,230 @@
+//! Keyframe animations, played one after another by a task of their own.
+//!
+//! An animation is a list of keyframes, each saying what the LEDs should show at some point in
+//! time and how to get there from the keyframe before. Apps describe an animation once, usually
+//! as a `static`, and [`play`] it whenever they like:
+//!
+//! ```ignore
+//! static PULSE: [Keyframe; 3] = [
+//!     Keyframe::new(Duration::from_millis(0), Colors::All(Rgb::new(0, 0, 0))),
+//!     Keyframe::new(Duration::from_millis(500), Colors::All(Rgb::new(0, 0, 64)))
+//!         .with_easing(Easing::EaseInOut),
+//!     Keyframe::new(Duration::from_millis(1000), Colors::All(Rgb::new(0, 0, 0)))
+//!         .with_easing(Easing::EaseInOut),
+//! ];
+//!
+//! let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+//! led::spawn_animation_task(&spawner, leds);
+//! led::play(Animation::new(&PULSE).looping());
+//! ```
+
+use defmt::warn;
+use embassy_executor::{Spawner, task};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
+use embassy_time::{Duration, Instant};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::LED_COUNT;
+use crate::{color::Blend, motion, safety::SafeLeds};
+
+// How many animations can wait for the one playing to finish
+const QUEUE_CAPACITY: usize = 4;
+
+static QUEUE: Channel<CriticalSectionRawMutex, Animation, QUEUE_CAPACITY> = Channel::new();
+
+/// How the colours change on the way to a keyframe, as a function of time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum Easing {
+    /// At an even pace.
+    #[default]
+    Linear,
+    /// Starting slowly and speeding up.
+    EaseIn,
+    /// Starting fast and slowing down.
+    EaseOut,
+    /// Slow at both ends, fast in the middle.
+    EaseInOut,
+    /// Not at all until the keyframe, then all at once.
+    Step,
+}
+
+impl Easing {
+    /// How far along the change is, from 0.0 to 1.0, `t` of the way through the time.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// What the LEDs show at a keyframe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colors {
+    /// The same colour on every LED.
+    All(Rgb<Srgb, u8>),
+    /// A colour for each LED, by index. A shorter list starts over from the beginning, so two
+    /// colours alternate along the strip.
+    Each(&'static [Rgb<Srgb, u8>]),
+}
+
+impl Colors {
+    /// The colour of the LED at `index`.
+    pub fn get(&self, index: usize) -> Rgb<Srgb, u8> {
+        match *self {
+            Colors::All(color) => color,
+            Colors::Each([]) => Rgb::new(0, 0, 0),
+            Colors::Each(colors) => colors[index % colors.len()],
+        }
+    }
+}
+
+/// What the LEDs show `at` some time into the animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keyframe {
+    pub at: Duration,
+    pub colors: Colors,
+    /// How to get here from the keyframe before.
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    pub const fn new(at: Duration, colors: Colors) -> Self {
+        Self {
+            at,
+            colors,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub const fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/// Keyframes in order of time, played once or over and over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Animation {
+    keyframes: &'static [Keyframe],
+    looping: bool,
+    blend: Blend,
+}
+
+impl Animation {
+    /// Play `keyframes` once, then hold the last one. The keyframes must be in order of time.
+    pub const fn new(keyframes: &'static [Keyframe]) -> Self {
+        Self {
+            keyframes,
+            looping: false,
+            blend: Blend::Oklab,
+        }
+    }
+
+    /// Start over from the beginning after the last keyframe, until another animation is
+    /// queued. End on the same colours as the first keyframe for a seamless loop.
+    pub const fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// How to blend between keyframes, [`Blend::Oklab`] unless set.
+    pub const fn with_blend(mut self, blend: Blend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// How long one round takes, up to the last keyframe.
+    pub fn duration(&self) -> Duration {
+        self.keyframes
+            .last()
+            .map_or(Duration::from_ticks(0), |keyframe| keyframe.at)
+    }
+
+    /// The colour of the LED at `index`, `elapsed` into the animation.
+    pub fn color_at(&self, elapsed: Duration, index: usize) -> Rgb<Srgb, u8> {
+        let duration = self.duration();
+        let elapsed = if self.looping && duration.as_ticks() > 0 {
+            Duration::from_ticks(elapsed.as_ticks() % duration.as_ticks())
+        } else {
+            elapsed
+        };
+
+        let next = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.at > elapsed);
+        let (from, to) = match next {
+            Some(0) => return self.keyframes[0].colors.get(index),
+            Some(next) => (&self.keyframes[next - 1], &self.keyframes[next]),
+            None => {
+                return self
+                    .keyframes
+                    .last()
+                    .map_or(Rgb::new(0, 0, 0), |keyframe| keyframe.colors.get(index));
+            }
+        };
+
+        let t = (elapsed - from.at).as_ticks() as f32 / (to.at - from.at).as_ticks() as f32;
+        self.blend.mix(
+            from.colors.get(index),
+            to.colors.get(index),
+            to.easing.apply(t),
+        )
+    }
+}
+
+/// Queue `animation` to play after the ones already queued.
+///
+/// An animation that plays once finishes before the next one starts, a looping one gives way
+/// as soon as there's another one waiting.
+pub fn play(animation: Animation) {
+    QUEUE
+        .try_send(animation)
+        .unwrap_or_else(|_| warn!("Unable to queue animation, the queue is full"));
+}
+
+/// Start the task playing the animations queued with [`play`] on `leds`, one frame every
+/// [`FRAME_TIME`](motion::FRAME_TIME), in step with [`motion::next_frame`].
+pub fn spawn_animation_task(spawner: &Spawner, leds: &'static mut SafeLeds<'static>) {
+    spawner.must_spawn(animation_task(leds));
+}
+
+#[task]
+async fn animation_task(leds: &'static mut SafeLeds<'static>) {
+    loop {
+        let animation = QUEUE.receive().await;
+        let start = Instant::now();
+        loop {
+            let elapsed = Instant::now() - start;
+            for index in 0..LED_COUNT {
+                leds.set(index, animation.color_at(elapsed, index));
+            }
+            leds.update().await;
+
+            let finished = if animation.is_looping() {
+                !QUEUE.is_empty()
+            } else {
+                elapsed >= animation.duration()
+            };
+            if finished {
+                break;
+            }
+            motion::next_frame().await;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! White balance for each LED, to even out LEDs that come out tinted.
+//!
+//! LEDs from the same reel still differ a little, so the same colour can look greener on one
+//! than on its neighbour. A [`Correction`] dims the channels of each LED that are too strong.
+//! [`SafeLeds`](crate::safety::SafeLeds) applies it on every update, so apps don't need to know
+//! about it. The correction is the `led_correction` field of [`Settings`], applied with
+//! [`apply_settings`], and the `led_calibration` app helps find the right values. The plain BSP
+//! `Leds` know nothing of it, so [`set`] only changes LEDs driven through `SafeLeds`.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::LED_COUNT;
+use crate::settings::Settings;
+
+const FULL: Rgb<Srgb, u8> = Rgb::new(255, 255, 255);
+
+/// How strong each channel of each LED is, out of 255.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Correction {
+    table: [Rgb<Srgb, u8>; LED_COUNT],
+}
+
+impl Correction {
+    /// Every LED shows colours as they are.
+    pub const IDENTITY: Self = Self {
+        table: [FULL; LED_COUNT],
+    };
+
+    /// The channel strengths of the LED at `index`.
+    pub fn get(&self, index: usize) -> Rgb<Srgb, u8> {
+        self.table.get(index).copied().unwrap_or(FULL)
+    }
+
+    /// This correction with the LED at `index` set to `strength`.
+    pub fn with(mut self, index: usize, strength: Rgb<Srgb, u8>) -> Self {
+        if let Some(entry) = self.table.get_mut(index) {
+            *entry = strength;
+        }
+        self
+    }
+
+    /// `color` as the LED at `index` should be sent it.
+    pub fn apply(&self, index: usize, color: Rgb<Srgb, u8>) -> Rgb<Srgb, u8> {
+        let strength = self.get(index);
+        let channel =
+            |value: u8, strength: u8| ((value as u16 * strength as u16 + 127) / 255) as u8;
+        Rgb::new(
+            channel(color.red, strength.red),
+            channel(color.green, strength.green),
+            channel(color.blue, strength.blue),
+        )
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; 3 * LED_COUNT] {
+        let mut bytes = [0; 3 * LED_COUNT];
+        for (chunk, strength) in bytes.chunks_exact_mut(3).zip(self.table) {
+            chunk.copy_from_slice(&[strength.red, strength.green, strength.blue]);
+        }
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: [u8; 3 * LED_COUNT]) -> Self {
+        let mut table = [FULL; LED_COUNT];
+        for (strength, chunk) in table.iter_mut().zip(bytes.chunks_exact(3)) {
+            *strength = Rgb::new(chunk[0], chunk[1], chunk[2]);
+        }
+        Self { table }
+    }
+}
+
+impl Default for Correction {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+// One atomic per LED, holding its strengths as 0x00RRGGBB
+static TABLE: [AtomicU32; LED_COUNT] = [const { AtomicU32::new(0x00FF_FFFF) }; LED_COUNT];
+
+fn pack(strength: Rgb<Srgb, u8>) -> u32 {
+    u32::from_be_bytes([0, strength.red, strength.green, strength.blue])
+}
+
+fn unpack(packed: u32) -> Rgb<Srgb, u8> {
+    let [_, red, green, blue] = packed.to_be_bytes();
+    Rgb::new(red, green, blue)
+}
+
+/// Switch to the correction chosen in the settings.
+pub fn apply_settings(settings: &Settings) {
+    set(settings.led_correction);
+}
+
+/// Switch to `correction` from the next update on.
+pub fn set(correction: Correction) {
+    for (index, entry) in TABLE.iter().enumerate() {
+        entry.store(pack(correction.get(index)), Ordering::Relaxed);
+    }
+}
+
+/// The correction in use.
+pub fn current() -> Correction {
+    Correction {
+        table: TABLE
+            .each_ref()
+            .map(|entry| unpack(entry.load(Ordering::Relaxed))),
+    }
+}
@@ -0,0 +1,318 @@
+//! Ready-made effects, for showing something on the LEDs without writing an animation loop.
+//!
+//! [`SafeLeds::effect`] runs an effect until the future is dropped, so it's easy to stop with a
+//! `select`:
+//!
+//! ```ignore
+//! let breathe = Breathe { color: Rgb::new(0, 40, 0), period: Duration::from_secs(3) };
+//! select(leds.effect(breathe), wait_for_button()).await;
+//! ```
+//!
+//! To switch between effects or apps without a hard cut, [`SafeLeds::fade_to`] the first frame
+//! of what comes next.
+
+use core::f32::consts::TAU;
+
+use embassy_time::{Duration, Instant};
+use esp_hal::rng::Rng;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::LED_COUNT;
+use crate::{color::Blend, motion, safety::SafeLeds};
+
+/// Something to show on the LEDs that changes over time.
+pub trait Effect {
+    /// Fill in `colors` for `elapsed` time since the effect started.
+    fn render(&mut self, elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]);
+}
+
+impl SafeLeds<'_> {
+    /// Show `effect` forever, one frame every [`FRAME_TIME`](motion::FRAME_TIME) in step with
+    /// [`motion::next_frame`].
+    pub async fn effect(&mut self, mut effect: impl Effect) -> ! {
+        let mut colors = [Rgb::new(0, 0, 0); LED_COUNT];
+        let start = Instant::now();
+        loop {
+            effect.render(Instant::now() - start, &mut colors);
+            for (index, &color) in colors.iter().enumerate() {
+                self.set(index, color);
+            }
+            self.update().await;
+            motion::next_frame().await;
+        }
+    }
+
+    /// Blend from the colours shown now to `target` over `duration`, one frame every
+    /// [`FRAME_TIME`](motion::FRAME_TIME). The colours are blended in Oklab, see [`Blend`].
+    pub async fn fade_to(&mut self, target: [Rgb<Srgb, u8>; LED_COUNT], duration: Duration) {
+        let from = self.colors();
+        let start = Instant::now();
+        loop {
+            let elapsed = Instant::now() - start;
+            let t = elapsed.as_ticks() as f32 / duration.as_ticks().max(1) as f32;
+            for (index, (&from, &to)) in from.iter().zip(&target).enumerate() {
+                self.set(index, Blend::Oklab.mix(from, to, t));
+            }
+            self.update().await;
+            if elapsed >= duration {
+                return;
+            }
+            motion::next_frame().await;
+        }
+    }
+}
+
+// Where in the current round of `period` we are, from 0.0 to 1.0
+fn phase(elapsed: Duration, period: Duration) -> f32 {
+    let period = period.as_ticks().max(1);
+    (elapsed.as_ticks() % period) as f32 / period as f32
+}
+
+// `color` at `level` from 0.0 (off) to 1.0 (unchanged)
+fn dim(color: Rgb<Srgb, u8>, level: f32) -> Rgb<Srgb, u8> {
+    let channel = |value: u8| libm::roundf(value as f32 * level) as u8;
+    Rgb::new(
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue),
+    )
+}
+
+/// All LEDs fading smoothly in and out, like breathing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breathe {
+    /// The colour at the top of a breath.
+    pub color: Rgb<Srgb, u8>,
+    /// From one breath to the next.
+    pub period: Duration,
+}
+
+impl Effect for Breathe {
+    fn render(&mut self, elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]) {
+        // A cosine starting from off, so the effect fades in instead of jumping to full colour
+        let level = (1.0 - libm::cosf(phase(elapsed, self.period) * TAU)) / 2.0;
+        colors.fill(dim(self.color, level));
+    }
+}
+
+/// The hues of the rainbow going round along the strip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rainbow {
+    /// How long the colours take to go once round, shorter is faster.
+    pub period: Duration,
+    /// From 0.0 (white) to 1.0 (full colour).
+    pub saturation: f32,
+    /// From 0.0 (off) to 1.0 (full brightness).
+    pub value: f32,
+}
+
+impl Effect for Rainbow {
+    fn render(&mut self, elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]) {
+        let start = phase(elapsed, self.period) * 360.0;
+        // Spread one full round of hues over the strip
+        let step = 360.0 / LED_COUNT as f32;
+        for (index, color) in colors.iter_mut().enumerate() {
+            *color = super::hsv(start + index as f32 * step, self.saturation, self.value);
+        }
+    }
+}
+
+/// A lit LED running along the strip, with a fading tail behind it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chase {
+    pub color: Rgb<Srgb, u8>,
+    /// How long the light stays on each LED, shorter is faster.
+    pub step: Duration,
+    /// How many LEDs behind the lit one are still glowing.
+    pub tail: usize,
+    /// Run from the last LED towards the first instead.
+    pub reverse: bool,
+}
+
+impl Effect for Chase {
+    fn render(&mut self, elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]) {
+        let steps = (elapsed.as_ticks() / self.step.as_ticks().max(1)) as usize % LED_COUNT;
+        let head = if self.reverse {
+            LED_COUNT - 1 - steps
+        } else {
+            steps
+        };
+        for (index, color) in colors.iter_mut().enumerate() {
+            // How many LEDs behind the head this one is, going round the end of the strip
+            let behind = if self.reverse {
+                (index + LED_COUNT - head) % LED_COUNT
+            } else {
+                (head + LED_COUNT - index) % LED_COUNT
+            };
+            *color = if behind <= self.tail {
+                dim(self.color, 1.0 - behind as f32 / (self.tail + 1) as f32)
+            } else {
+                Rgb::new(0, 0, 0)
+            };
+        }
+    }
+}
+
+/// Random LEDs flashing briefly over a base colour, like glitter.
+///
+/// The randomness comes from the hardware random number generator.
+#[derive(Clone, Debug)]
+pub struct Sparkle {
+    base: Rgb<Srgb, u8>,
+    color: Rgb<Srgb, u8>,
+    density: f32,
+    decay: Duration,
+    rng: Rng,
+    // How far each LED is into its sparkle, from 1.0 at the flash to 0.0 back at the base
+    levels: [f32; LED_COUNT],
+    last: Duration,
+}
+
+impl Sparkle {
+    /// Sparkles of `color` over `base`, one per LED every two seconds on average.
+    pub fn new(base: Rgb<Srgb, u8>, color: Rgb<Srgb, u8>) -> Self {
+        Self {
+            base,
+            color,
+            density: 0.5,
+            decay: Duration::from_millis(300),
+            rng: Rng::new(),
+            levels: [0.0; LED_COUNT],
+            last: Duration::from_ticks(0),
+        }
+    }
+
+    /// How many times a second each LED sparkles on average.
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.max(0.0);
+        self
+    }
+
+    /// How long a sparkle takes to fade back to the base colour.
+    pub fn with_decay(mut self, decay: Duration) -> Self {
+        self.decay = decay;
+        self
+    }
+}
+
+impl Effect for Sparkle {
+    fn render(&mut self, elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]) {
+        // A restarted effect starts counting from zero again
+        let since_last = elapsed
+            .checked_sub(self.last)
+            .unwrap_or_default()
+            .as_micros() as f32;
+        self.last = elapsed;
+        let fade = since_last / self.decay.as_micros().max(1) as f32;
+        let chance = self.density * since_last / 1_000_000.0;
+
+        for (level, color) in self.levels.iter_mut().zip(colors.iter_mut()) {
+            *level = (*level - fade).max(0.0);
+            if (self.rng.random() as f32 / u32::MAX as f32) < chance {
+                *level = 1.0;
+            }
+            *color = super::mix(self.base, self.color, *level);
+        }
+    }
+}
+
+/// The colours of a fire from cold to hot, for [`Fire`].
+pub const HEAT_PALETTE: [Rgb<Srgb, u8>; 4] = [
+    Rgb::new(0, 0, 0),
+    Rgb::new(160, 0, 0),
+    Rgb::new(255, 140, 0),
+    Rgb::new(255, 255, 160),
+];
+
+/// Flames rising from the first LED: sparks of heat that spread up the strip and cool down.
+///
+/// Every frame, each LED loses some heat, heat drifts away from the first LED, and a new spark
+/// may light up near it. The heat is then shown as a colour from the palette.
+#[derive(Clone, Debug)]
+pub struct Fire {
+    palette: &'static [Rgb<Srgb, u8>],
+    cooling: u8,
+    sparking: u8,
+    rng: Rng,
+    heat: [u8; LED_COUNT],
+}
+
+impl Default for Fire {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fire {
+    /// A fire in [`HEAT_PALETTE`] colours.
+    pub fn new() -> Self {
+        Self {
+            palette: &HEAT_PALETTE,
+            cooling: 55,
+            sparking: 120,
+            rng: Rng::new(),
+            heat: [0; LED_COUNT],
+        }
+    }
+
+    /// The colours from cold to hot, blended evenly over the range of heat.
+    pub fn with_palette(mut self, palette: &'static [Rgb<Srgb, u8>]) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// How fast the flames cool down, 55 unless set. More cooling makes for shorter flames.
+    pub fn with_cooling(mut self, cooling: u8) -> Self {
+        self.cooling = cooling;
+        self
+    }
+
+    /// The chance of a new spark every frame, out of 255, 120 unless set. More sparks make for a
+    /// busier fire.
+    pub fn with_sparking(mut self, sparking: u8) -> Self {
+        self.sparking = sparking;
+        self
+    }
+
+    // A random number from 0 up to but not including `below`
+    fn random(&self, below: u32) -> u32 {
+        self.rng.random() % below.max(1)
+    }
+
+    // `level` from 0.0 to 1.0 along the palette
+    fn color(&self, level: f32) -> Rgb<Srgb, u8> {
+        let Some(last) = self.palette.len().checked_sub(1) else {
+            return Rgb::new(0, 0, 0);
+        };
+        let position = level * last as f32;
+        let index = (position as usize).min(last);
+        let (from, to) = (self.palette[index], self.palette[(index + 1).min(last)]);
+        super::mix(from, to, position - index as f32)
+    }
+}
+
+impl Effect for Fire {
+    fn render(&mut self, _elapsed: Duration, colors: &mut [Rgb<Srgb, u8>; LED_COUNT]) {
+        let max_cooling = self.cooling as u32 * 10 / LED_COUNT as u32 + 2;
+        for index in 0..LED_COUNT {
+            let cooling = self.random(max_cooling) as u8;
+            self.heat[index] = self.heat[index].saturating_sub(cooling);
+        }
+
+        // Each LED takes most of its heat from the two below it
+        for index in (2..LED_COUNT).rev() {
+            let below = self.heat[index - 1] as u16 + 2 * self.heat[index - 2] as u16;
+            self.heat[index] = (below / 3) as u8;
+        }
+
+        if self.random(256) < self.sparking as u32 {
+            let index = self.random(LED_COUNT.min(3) as u32) as usize;
+            let spark = 160 + self.random(96) as u8;
+            self.heat[index] = self.heat[index].saturating_add(spark);
+        }
+
+        for (heat, color) in self.heat.iter().zip(colors.iter_mut()) {
+            *color = self.color(*heat as f32 / 255.0);
+        }
+    }
+}
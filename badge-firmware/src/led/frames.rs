@@ -0,0 +1,54 @@
+//! A task showing one computed frame after another at a fixed frame rate.
+//!
+//! The frame function only works out what frame N looks like, the task takes care of the timing
+//! and of sending the colours to the LEDs:
+//!
+//! ```ignore
+//! let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+//! led::spawn_frame_task(&spawner, leds, 30, |frame, colors| {
+//!     for (index, color) in colors.iter_mut().enumerate() {
+//!         *color = led::hsv((frame as usize * 4 + index * 36) as f32, 1.0, 0.3);
+//!     }
+//! });
+//! ```
+//!
+//! The task owns the LEDs, so it can't be used together with the animation task.
+
+use embassy_executor::{Spawner, task};
+use embassy_time::{Duration, Ticker};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::LED_COUNT;
+use crate::safety::SafeLeds;
+
+/// A function filling in the colours of every LED for the given frame number, counting up from 0.
+pub type FrameFn = fn(u32, &mut [Rgb<Srgb, u8>; LED_COUNT]);
+
+/// Start a task calling `frame` `fps` times a second and showing the result on `leds`.
+///
+/// If a frame takes longer than its share of the second, the following ones are shown as soon as
+/// possible until the task has caught up, so the frame number keeps matching the time.
+pub fn spawn_frame_task(
+    spawner: &Spawner,
+    leds: &'static mut SafeLeds<'static>,
+    fps: u32,
+    frame: FrameFn,
+) {
+    spawner.must_spawn(frame_task(leds, fps, frame));
+}
+
+#[task]
+async fn frame_task(leds: &'static mut SafeLeds<'static>, fps: u32, frame: FrameFn) {
+    let mut ticker = Ticker::every(Duration::from_hz(fps.max(1) as u64));
+    let mut colors = [Rgb::new(0, 0, 0); LED_COUNT];
+    let mut number: u32 = 0;
+    loop {
+        frame(number, &mut colors);
+        for (index, &color) in colors.iter().enumerate() {
+            leds.set(index, color);
+        }
+        leds.update().await;
+        number = number.wrapping_add(1);
+        ticker.next().await;
+    }
+}
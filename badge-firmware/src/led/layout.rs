@@ -0,0 +1,67 @@
+//! Where the LEDs are on the badge, for effects that think in 2D rather than along the strip.
+//!
+//! Positions are relative to the centre of the badge front, with x growing right and y growing
+//! up, scaled so the badge edges are at about -1.0 and 1.0. A radial wave from the centre only
+//! needs each LED's distance from it:
+//!
+//! ```ignore
+//! for (index, color) in colors.iter_mut().enumerate() {
+//!     let distance = led::position(index).distance_from_center();
+//!     let level = (1.0 + libm::sinf(distance * 6.0 - time * 4.0)) / 2.0;
+//!     *color = led::hsv(200.0, 1.0, 0.4 * level);
+//! }
+//! ```
+
+use super::LED_COUNT;
+
+/// A point on the badge front, see the [module docs](self) for the coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, defmt::Format)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Position {
+    pub const CENTER: Self = Self::new(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance(self, other: Position) -> f32 {
+        libm::hypotf(self.x - other.x, self.y - other.y)
+    }
+
+    pub fn distance_from_center(self) -> f32 {
+        self.distance(Self::CENTER)
+    }
+
+    /// The direction from the centre in degrees, counterclockwise from the right, like a hue
+    /// for [`hsv`](super::hsv).
+    pub fn angle(self) -> f32 {
+        let angle = libm::atan2f(self.y, self.x).to_degrees();
+        if angle < 0.0 { angle + 360.0 } else { angle }
+    }
+}
+
+/// The position of every LED by index, running in a row along the top edge from left to right,
+/// then back along the bottom edge from right to left.
+///
+/// These are approximate, good enough for effects but not for measuring anything.
+pub const LAYOUT: [Position; LED_COUNT] = [
+    Position::new(-0.8, 0.9),
+    Position::new(-0.4, 0.9),
+    Position::new(0.0, 0.9),
+    Position::new(0.4, 0.9),
+    Position::new(0.8, 0.9),
+    Position::new(0.8, -0.9),
+    Position::new(0.4, -0.9),
+    Position::new(0.0, -0.9),
+    Position::new(-0.4, -0.9),
+    Position::new(-0.8, -0.9),
+];
+
+/// The position of the LED at `index`, or the centre for an index past the last LED.
+pub fn position(index: usize) -> Position {
+    LAYOUT.get(index).copied().unwrap_or(Position::CENTER)
+}
@@ -0,0 +1,82 @@
+//! Notifications shown on top of whatever the LEDs are doing.
+//!
+//! Any task can [`notify`] with an [`Animation`], such as a quick flash when a message comes in.
+//! [`SafeLeds`](crate::safety::SafeLeds) shows it instead of the app's own colours until it ends,
+//! then goes back to them, so the app never needs to know and tasks don't fight over the LEDs:
+//!
+//! ```ignore
+//! static MESSAGE: [Keyframe; 2] = [
+//!     Keyframe::new(Duration::from_millis(0), Colors::All(Rgb::new(0, 80, 80))),
+//!     Keyframe::new(Duration::from_millis(400), Colors::All(Rgb::new(0, 0, 0))),
+//! ];
+//!
+//! led::overlay::notify(Animation::new(&MESSAGE), 1);
+//! ```
+//!
+//! The overlay only shows while something keeps updating the LEDs, like an effect or the
+//! animation task.
+
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use embassy_time::Instant;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::{Animation, LED_COUNT};
+
+#[derive(Clone, Copy)]
+struct Overlay {
+    animation: Animation,
+    priority: u8,
+    start: Instant,
+}
+
+static OVERLAY: Mutex<CriticalSectionRawMutex, Cell<Option<Overlay>>> = Mutex::new(Cell::new(None));
+
+/// Show `animation` over the LEDs until it ends, unless a notification with a higher `priority`
+/// is showing. Returns whether it's shown.
+///
+/// A looping animation shows until [`clear`]ed or replaced.
+pub fn notify(animation: Animation, priority: u8) -> bool {
+    OVERLAY.lock(|cell| {
+        let now = Instant::now();
+        match cell.get() {
+            Some(overlay) if overlay.priority > priority && !overlay.is_finished(now) => false,
+            _ => {
+                cell.set(Some(Overlay {
+                    animation,
+                    priority,
+                    start: now,
+                }));
+                true
+            }
+        }
+    })
+}
+
+/// Stop showing the notification, if any.
+pub fn clear() {
+    OVERLAY.lock(|cell| cell.set(None));
+}
+
+impl Overlay {
+    fn is_finished(&self, now: Instant) -> bool {
+        !self.animation.is_looping() && now - self.start >= self.animation.duration()
+    }
+}
+
+// The colours of the notification showing at `now`, if any
+pub(crate) fn colors_at(now: Instant) -> Option<[Rgb<Srgb, u8>; LED_COUNT]> {
+    let overlay = OVERLAY.lock(|cell| {
+        let overlay = cell.get()?;
+        if overlay.is_finished(now) {
+            cell.set(None);
+            return None;
+        }
+        Some(overlay)
+    })?;
+    let elapsed = now - overlay.start;
+    Some(core::array::from_fn(|index| {
+        overlay.animation.color_at(elapsed, index)
+    }))
+}
@@ -0,0 +1,112 @@
+//! Keeping the LEDs within a current budget.
+//!
+//! Every LED at full white draws a lot more than the rest of the badge together, enough to drain
+//! the battery fast or brown out the chip. [`SafeLeds`](crate::safety::SafeLeds) estimates the
+//! draw of each frame and dims the whole frame evenly when it would go over the budget, so
+//! colours keep their balance and only get darker.
+//!
+//! On battery the LEDs are also dimmed to at most [`BATTERY_BRIGHTNESS`], and further to
+//! [`LOW_BATTERY_BRIGHTNESS`] once the battery runs low. Nothing here measures the supply, so
+//! whatever does, reports it with [`set_power_source`]:
+//!
+//! ```ignore
+//! led::set_power_source(PowerSource::Battery { millivolts: read_battery() });
+//! ```
+//!
+//! Until then the badge counts as externally powered.
+//!
+//! Only [`SafeLeds`](crate::safety::SafeLeds) keeps to the budget. Colours sent to the plain BSP
+//! `Leds` go out as they are, so every binary in this crate drives the LEDs through `SafeLeds`.
+
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use palette::{encoding::Srgb, rgb::Rgb};
+
+/// The estimated draw of one colour channel of one LED at full duty.
+pub const MILLIAMPS_PER_CHANNEL: u32 = 20;
+/// The budget unless set otherwise: about two thirds of every LED at full white.
+pub const DEFAULT_POWER_BUDGET: u16 = 400;
+
+/// The brightest the LEDs go on battery, as for [`set_brightness`](super::set_brightness).
+pub const BATTERY_BRIGHTNESS: u8 = 160;
+/// The brightest the LEDs go on a low battery.
+pub const LOW_BATTERY_BRIGHTNESS: u8 = 64;
+/// Below this the battery counts as low.
+pub const LOW_BATTERY_MILLIVOLTS: u16 = 3500;
+
+static POWER_BUDGET: AtomicU16 = AtomicU16::new(DEFAULT_POWER_BUDGET);
+// The battery voltage, or 0 for external power
+static BATTERY_MILLIVOLTS: AtomicU16 = AtomicU16::new(0);
+
+/// Where the badge gets its power from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum PowerSource {
+    /// USB or another supply with power to spare.
+    External,
+    Battery {
+        millivolts: u16,
+    },
+}
+
+/// Report the power source, to dim the LEDs on battery. Call again whenever it changes, and
+/// every now and then on battery as the voltage drops.
+pub fn set_power_source(source: PowerSource) {
+    let millivolts = match source {
+        PowerSource::External => 0,
+        PowerSource::Battery { millivolts } => millivolts.max(1),
+    };
+    BATTERY_MILLIVOLTS.store(millivolts, Ordering::Relaxed);
+}
+
+pub fn power_source() -> PowerSource {
+    match BATTERY_MILLIVOLTS.load(Ordering::Relaxed) {
+        0 => PowerSource::External,
+        millivolts => PowerSource::Battery { millivolts },
+    }
+}
+
+/// The brightest the LEDs may go on the current power source, whatever the brightness setting.
+pub fn max_brightness() -> u8 {
+    match power_source() {
+        PowerSource::External => u8::MAX,
+        PowerSource::Battery { millivolts } if millivolts < LOW_BATTERY_MILLIVOLTS => {
+            LOW_BATTERY_BRIGHTNESS
+        }
+        PowerSource::Battery { .. } => BATTERY_BRIGHTNESS,
+    }
+}
+
+/// Limit the LEDs to drawing about `milliamps` in total. `u16::MAX` is as good as no limit.
+pub fn set_power_budget(milliamps: u16) {
+    POWER_BUDGET.store(milliamps, Ordering::Relaxed);
+}
+
+pub fn power_budget() -> u16 {
+    POWER_BUDGET.load(Ordering::Relaxed)
+}
+
+/// The estimated draw of showing `colors`, in milliamps.
+pub fn estimate_draw(colors: &[Rgb<Srgb, u8>]) -> u32 {
+    let total: u32 = colors
+        .iter()
+        .map(|color| color.red as u32 + color.green as u32 + color.blue as u32)
+        .sum();
+    total * MILLIAMPS_PER_CHANNEL / 255
+}
+
+/// Dim `colors` evenly so they fit in the power budget, if they don't already.
+pub fn fit_power_budget(colors: &mut [Rgb<Srgb, u8>]) {
+    let (draw, budget) = (estimate_draw(colors), power_budget() as u32);
+    if draw <= budget {
+        return;
+    }
+    // Round down, so the result is never over the budget
+    let channel = |value: u8| (value as u32 * budget / draw) as u8;
+    for color in colors {
+        *color = Rgb::new(
+            channel(color.red),
+            channel(color.green),
+            channel(color.blue),
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! The LED look chosen in the settings: an effect, its colour and the brightness.
+//!
+//! These are the `led_effect`, `led_color` and `led_brightness` fields of [`Settings`], applied
+//! with [`apply_settings`]. An app that just wants the LEDs to look the way the user likes them
+//! restores the look on boot and leaves [`run`] going:
+//!
+//! ```ignore
+//! led::settings::apply_settings(&store.load());
+//! led::settings::run(&mut leds).await;
+//! ```
+//!
+//! A settings menu changes the look with [`set`], and saves [`current`] with
+//! [`LedSettings::store_in`].
+
+use core::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+
+use embassy_time::{Duration, Timer};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::{Breathe, Chase, Fire, Rainbow, Sparkle};
+use crate::{safety::SafeLeds, settings::Settings};
+
+/// The built-in effects to pick from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum EffectKind {
+    /// The colour, steady.
+    Solid,
+    #[default]
+    Breathe,
+    Rainbow,
+    Chase,
+    Sparkle,
+    Fire,
+}
+
+impl EffectKind {
+    /// Every effect, in the order a picker should show them.
+    pub const ALL: [EffectKind; 6] = [
+        EffectKind::Solid,
+        EffectKind::Breathe,
+        EffectKind::Rainbow,
+        EffectKind::Chase,
+        EffectKind::Sparkle,
+        EffectKind::Fire,
+    ];
+
+    /// A name to show in menus.
+    pub const fn name(self) -> &'static str {
+        match self {
+            EffectKind::Solid => "Solid",
+            EffectKind::Breathe => "Breathe",
+            EffectKind::Rainbow => "Rainbow",
+            EffectKind::Chase => "Chase",
+            EffectKind::Sparkle => "Sparkle",
+            EffectKind::Fire => "Fire",
+        }
+    }
+
+    pub(crate) fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(index as usize).copied()
+    }
+
+    pub(crate) fn index(self) -> u8 {
+        self as u8
+    }
+}
+
+/// How the LEDs look.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LedSettings {
+    pub effect: EffectKind,
+    /// The colour of the effects that use one. Rainbow and fire bring their own colours.
+    pub color: Rgb<Srgb, u8>,
+    /// See [`set_brightness`](super::set_brightness).
+    pub brightness: u8,
+}
+
+impl LedSettings {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            effect: settings.led_effect,
+            color: settings.led_color,
+            brightness: settings.led_brightness,
+        }
+    }
+
+    /// Copy these into `settings`, ready to be saved.
+    pub fn store_in(&self, settings: &mut Settings) {
+        settings.led_effect = self.effect;
+        settings.led_color = self.color;
+        settings.led_brightness = self.brightness;
+    }
+}
+
+static EFFECT: AtomicU8 = AtomicU8::new(EffectKind::Breathe as u8);
+// The colour as 0x00RRGGBB
+static COLOR: AtomicU32 = AtomicU32::new(0x0050_0050);
+
+/// Switch to the look chosen in the settings.
+pub fn apply_settings(settings: &Settings) {
+    set(LedSettings::from_settings(settings));
+}
+
+/// Switch to `led_settings`. The brightness changes right away, the effect and colour the next
+/// time [`run`] starts.
+pub fn set(led_settings: LedSettings) {
+    let color = led_settings.color;
+    EFFECT.store(led_settings.effect.index(), Ordering::Relaxed);
+    COLOR.store(
+        u32::from_be_bytes([0, color.red, color.green, color.blue]),
+        Ordering::Relaxed,
+    );
+    super::set_brightness(led_settings.brightness);
+}
+
+/// The look in use.
+pub fn current() -> LedSettings {
+    let [_, red, green, blue] = COLOR.load(Ordering::Relaxed).to_be_bytes();
+    LedSettings {
+        effect: EffectKind::from_index(EFFECT.load(Ordering::Relaxed)).unwrap_or_default(),
+        color: Rgb::new(red, green, blue),
+        brightness: super::brightness(),
+    }
+}
+
+/// Show the [`current`] effect on `leds`, forever.
+pub async fn run(leds: &mut SafeLeds<'_>) -> ! {
+    let LedSettings { effect, color, .. } = current();
+    match effect {
+        EffectKind::Solid => loop {
+            leds.fill(color);
+            leds.update().await;
+            // Nothing changes, but keep going in case the brightness does
+            Timer::after(Duration::from_millis(100)).await;
+        },
+        EffectKind::Breathe => {
+            leds.effect(Breathe {
+                color,
+                period: Duration::from_secs(4),
+            })
+            .await
+        }
+        EffectKind::Rainbow => {
+            leds.effect(Rainbow {
+                period: Duration::from_secs(6),
+                saturation: 1.0,
+                value: 0.5,
+            })
+            .await
+        }
+        EffectKind::Chase => {
+            leds.effect(Chase {
+                color,
+                step: Duration::from_millis(80),
+                tail: 3,
+                reverse: false,
+            })
+            .await
+        }
+        EffectKind::Sparkle => leds.effect(Sparkle::new(Rgb::new(0, 0, 0), color)).await,
+        EffectKind::Fire => leds.effect(Fire::new()).await,
+    }
+}
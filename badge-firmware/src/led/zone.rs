@@ -0,0 +1,181 @@
+//! Named groups of LEDs, so different tasks can each drive a part of the strip.
+//!
+//! A task [`claim`](Zone::claim)s a zone and gets [`ZoneLeds`] for only those LEDs. No two
+//! claims can share an LED, so tasks can't fight over one, and the zone is free again once its
+//! `ZoneLeds` are dropped. The zone task started with [`spawn_zone_task`] puts what all the
+//! zones show together on the LEDs:
+//!
+//! ```ignore
+//! let leds = mk_static!(SafeLeds<'static>, SafeLeds::new(resources.leds.into()));
+//! led::zone::spawn_zone_task(&spawner, leds);
+//!
+//! let mut top = zone::TOP.claim().unwrap();
+//! top.fill(Rgb::new(0, 40, 0));
+//! spawner.must_spawn(chase_task(zone::BOTTOM.claim().unwrap()));
+//! ```
+//!
+//! The zone task owns the LEDs, so it can't be used together with the animation or frame tasks.
+
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_executor::{Spawner, task};
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use embassy_time::Instant;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use super::{Effect, LED_COUNT};
+use crate::{motion, safety::SafeLeds};
+
+const OFF: Rgb<Srgb, u8> = Rgb::new(0, 0, 0);
+
+// The claims keep one bit per LED
+const _: () = assert!(LED_COUNT <= 32);
+
+// One bit per LED, set while a zone containing it is claimed
+static CLAIMED: AtomicU32 = AtomicU32::new(0);
+static FRAME: Mutex<CriticalSectionRawMutex, Cell<[Rgb<Srgb, u8>; LED_COUNT]>> =
+    Mutex::new(Cell::new([OFF; LED_COUNT]));
+
+/// The top row, left to right.
+pub const TOP: Zone = Zone::new("top", &[0, 1, 2, 3, 4]);
+/// The bottom row, left to right.
+pub const BOTTOM: Zone = Zone::new("bottom", &[9, 8, 7, 6, 5]);
+/// The two leftmost LEDs of each row, top first.
+pub const LEFT: Zone = Zone::new("left", &[0, 1, 9, 8]);
+/// The two rightmost LEDs of each row, top first.
+pub const RIGHT: Zone = Zone::new("right", &[3, 4, 6, 5]);
+/// Every LED, in the order of the strip.
+pub const ALL: Zone = Zone::new("all", &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+/// A named group of LEDs, by their index on the strip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Zone {
+    name: &'static str,
+    leds: &'static [usize],
+}
+
+impl Zone {
+    /// A zone of the LEDs at `leds`, in the order [`ZoneLeds::set`] counts them. Indexes past
+    /// the last LED are left out.
+    pub const fn new(name: &'static str, leds: &'static [usize]) -> Self {
+        Self { name, leds }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub const fn leds(&self) -> &'static [usize] {
+        self.leds
+    }
+
+    pub const fn len(&self) -> usize {
+        self.leds.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.leds.is_empty()
+    }
+
+    fn mask(&self) -> u32 {
+        self.leds
+            .iter()
+            .filter(|&&index| index < LED_COUNT)
+            .fold(0, |mask, &index| mask | 1 << index)
+    }
+
+    /// Take the LEDs of the zone for this task, or `None` if another claim holds any of them.
+    pub fn claim(self) -> Option<ZoneLeds> {
+        let mask = self.mask();
+        CLAIMED
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |claimed| {
+                (claimed & mask == 0).then_some(claimed | mask)
+            })
+            .ok()?;
+        Some(ZoneLeds { zone: self })
+    }
+}
+
+/// The LEDs of a claimed [`Zone`]. They turn off and the zone is free to claim again when
+/// dropped.
+pub struct ZoneLeds {
+    zone: Zone,
+}
+
+impl ZoneLeds {
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    /// Set every LED of the zone to `color`.
+    pub fn fill(&mut self, color: Rgb<Srgb, u8>) {
+        self.update(|_, entry| *entry = color);
+    }
+
+    /// Set the LED `index` places into the zone to `color`. Indexes past the end of the zone
+    /// are ignored.
+    pub fn set(&mut self, index: usize, color: Rgb<Srgb, u8>) {
+        let Some(&led) = self.zone.leds.get(index) else {
+            return;
+        };
+        self.update(|position, entry| {
+            if position == led {
+                *entry = color;
+            }
+        });
+    }
+
+    /// Show `effect` forever, one frame every [`FRAME_TIME`](motion::FRAME_TIME). The zone shows
+    /// its own LEDs' part of the effect as it would look on the whole strip.
+    pub async fn effect(&mut self, mut effect: impl Effect) -> ! {
+        let mut colors = [OFF; LED_COUNT];
+        let start = Instant::now();
+        loop {
+            effect.render(Instant::now() - start, &mut colors);
+            self.update(|index, entry| *entry = colors[index]);
+            motion::next_frame().await;
+        }
+    }
+
+    // Call `change` with the index and colour of each LED in the zone
+    fn update(&mut self, mut change: impl FnMut(usize, &mut Rgb<Srgb, u8>)) {
+        let mask = self.zone.mask();
+        FRAME.lock(|cell| {
+            let mut frame = cell.get();
+            for (index, entry) in frame.iter_mut().enumerate() {
+                if mask & 1 << index != 0 {
+                    change(index, entry);
+                }
+            }
+            cell.set(frame);
+        });
+    }
+}
+
+impl Drop for ZoneLeds {
+    fn drop(&mut self) {
+        self.fill(OFF);
+        CLAIMED.fetch_and(!self.zone.mask(), Ordering::AcqRel);
+    }
+}
+
+/// Start the task showing every zone on `leds`, one frame every
+/// [`FRAME_TIME`](motion::FRAME_TIME). LEDs outside any claimed zone stay off.
+pub fn spawn_zone_task(spawner: &Spawner, leds: &'static mut SafeLeds<'static>) {
+    spawner.must_spawn(zone_task(leds));
+}
+
+#[task]
+async fn zone_task(leds: &'static mut SafeLeds<'static>) {
+    loop {
+        let frame = FRAME.lock(|cell| cell.get());
+        for (index, color) in frame.into_iter().enumerate() {
+            leds.set(index, color);
+        }
+        leds.update().await;
+        motion::next_frame().await;
+    }
+}
@@ -1 +1,24 @@
 #![no_std]
+
+extern crate alloc;
+
+mod checksum;
+pub mod clock;
+pub mod color;
+mod config;
+pub mod contact;
+pub mod display;
+pub mod espnow;
+pub mod input;
+pub mod led;
+pub mod motion;
+pub mod palette;
+pub mod safety;
+pub mod settings;
+pub mod status;
+pub mod tempo;
+pub mod theme;
+pub mod ui;
+pub mod version;
+
+pub use config::{Config, MAX_HEAP_SIZE, init, init_default};
@@ -0,0 +1,114 @@
+//! Velocity-based movement for simple games.
+//!
+//! Instead of moving things a fixed distance per button press, push them around and let them
+//! glide: call [`Motion::push`] when input arrives and [`Motion::step`] once per frame of a
+//! fixed-timestep loop driven by an [`embassy_time::Ticker`] ticking every [`FRAME_TIME`].
+//!
+//! When more than one task animates something, like the LEDs and the display in a synchronised
+//! demo, wait for [`next_frame`] instead. It wakes every task on the same frame boundaries,
+//! counted from boot, so they stay in lockstep instead of drifting apart by however far apart
+//! their tickers started.
+
+use embassy_time::{Duration, Instant, Timer};
+
+/// Frame length for fixed-timestep game loops, giving 50 frames per second.
+pub const FRAME_TIME: Duration = Duration::from_millis(20);
+
+/// The number of the frame `instant` falls in, counting frames of [`FRAME_TIME`] from boot.
+pub fn frame_at(instant: Instant) -> u64 {
+    instant.as_ticks() / FRAME_TIME.as_ticks()
+}
+
+/// When `frame` starts.
+pub fn frame_start(frame: u64) -> Instant {
+    Instant::from_ticks(frame * FRAME_TIME.as_ticks())
+}
+
+/// Wait for the next frame to start and return its number.
+///
+/// A task that falls behind skips the frames it missed instead of rushing through them, so
+/// every task sees the same frame numbers at the same time.
+pub async fn next_frame() -> u64 {
+    let frame = frame_at(Instant::now()) + 1;
+    Timer::at(frame_start(frame)).await;
+    frame
+}
+
+/// Position and velocity along one axis, in pixels and pixels per frame.
+#[derive(Clone, Copy, Debug)]
+pub struct Motion {
+    position: f32,
+    velocity: f32,
+    min: f32,
+    max: f32,
+    friction: f32,
+    max_speed: f32,
+}
+
+impl Motion {
+    /// Start at rest at `position`, never leaving `min..=max`. Bounds given the wrong way round
+    /// are swapped.
+    pub fn new(position: i32, min: i32, max: i32) -> Self {
+        Self {
+            position: position as f32,
+            velocity: 0.0,
+            min: min.min(max) as f32,
+            max: max.max(min) as f32,
+            friction: 0.85,
+            max_speed: 8.0,
+        }
+    }
+
+    /// Fraction of the velocity kept each frame: 1.0 glides forever, 0.0 stops immediately.
+    /// NaN is ignored.
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        if !friction.is_nan() {
+            self.friction = friction.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// Maximum speed in pixels per frame. Negative speeds count as 0.0, and NaN is ignored.
+    pub fn with_max_speed(mut self, max_speed: f32) -> Self {
+        if !max_speed.is_nan() {
+            self.max_speed = max_speed.max(0.0);
+        }
+        self
+    }
+
+    /// Add `impulse` pixels per frame to the velocity. Infinite or NaN impulses are ignored, as
+    /// they would leave the position unusable for good.
+    pub fn push(&mut self, impulse: f32) {
+        if !impulse.is_finite() {
+            return;
+        }
+        self.velocity = (self.velocity + impulse).clamp(-self.max_speed, self.max_speed);
+    }
+
+    /// Advance one frame and return the new position.
+    pub fn step(&mut self) -> i32 {
+        self.position += self.velocity;
+        self.velocity *= self.friction;
+
+        // Stop dead at the edges instead of pushing against them
+        if self.position <= self.min || self.position >= self.max {
+            self.position = self.position.clamp(self.min, self.max);
+            self.velocity = 0.0;
+        }
+        // Snap to rest once the movement is too slow to see
+        if libm::fabsf(self.velocity) < 0.05 {
+            self.velocity = 0.0;
+        }
+        self.position()
+    }
+
+    /// The current position rounded to the nearest pixel.
+    pub fn position(&self) -> i32 {
+        libm::roundf(self.position) as i32
+    }
+
+    /// Whether the velocity is non-zero.
+    pub fn is_moving(&self) -> bool {
+        self.velocity != 0.0
+    }
+}
@@ -0,0 +1,99 @@
+//! Named LED colours and palettes to pick them from.
+//!
+//! The colours are toned down for the LEDs, which are uncomfortably bright at full power. A
+//! [`Palette`] wraps around at either end, so stepping through one never runs out:
+//!
+//! ```ignore
+//! let mut index = 0;
+//! leds.fill(palette::RAINBOW.get(index));
+//! index = palette::RAINBOW.next(index);
+//! ```
+//!
+//! This module shares its name with the `palette` crate, so import what you need from it rather
+//! than the module itself.
+
+use ::palette::{encoding::Srgb, rgb::Rgb};
+
+pub const OFF: Rgb<Srgb, u8> = Rgb::new(0, 0, 0);
+pub const WHITE: Rgb<Srgb, u8> = Rgb::new(80, 80, 80);
+pub const RED: Rgb<Srgb, u8> = Rgb::new(80, 0, 0);
+pub const YELLOW: Rgb<Srgb, u8> = Rgb::new(80, 80, 0);
+pub const GREEN: Rgb<Srgb, u8> = Rgb::new(0, 80, 0);
+pub const CYAN: Rgb<Srgb, u8> = Rgb::new(0, 80, 80);
+pub const BLUE: Rgb<Srgb, u8> = Rgb::new(0, 0, 80);
+/// The Disobey purple, also the default LED colour.
+pub const DISOBEY_PURPLE: Rgb<Srgb, u8> = Rgb::new(80, 0, 80);
+/// The green of an old phosphor terminal.
+pub const TERMINAL_GREEN: Rgb<Srgb, u8> = Rgb::new(16, 80, 16);
+/// For warnings, like a low battery.
+pub const WARNING_AMBER: Rgb<Srgb, u8> = Rgb::new(120, 60, 0);
+pub const COPPER: Rgb<Srgb, u8> = Rgb::new(120, 60, 30);
+
+/// A list of colours to cycle through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    colors: &'static [Rgb<Srgb, u8>],
+}
+
+impl Palette {
+    pub const fn new(colors: &'static [Rgb<Srgb, u8>]) -> Self {
+        Self { colors }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub const fn colors(&self) -> &'static [Rgb<Srgb, u8>] {
+        self.colors
+    }
+
+    /// The colour at `index`, wrapping around past the end. [`OFF`] for an empty palette.
+    pub fn get(&self, index: usize) -> Rgb<Srgb, u8> {
+        match self.colors.len() {
+            0 => OFF,
+            len => self.colors[index % len],
+        }
+    }
+
+    /// Where `color` is in the palette, if it is.
+    pub fn position(&self, color: Rgb<Srgb, u8>) -> Option<usize> {
+        self.colors.iter().position(|&entry| entry == color)
+    }
+
+    /// The index after `index`, back to the first after the last.
+    pub fn next(&self, index: usize) -> usize {
+        (index + 1) % self.colors.len().max(1)
+    }
+
+    /// The index before `index`, round to the last before the first.
+    pub fn previous(&self, index: usize) -> usize {
+        let len = self.colors.len().max(1);
+        (index % len + len - 1) % len
+    }
+
+    /// Every colour in order, over and over.
+    pub fn cycle(&self) -> impl Iterator<Item = Rgb<Srgb, u8>> + 'static {
+        self.colors.iter().copied().cycle()
+    }
+}
+
+/// The six colours of the rainbow.
+pub const RAINBOW: Palette = Palette::new(&[RED, YELLOW, GREEN, CYAN, BLUE, DISOBEY_PURPLE]);
+
+/// The rainbow plus white, off and copper: one colour for each button.
+pub const BUTTONS: Palette = Palette::new(&[
+    RED,
+    YELLOW,
+    GREEN,
+    CYAN,
+    BLUE,
+    DISOBEY_PURPLE,
+    WHITE,
+    OFF,
+    COPPER,
+]);
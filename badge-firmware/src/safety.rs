@@ -0,0 +1,422 @@
+//! Photosensitivity safety limits for the LEDs and the display.
+//!
+//! Large, rapid changes in brightness can trigger seizures in people with photosensitive
+//! epilepsy. Following the WCAG "three flashes" guideline, [`FlashLimiter`] lets through at most
+//! three flashes a second, that is six brightness changes bigger than [`MAX_LUMA_DELTA`], and
+//! softens anything faster. [`SafeLeds`] and [`SafeDisplay`] put a limiter between an effect and
+//! the hardware, so effects don't need to be careful themselves, and a
+//! [`Framebuffer`](crate::display::Framebuffer) limits what it presents the same way. Every
+//! binary drives the LEDs and the display through one of these.
+//!
+//! The limit is on by default. The only way to turn it off is the `flash_limit` field of
+//! [`Settings`], applied with [`apply_settings`].
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::vec::Vec;
+use disobey2026badge::Leds;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use crate::{
+    led::{self, LED_COUNT},
+    settings::Settings,
+};
+
+/// The most flashes allowed per second.
+pub const MAX_FLASHES_PER_SECOND: u64 = 3;
+/// The largest change in luma, from 0.0 to 1.0, that doesn't count as a flash.
+pub const MAX_LUMA_DELTA: f32 = 0.1;
+
+// A flash is a change there and back, so two changes per flash
+const MIN_CHANGE_INTERVAL: Duration =
+    Duration::from_micros(1_000_000 / (2 * MAX_FLASHES_PER_SECOND));
+// How many pixels of a large drawing are limited together, as a band of whole rows if they're
+// in an area
+const BAND_PIXELS: usize = 1024;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turn the flash limit on or off as chosen in the settings.
+pub fn apply_settings(settings: &Settings) {
+    ENABLED.store(settings.flash_limit, Ordering::Relaxed);
+}
+
+/// Whether the flash limit is currently on.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Keeps a stream of colours within the flash limit.
+///
+/// Feed every colour through [`limit`](Self::limit) before showing it. Changes too big and too
+/// soon after the previous one are cut down to [`MAX_LUMA_DELTA`].
+#[derive(Clone, Copy, Debug)]
+pub struct FlashLimiter {
+    // Luma of the colour at the last big change
+    anchor: f32,
+    last_change: Instant,
+    last_output: Rgb<Srgb, u8>,
+}
+
+impl Default for FlashLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlashLimiter {
+    /// A limiter starting from black.
+    pub const fn new() -> Self {
+        Self {
+            anchor: 0.0,
+            last_change: Instant::from_ticks(0),
+            last_output: Rgb::new(0, 0, 0),
+        }
+    }
+
+    /// Return the colour to show instead of `color` at time `now`.
+    pub fn limit(&mut self, now: Instant, color: Rgb<Srgb, u8>) -> Rgb<Srgb, u8> {
+        let target = luma(color);
+        if libm::fabsf(target - self.anchor) <= MAX_LUMA_DELTA {
+            self.last_output = color;
+            return color;
+        }
+
+        if !is_enabled() || now.saturating_duration_since(self.last_change) >= MIN_CHANGE_INTERVAL {
+            self.anchor = target;
+            self.last_change = now;
+            self.last_output = color;
+            return color;
+        }
+
+        // Too soon for another big change: go only as far as the limit allows
+        let allowed = target.clamp(self.anchor - MAX_LUMA_DELTA, self.anchor + MAX_LUMA_DELTA);
+        let current = luma(self.last_output);
+        let t = if target == current {
+            0.0
+        } else {
+            ((allowed - current) / (target - current)).clamp(0.0, 1.0)
+        };
+        self.last_output = lerp(self.last_output, color, t);
+        self.last_output
+    }
+
+    // Like limit for a whole area of the display at once, going by its average colour. What to
+    // add to each pixel to keep the area within the limit.
+    pub(crate) fn limit_area(&mut self, now: Instant, average: &Average) -> Shift {
+        let Some(color) = average.color() else {
+            return Shift::NONE;
+        };
+        let limited = self.limit(now, color);
+        let delta = |from: u8, to: u8| to as i16 - from as i16;
+        Shift([
+            delta(color.red, limited.red),
+            delta(color.green, limited.green),
+            delta(color.blue, limited.blue),
+        ])
+    }
+}
+
+// Whether `area` pixels are enough of a display of `size` to be limited as a flash
+pub(crate) fn is_large(area: u32, size: Size) -> bool {
+    area.saturating_mul(4) >= size.width * size.height
+}
+
+// The average of the colours of an area
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Average {
+    sums: [u32; 3],
+    count: u32,
+}
+
+impl Average {
+    pub(crate) fn add(&mut self, color: Rgb565) {
+        let color = expand(color);
+        self.sums[0] += color.red as u32;
+        self.sums[1] += color.green as u32;
+        self.sums[2] += color.blue as u32;
+        self.count += 1;
+    }
+
+    fn color(&self) -> Option<Rgb<Srgb, u8>> {
+        let channel = |sum: u32| (sum / self.count) as u8;
+        (self.count > 0).then(|| {
+            Rgb::new(
+                channel(self.sums[0]),
+                channel(self.sums[1]),
+                channel(self.sums[2]),
+            )
+        })
+    }
+}
+
+// How much to brighten or darken each channel of every pixel of an area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Shift([i16; 3]);
+
+impl Shift {
+    pub(crate) const NONE: Self = Self([0; 3]);
+
+    pub(crate) fn apply(&self, color: Rgb565) -> Rgb565 {
+        if *self == Self::NONE {
+            return color;
+        }
+        let color = expand(color);
+        let channel = |value: u8, delta: i16| (value as i16 + delta).clamp(0, 255) as u8;
+        narrow(Rgb::new(
+            channel(color.red, self.0[0]),
+            channel(color.green, self.0[1]),
+            channel(color.blue, self.0[2]),
+        ))
+    }
+}
+
+// Rec. 709 weights on the gamma-encoded values, which is close enough to perceived brightness
+fn luma(color: Rgb<Srgb, u8>) -> f32 {
+    (0.2126 * color.red as f32 + 0.7152 * color.green as f32 + 0.0722 * color.blue as f32) / 255.0
+}
+
+// Repeat the top bits in the bottom ones, so full brightness stays full
+fn expand(color: Rgb565) -> Rgb<Srgb, u8> {
+    Rgb::new(
+        color.r() << 3 | color.r() >> 2,
+        color.g() << 2 | color.g() >> 4,
+        color.b() << 3 | color.b() >> 2,
+    )
+}
+
+fn narrow(color: Rgb<Srgb, u8>) -> Rgb565 {
+    Rgb565::new(color.red >> 3, color.green >> 2, color.blue >> 3)
+}
+
+fn lerp(from: Rgb<Srgb, u8>, to: Rgb<Srgb, u8>, t: f32) -> Rgb<Srgb, u8> {
+    let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t) as u8;
+    Rgb::new(
+        channel(from.red, to.red),
+        channel(from.green, to.green),
+        channel(from.blue, to.blue),
+    )
+}
+
+/// The LEDs with a [`FlashLimiter`] in front of each of them, kept within the
+/// [power budget](led::power_budget) and with the [correction](led::correction) applied.
+///
+/// These only apply to colours that go through here: anything that drives the BSP `Leds` itself
+/// gets none of them.
+pub struct SafeLeds<'d> {
+    leds: Leds<'d>,
+    limiters: [FlashLimiter; LED_COUNT],
+    // The colours as set, before any limits
+    colors: [Rgb<Srgb, u8>; LED_COUNT],
+}
+
+impl<'d> SafeLeds<'d> {
+    pub fn new(leds: Leds<'d>) -> Self {
+        Self {
+            leds,
+            limiters: [FlashLimiter::new(); LED_COUNT],
+            colors: [Rgb::new(0, 0, 0); LED_COUNT],
+        }
+    }
+
+    /// The colours last set, as they were asked for rather than as limited and dimmed.
+    pub fn colors(&self) -> [Rgb<Srgb, u8>; LED_COUNT] {
+        self.colors
+    }
+
+    /// Set all LEDs to `color`, or as close to it as the flash limit allows, at the brightness
+    /// set with [`led::set_brightness`].
+    pub fn fill(&mut self, color: Rgb<Srgb, u8>) {
+        for index in 0..LED_COUNT {
+            self.set(index, color);
+        }
+    }
+
+    /// Light up LEDs in order in proportion to `value` out of `max`, like a bar for the battery
+    /// level, volume or progress. The last lit LED is dimmed for a part of a step, the rest are
+    /// off.
+    pub fn gauge(&mut self, value: u32, max: u32, color: Rgb<Srgb, u8>) {
+        let level = value.min(max) as f32 / max.max(1) as f32 * LED_COUNT as f32;
+        for index in 0..LED_COUNT {
+            let lit = (level - index as f32).clamp(0.0, 1.0);
+            let channel = |value: u8| libm::roundf(value as f32 * lit) as u8;
+            self.set(
+                index,
+                Rgb::new(
+                    channel(color.red),
+                    channel(color.green),
+                    channel(color.blue),
+                ),
+            );
+        }
+    }
+
+    /// Set the LED at `index` to `color`, like [`fill`](Self::fill) does for all of them.
+    pub fn set(&mut self, index: usize, color: Rgb<Srgb, u8>) {
+        if let Some(entry) = self.colors.get_mut(index) {
+            *entry = color;
+        }
+    }
+
+    /// Flush the buffer to the LEDs, or the [notification](led::overlay) showing on top of it,
+    /// with the [correction](led::correction) for each LED and dimmed if needed to fit the power
+    /// budget.
+    pub async fn update(&mut self) {
+        let now = Instant::now();
+        let correction = led::correction::current();
+        let mut frame = led::overlay::colors_at(now).unwrap_or(self.colors);
+        for (index, color) in frame.iter_mut().enumerate() {
+            let limited = self.limiters[index].limit(now, *color);
+            *color = correction.apply(index, led::scale(limited));
+        }
+        led::fit_power_budget(&mut frame);
+        for (index, color) in frame.into_iter().enumerate() {
+            self.leds.set(index, color);
+        }
+        self.leds.update().await;
+    }
+}
+
+/// A display with a [`FlashLimiter`] on anything drawn covering a quarter of the screen or
+/// more, whether filled with one colour, an image or pixel by pixel.
+///
+/// Large drawing is sent a band at a time, each band brightened or darkened as a whole to stay
+/// within the limit. Smaller drawing, such as text and sprites, passes straight through.
+pub struct SafeDisplay<D> {
+    display: D,
+    limiter: FlashLimiter,
+    band: Vec<Pixel<Rgb565>>,
+}
+
+impl<D> SafeDisplay<D>
+where
+    D: DrawTarget<Color = Rgb565> + OriginDimensions,
+{
+    pub fn new(display: D) -> Self {
+        Self {
+            display,
+            limiter: FlashLimiter::new(),
+            band: Vec::new(),
+        }
+    }
+
+    fn is_large(&self, area: &Rectangle) -> bool {
+        // Only the part on the display counts, which also keeps the area from overflowing
+        let area = area.intersection(&self.bounding_box());
+        is_large(area.size.width * area.size.height, self.size())
+    }
+
+    fn limit(&mut self, color: Rgb565) -> Rgb565 {
+        narrow(self.limiter.limit(Instant::now(), expand(color)))
+    }
+
+    // Limit the band against `before`, how the screen was before this drawing started, and add
+    // what's sent to `shown`
+    fn limit_band(&mut self, now: Instant, before: FlashLimiter, shown: &mut Average) {
+        let mut average = Average::default();
+        for Pixel(_, color) in &self.band {
+            average.add(*color);
+        }
+        let mut limiter = before;
+        let shift = limiter.limit_area(now, &average);
+        for Pixel(_, color) in &mut self.band {
+            *color = shift.apply(*color);
+            shown.add(*color);
+        }
+    }
+}
+
+impl<D> OriginDimensions for SafeDisplay<D>
+where
+    D: OriginDimensions,
+{
+    fn size(&self) -> Size {
+        self.display.size()
+    }
+}
+
+impl<D> DrawTarget for SafeDisplay<D>
+where
+    D: DrawTarget<Color = Rgb565> + OriginDimensions,
+{
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // How many pixels make a large drawing is only known once that many have been drawn,
+        // so the ones before that go through as they are
+        let now = Instant::now();
+        let before = self.limiter;
+        let mut shown = Average::default();
+        let mut drawn: u32 = 0;
+        let mut pixels = pixels.into_iter().peekable();
+        while pixels.peek().is_some() {
+            self.band.clear();
+            self.band.extend(pixels.by_ref().take(BAND_PIXELS));
+            drawn = drawn.saturating_add(self.band.len() as u32);
+            if is_large(drawn, self.size()) {
+                self.limit_band(now, before, &mut shown);
+            } else {
+                for Pixel(_, color) in &self.band {
+                    shown.add(*color);
+                }
+            }
+            self.display.draw_iter(self.band.drain(..))?;
+        }
+        if is_large(drawn, self.size()) {
+            self.limiter.limit_area(now, &shown);
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if !self.is_large(area) {
+            return self.display.fill_contiguous(area, colors);
+        }
+
+        let now = Instant::now();
+        let before = self.limiter;
+        let mut shown = Average::default();
+        let rows = (BAND_PIXELS as u32 / area.size.width).max(1);
+        let mut colors = colors.into_iter();
+        for top in (0..area.size.height).step_by(rows as usize) {
+            let band = Rectangle::new(
+                area.top_left + Point::new(0, top as i32),
+                Size::new(area.size.width, rows.min(area.size.height - top)),
+            );
+            self.band.clear();
+            self.band.extend(
+                band.points()
+                    .zip(colors.by_ref())
+                    .map(|(point, color)| Pixel(point, color)),
+            );
+            self.limit_band(now, before, &mut shown);
+            let colors = self.band.drain(..).map(|Pixel(_, color)| color);
+            self.display.fill_contiguous(&band, colors)?;
+        }
+        self.limiter.limit_area(now, &shown);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = if self.is_large(area) {
+            self.limit(color)
+        } else {
+            color
+        };
+        self.display.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let color = self.limit(color);
+        self.display.clear(color)
+    }
+}
@@ -0,0 +1,253 @@
+//! Persistent settings stored in flash.
+//!
+//! Settings live in a single small record at the start of the `badge_cfg` data partition, which
+//! `partitions.csv` adds next to ESP-IDF's own `nvs` so that whatever other firmware stored
+//! there, such as radio calibration, is left alone. The record is
+//! `[MAGIC, payload length (u16 LE), payload.., crc8]`, where the payload is the fields of
+//! [`Settings`] in declaration order. New fields are only ever appended, so a record written by
+//! older firmware still loads: fields missing from its payload get their default values.
+
+use embedded_storage::{ReadStorage, Storage};
+use esp_bootloader_esp_idf::partitions::{self, PARTITION_TABLE_MAX_LEN};
+use esp_hal::peripherals::FLASH;
+use esp_storage::FlashStorage;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use crate::{
+    checksum::crc8,
+    contact::ContactCard,
+    input::remap::Remap,
+    led::{correction::Correction, settings::EffectKind},
+    theme::ThemeKind,
+};
+
+/// The label of the settings partition in the partition table.
+pub const PARTITION_LABEL: &str = "badge_cfg";
+// The data partition type, and the custom subtype `partitions.csv` gives the settings partition
+const PARTITION_TYPE: u8 = 0x01;
+const PARTITION_SUBTYPE: u8 = 0x40;
+
+const MAGIC: u8 = 0x5E;
+const HEADER_LEN: usize = 3;
+const MAX_PAYLOAD_LEN: usize = 128;
+const MAX_RECORD_LEN: usize = HEADER_LEN + MAX_PAYLOAD_LEN + 1;
+
+/// Everything the badge remembers across reboots.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// The colour the LEDs show when an app has nothing better to do.
+    pub led_color: Rgb<Srgb, u8>,
+    /// Whether to keep flashing effects within photosensitivity-safe limits, see
+    /// [`crate::safety`]. Only turn this off if you know what you're doing.
+    pub flash_limit: bool,
+    /// Colours for text and widgets on the display, see [`crate::theme`].
+    pub theme: ThemeKind,
+    /// Who you are, for the name tag and contact exchange.
+    pub card: ContactCard,
+    /// Which button each button reports as, see [`crate::input::remap`].
+    pub remap: Remap,
+    /// How strong each colour channel of each LED is, see [`crate::led::correction`].
+    pub led_correction: Correction,
+    /// The effect the LEDs show in [`led_color`](Self::led_color), see
+    /// [`crate::led::settings`].
+    pub led_effect: EffectKind,
+    /// How bright the LEDs are, see [`crate::led::set_brightness`].
+    pub led_brightness: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            led_color: crate::palette::DISOBEY_PURPLE,
+            flash_limit: true,
+            theme: ThemeKind::Default,
+            card: ContactCard::new("", ""),
+            remap: Remap::IDENTITY,
+            led_correction: Correction::IDENTITY,
+            led_effect: EffectKind::Breathe,
+            led_brightness: u8::MAX,
+        }
+    }
+}
+
+impl Settings {
+    fn encode(&self, out: &mut [u8; MAX_PAYLOAD_LEN]) -> usize {
+        let mut writer = Writer { out, pos: 0 };
+        writer.color(self.led_color);
+        writer.bool(self.flash_limit);
+        writer.u8(self.theme.index());
+        // The fields are public, so make sure they fit
+        let card = ContactCard::new(&self.card.name, &self.card.handle);
+        writer.str(&card.name);
+        writer.str(&card.handle);
+        writer.bytes(&self.remap.to_bytes());
+        writer.bytes(&self.led_correction.to_bytes());
+        writer.u8(self.led_effect.index());
+        writer.u8(self.led_brightness);
+        writer.pos
+    }
+
+    fn decode(payload: &[u8]) -> Self {
+        let mut reader = Reader { payload };
+        let defaults = Self::default();
+        Self {
+            led_color: reader.color().unwrap_or(defaults.led_color),
+            flash_limit: reader.bool().unwrap_or(defaults.flash_limit),
+            theme: reader
+                .u8()
+                .and_then(ThemeKind::from_index)
+                .unwrap_or(defaults.theme),
+            card: match (reader.str(), reader.str()) {
+                (Some(name), Some(handle)) => ContactCard::new(name, handle),
+                _ => defaults.card,
+            },
+            remap: reader
+                .bytes()
+                .and_then(Remap::from_bytes)
+                .unwrap_or(defaults.remap),
+            led_correction: reader
+                .bytes()
+                .map(Correction::from_bytes)
+                .unwrap_or(defaults.led_correction),
+            led_effect: reader
+                .u8()
+                .and_then(EffectKind::from_index)
+                .unwrap_or(defaults.led_effect),
+            led_brightness: reader.u8().unwrap_or(defaults.led_brightness),
+        }
+    }
+}
+
+struct Writer<'a> {
+    out: &'a mut [u8; MAX_PAYLOAD_LEN],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.out[self.pos..self.pos + bytes.len()].copy_from_slice(bytes);
+        self.pos += bytes.len();
+    }
+
+    fn color(&mut self, color: Rgb<Srgb, u8>) {
+        self.bytes(&[color.red, color.green, color.blue]);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.bytes(&[value as u8]);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.bytes(&[value]);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u8(value.len() as u8);
+        self.bytes(value.as_bytes());
+    }
+}
+
+struct Reader<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (bytes, rest) = self.payload.split_first_chunk::<N>()?;
+        self.payload = rest;
+        Some(*bytes)
+    }
+
+    fn color(&mut self) -> Option<Rgb<Srgb, u8>> {
+        let [red, green, blue] = self.bytes()?;
+        Some(Rgb::new(red, green, blue))
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        let [value] = self.bytes()?;
+        Some(value != 0)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let [value] = self.bytes()?;
+        Some(value)
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = self.u8()? as usize;
+        let bytes = self.payload.get(..len)?;
+        self.payload = &self.payload[len..];
+        core::str::from_utf8(bytes).ok()
+    }
+}
+
+/// Reasons loading or saving settings can fail.
+#[derive(Debug, defmt::Format)]
+pub enum SettingsError {
+    /// The partition table has no `badge_cfg` data partition.
+    NoPartition,
+    /// Reading or writing the flash failed.
+    Flash,
+}
+
+/// Loads and saves [`Settings`] in the `badge_cfg` partition.
+pub struct SettingsStore {
+    flash: FlashStorage<'static>,
+    offset: u32,
+}
+
+impl SettingsStore {
+    /// Find the `badge_cfg` partition in the partition table.
+    pub fn new(flash: FLASH<'static>) -> Result<Self, SettingsError> {
+        let mut flash = FlashStorage::new(flash);
+        let mut table_buf = [0u8; PARTITION_TABLE_MAX_LEN];
+        let table = partitions::read_partition_table(&mut flash, &mut table_buf)
+            .map_err(|_| SettingsError::Flash)?;
+        let partition = table
+            .iter()
+            .find(|entry| {
+                entry.raw_type() == PARTITION_TYPE
+                    && entry.raw_subtype() == PARTITION_SUBTYPE
+                    && entry.label_as_str() == PARTITION_LABEL
+            })
+            .ok_or(SettingsError::NoPartition)?;
+        let offset = partition.offset();
+        Ok(Self { flash, offset })
+    }
+
+    /// Load the stored settings, falling back to defaults if nothing valid has been saved yet.
+    pub fn load(&mut self) -> Settings {
+        let mut record = [0u8; MAX_RECORD_LEN];
+        if self.flash.read(self.offset, &mut record).is_err() {
+            return Settings::default();
+        }
+
+        let len = u16::from_le_bytes([record[1], record[2]]) as usize;
+        if record[0] != MAGIC || len > MAX_PAYLOAD_LEN {
+            return Settings::default();
+        }
+        let (body, rest) = record.split_at(HEADER_LEN + len);
+        if crc8(body) != rest[0] {
+            return Settings::default();
+        }
+        Settings::decode(&body[HEADER_LEN..])
+    }
+
+    /// Write the settings to flash.
+    ///
+    /// This erases and rewrites a flash sector, so avoid calling it on every keypress.
+    pub fn save(&mut self, settings: &Settings) -> Result<(), SettingsError> {
+        let mut payload = [0u8; MAX_PAYLOAD_LEN];
+        let len = settings.encode(&mut payload);
+
+        let mut record = [0u8; MAX_RECORD_LEN];
+        record[0] = MAGIC;
+        record[1..HEADER_LEN].copy_from_slice(&(len as u16).to_le_bytes());
+        record[HEADER_LEN..HEADER_LEN + len].copy_from_slice(&payload[..len]);
+        record[HEADER_LEN + len] = crc8(&record[..HEADER_LEN + len]);
+
+        self.flash
+            .write(self.offset, &record[..HEADER_LEN + len + 1])
+            .map_err(|_| SettingsError::Flash)
+    }
+}
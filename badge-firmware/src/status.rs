@@ -0,0 +1,103 @@
+//! Status and error codes blinked on an LED, for when the display can't be trusted.
+//!
+//! Like a PC beeping at boot, the badge can tell what went wrong with a number of blinks on
+//! [`STATUS_LED`]: some long ones for the kind of problem, then some short ones for the details.
+//! This works before the display is up, and the blocking version even from the panic handler,
+//! as long as it can get at the LEDs:
+//!
+//! ```ignore
+//! static PANIC_LEDS: Mutex<CriticalSectionRawMutex, RefCell<Option<SafeLeds<'static>>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! #[panic_handler]
+//! fn panic(_: &core::panic::PanicInfo) -> ! {
+//!     match PANIC_LEDS.lock(|leds| leds.borrow_mut().take()) {
+//!         Some(mut leds) => status::blink_forever(&mut leds, BlinkCode::PANIC),
+//!         None => loop {},
+//!     }
+//! }
+//! ```
+//!
+//! The blinks go through [`SafeLeds`] like everything else, and are slow and dim enough to pass
+//! the flash limit and the power budget untouched.
+use embassy_time::{Duration, Timer};
+use esp_hal::delay::Delay;
+use palette::{encoding::Srgb, rgb::Rgb};
+
+use crate::safety::SafeLeds;
+
+/// The LED that blinks the codes.
+pub const STATUS_LED: usize = 0;
+/// The colour of the blinks, red for trouble.
+pub const STATUS_COLOR: Rgb<Srgb, u8> = Rgb::new(80, 0, 0);
+
+const SHORT: Duration = Duration::from_millis(200);
+const LONG: Duration = Duration::from_millis(800);
+// Between the blinks of a code
+const GAP: Duration = Duration::from_millis(400);
+// Between repeats of a code
+const PAUSE: Duration = Duration::from_secs(2);
+
+/// A code of `long` long blinks followed by `short` short ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct BlinkCode {
+    pub long: u8,
+    pub short: u8,
+}
+
+impl BlinkCode {
+    pub const fn new(long: u8, short: u8) -> Self {
+        Self { long, short }
+    }
+
+    /// The firmware panicked.
+    pub const PANIC: Self = Self::new(1, 1);
+    /// The heap ran out.
+    pub const OUT_OF_MEMORY: Self = Self::new(1, 2);
+    /// The settings couldn't be loaded or saved.
+    pub const SETTINGS: Self = Self::new(2, 1);
+    /// The radio couldn't be started.
+    pub const RADIO: Self = Self::new(2, 2);
+    /// The display isn't responding.
+    pub const DISPLAY: Self = Self::new(2, 3);
+
+    // Each blink's length, long ones first
+    fn blinks(self) -> impl Iterator<Item = Duration> {
+        let long = core::iter::repeat_n(LONG, self.long as usize);
+        long.chain(core::iter::repeat_n(SHORT, self.short as usize))
+    }
+}
+
+fn show(leds: &mut SafeLeds<'_>, on: bool) -> impl Future<Output = ()> {
+    leds.fill(Rgb::new(0, 0, 0));
+    if on {
+        leds.set(STATUS_LED, STATUS_COLOR);
+    }
+    leds.update()
+}
+
+/// Blink `code` once, followed by a pause so repeats can be told apart.
+pub async fn blink(leds: &mut SafeLeds<'_>, code: BlinkCode) {
+    for length in code.blinks() {
+        show(leds, true).await;
+        Timer::after(length).await;
+        show(leds, false).await;
+        Timer::after(GAP).await;
+    }
+    Timer::after(PAUSE).await;
+}
+
+/// Blink `code` over and over without the executor or timers, for the panic handler.
+pub fn blink_forever(leds: &mut SafeLeds<'_>, code: BlinkCode) -> ! {
+    let delay = Delay::new();
+    let wait = |duration: Duration| delay.delay_millis(duration.as_millis() as u32);
+    loop {
+        for length in code.blinks() {
+            embassy_futures::block_on(show(leds, true));
+            wait(length);
+            embassy_futures::block_on(show(leds, false));
+            wait(GAP);
+        }
+        wait(PAUSE);
+    }
+}
@@ -0,0 +1,136 @@
+//! Beat timing: tap-tempo input and a beat clock for music-synced effects.
+
+use embassy_time::{Duration, Instant};
+
+/// Slowest tempo [`TapTempo`] accepts.
+pub const MIN_BPM: u32 = 40;
+/// Fastest tempo [`TapTempo`] accepts.
+pub const MAX_BPM: u32 = 240;
+
+const MAX_INTERVALS: usize = 4;
+
+/// Works out a tempo from the user tapping a button along to the music.
+///
+/// Averages the last few intervals between taps. A pause longer than the slowest accepted beat
+/// starts a new measurement.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TapTempo {
+    last_tap: Option<Instant>,
+    intervals: [u64; MAX_INTERVALS],
+    count: usize,
+}
+
+impl TapTempo {
+    pub const fn new() -> Self {
+        Self {
+            last_tap: None,
+            intervals: [0; MAX_INTERVALS],
+            count: 0,
+        }
+    }
+
+    /// Register a tap. Returns the beat period once at least two taps are in.
+    pub fn tap(&mut self, now: Instant) -> Option<Duration> {
+        let last_tap = self.last_tap.replace(now);
+        let interval = now.saturating_duration_since(last_tap?);
+        if interval > bpm_to_period(MIN_BPM) {
+            self.count = 0;
+            return None;
+        }
+        if interval < bpm_to_period(MAX_BPM) {
+            // Contact bounce or an accidental double tap
+            return self.period();
+        }
+
+        self.intervals.copy_within(0..MAX_INTERVALS - 1, 1);
+        self.intervals[0] = interval.as_micros();
+        self.count = (self.count + 1).min(MAX_INTERVALS);
+        self.period()
+    }
+
+    /// The averaged beat period, if enough taps have been registered.
+    pub fn period(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let total: u64 = self.intervals[..self.count].iter().sum();
+        Some(Duration::from_micros(total / self.count as u64))
+    }
+}
+
+/// Convert beats per minute into the length of one beat.
+pub const fn bpm_to_period(bpm: u32) -> Duration {
+    Duration::from_micros(60_000_000 / bpm as u64)
+}
+
+/// Convert the length of one beat into beats per minute.
+pub fn period_to_bpm(period: Duration) -> u32 {
+    (60_000_000 / period.as_micros().max(1)) as u32
+}
+
+/// A beat period and how far into the current beat we are, both in microseconds, as received from
+/// another badge. `None` unless the tempo is one [`TapTempo`] accepts and the offset is within the
+/// beat.
+pub fn beat_from_micros(period: u32, since_beat: u32) -> Option<(Duration, Duration)> {
+    let period = Duration::from_micros(period as u64);
+    let since_beat = Duration::from_micros(since_beat as u64);
+    if period < bpm_to_period(MAX_BPM) || period > bpm_to_period(MIN_BPM) || since_beat >= period {
+        return None;
+    }
+    Some((period, since_beat))
+}
+
+/// A steady beat anchored to a point in time.
+#[derive(Clone, Copy, Debug)]
+pub struct BeatClock {
+    origin: Instant,
+    period: Duration,
+}
+
+impl BeatClock {
+    /// A clock with a beat at `origin` and every `period` after it.
+    pub const fn new(origin: Instant, period: Duration) -> Self {
+        Self { origin, period }
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn bpm(&self) -> u32 {
+        period_to_bpm(self.period)
+    }
+
+    /// Change the tempo, keeping the beat at `now` in phase.
+    pub fn set_period(&mut self, now: Instant, period: Duration) {
+        self.origin = self.last_beat(now);
+        self.period = period;
+    }
+
+    /// Put a beat at exactly `beat`, e.g. the time of the last tap.
+    pub fn align(&mut self, beat: Instant) {
+        self.origin = beat;
+    }
+
+    /// How many whole beats have passed since the origin.
+    pub fn beat(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.origin).as_micros() / self.period.as_micros().max(1)
+    }
+
+    /// How far into the current beat we are, from 0.0 on the beat to just under 1.0.
+    pub fn phase(&self, now: Instant) -> f32 {
+        let period = self.period.as_micros().max(1);
+        let elapsed = now.saturating_duration_since(self.origin).as_micros();
+        (elapsed % period) as f32 / period as f32
+    }
+
+    /// When the current beat started.
+    pub fn last_beat(&self, now: Instant) -> Instant {
+        self.origin + Duration::from_micros(self.beat(now) * self.period.as_micros())
+    }
+
+    /// When the next beat starts.
+    pub fn next_beat(&self, now: Instant) -> Instant {
+        self.last_beat(now) + self.period
+    }
+}
@@ -0,0 +1,205 @@
+//! Colour themes for text and widgets on the display.
+//!
+//! Apps draw with the colours and fonts of [`current`] instead of hard-coding their own, so
+//! picking a different [`ThemeKind`] in [`Settings`] changes the look everywhere. Besides the
+//! default dark theme there is a light one, a high-contrast one and one built on the Okabe-Ito
+//! palette, which stays distinguishable with deuteranopia and protanopia.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embedded_graphics::{
+    mono_font::{
+        MonoFont,
+        iso_8859_1::{FONT_6X10, FONT_8X13, FONT_10X20},
+    },
+    pixelcolor::Rgb565,
+};
+
+use crate::settings::Settings;
+
+/// The sizes of text a theme can use, all covering ISO-8859-1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Font {
+    /// 6x10 pixels, 53 characters across the display.
+    Small,
+    /// 8x13 pixels, 40 characters across the display.
+    Medium,
+    /// 10x20 pixels, 32 characters across the display.
+    Large,
+}
+
+impl Font {
+    pub const ALL: [Font; 3] = [Font::Small, Font::Medium, Font::Large];
+
+    /// The font to draw text with, as in `MonoTextStyle::new(theme.font.mono(), theme.text)`.
+    pub const fn mono(self) -> &'static MonoFont<'static> {
+        match self {
+            Font::Small => &FONT_6X10,
+            Font::Medium => &FONT_8X13,
+            Font::Large => &FONT_10X20,
+        }
+    }
+}
+
+/// The colours and fonts an app draws with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Behind everything else.
+    pub background: Rgb565,
+    /// Regular text.
+    pub text: Rgb565,
+    /// Less important text, such as hints and labels.
+    pub text_dim: Rgb565,
+    /// Behind the selected item.
+    pub highlight: Rgb565,
+    /// Text on top of [`highlight`](Self::highlight).
+    pub highlight_text: Rgb565,
+    /// Filled parts of widgets, such as bars and sliders.
+    pub widget: Rgb565,
+    /// Outlines of widgets.
+    pub border: Rgb565,
+    /// Something went well.
+    pub success: Rgb565,
+    /// Something went wrong.
+    pub error: Rgb565,
+    /// Regular text, such as menu items and buttons.
+    pub font: Font,
+    /// Small print, such as version numbers.
+    pub small_font: Font,
+}
+
+// Rgb565 wants its channels pre-shifted, this takes them in the usual 0-255 range
+const fn rgb(red: u8, green: u8, blue: u8) -> Rgb565 {
+    Rgb565::new(red >> 3, green >> 2, blue >> 3)
+}
+
+/// White on black with purple accents.
+pub const DEFAULT: Theme = Theme {
+    background: rgb(0, 0, 0),
+    text: rgb(255, 255, 255),
+    text_dim: rgb(150, 150, 150),
+    highlight: rgb(120, 0, 120),
+    highlight_text: rgb(255, 255, 255),
+    widget: rgb(180, 60, 180),
+    border: rgb(100, 100, 100),
+    success: rgb(0, 200, 0),
+    error: rgb(220, 0, 0),
+    font: Font::Large,
+    small_font: Font::Small,
+};
+
+/// Black on white with purple accents, for reading in bright light.
+pub const LIGHT: Theme = Theme {
+    background: rgb(255, 255, 255),
+    text: rgb(0, 0, 0),
+    text_dim: rgb(100, 100, 100),
+    highlight: rgb(120, 0, 120),
+    highlight_text: rgb(255, 255, 255),
+    widget: rgb(140, 30, 140),
+    border: rgb(150, 150, 150),
+    success: rgb(0, 140, 0),
+    error: rgb(200, 0, 0),
+    font: Font::Large,
+    small_font: Font::Small,
+};
+
+/// Pure black and white plus full-strength yellow, for the best readability.
+pub const HIGH_CONTRAST: Theme = Theme {
+    background: rgb(0, 0, 0),
+    text: rgb(255, 255, 255),
+    text_dim: rgb(255, 255, 255),
+    highlight: rgb(255, 255, 0),
+    highlight_text: rgb(0, 0, 0),
+    widget: rgb(255, 255, 255),
+    border: rgb(255, 255, 255),
+    success: rgb(0, 255, 255),
+    error: rgb(255, 255, 0),
+    // Small text is hard to read whatever the colours
+    font: Font::Large,
+    small_font: Font::Medium,
+};
+
+/// Okabe-Ito colours that avoid telling things apart by red and green alone.
+pub const COLOR_BLIND: Theme = Theme {
+    background: rgb(0, 0, 0),
+    text: rgb(255, 255, 255),
+    text_dim: rgb(170, 170, 170),
+    highlight: rgb(0, 114, 178),
+    highlight_text: rgb(255, 255, 255),
+    widget: rgb(86, 180, 233),
+    border: rgb(120, 120, 120),
+    success: rgb(0, 114, 178),
+    error: rgb(230, 159, 0),
+    font: Font::Large,
+    small_font: Font::Small,
+};
+
+/// The built-in themes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum ThemeKind {
+    #[default]
+    Default,
+    HighContrast,
+    ColorBlind,
+    Light,
+}
+
+impl ThemeKind {
+    /// Every theme, in the order a picker should show them. New themes go at the end, as the
+    /// settings store the position.
+    pub const ALL: [ThemeKind; 4] = [
+        ThemeKind::Default,
+        ThemeKind::HighContrast,
+        ThemeKind::ColorBlind,
+        ThemeKind::Light,
+    ];
+
+    pub const fn theme(self) -> &'static Theme {
+        match self {
+            ThemeKind::Default => &DEFAULT,
+            ThemeKind::HighContrast => &HIGH_CONTRAST,
+            ThemeKind::ColorBlind => &COLOR_BLIND,
+            ThemeKind::Light => &LIGHT,
+        }
+    }
+
+    /// A name to show in menus.
+    pub const fn name(self) -> &'static str {
+        match self {
+            ThemeKind::Default => "Default",
+            ThemeKind::HighContrast => "High contrast",
+            ThemeKind::ColorBlind => "Colour blind",
+            ThemeKind::Light => "Light",
+        }
+    }
+
+    pub(crate) fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(index as usize).copied()
+    }
+
+    pub(crate) fn index(self) -> u8 {
+        self as u8
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(ThemeKind::Default as u8);
+
+/// Switch to the theme chosen in the settings.
+pub fn apply_settings(settings: &Settings) {
+    set(settings.theme);
+}
+
+/// Switch to `kind` for everything drawn from now on.
+pub fn set(kind: ThemeKind) {
+    CURRENT.store(kind.index(), Ordering::Relaxed);
+}
+
+/// The theme in use.
+pub fn current() -> &'static Theme {
+    current_kind().theme()
+}
+
+/// Which built-in theme is in use.
+pub fn current_kind() -> ThemeKind {
+    ThemeKind::from_index(CURRENT.load(Ordering::Relaxed)).unwrap_or_default()
+}
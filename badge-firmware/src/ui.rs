@@ -0,0 +1,58 @@
+//! Reusable pieces of user interface on the display, drawn in the colours of the current
+//! [theme](crate::theme).
+//!
+//! Components don't read the buttons themselves: the app feeds them the [`ButtonEvent`]s it
+//! gets and draws them when something changed, so they fit any app's own loop:
+//!
+//! ```ignore
+//! let mut menu = Menu::new(&["Play", "Settings", "About"], area);
+//! loop {
+//!     menu.draw(&mut screen)?;
+//!     screen.present()?;
+//!     match menu.handle(subscriber.next_message_pure().await) {
+//!         Some(MenuEvent::Selected(index)) => open(index).await,
+//!         Some(MenuEvent::Back) => break,
+//!         None => {}
+//!     }
+//! }
+//! ```
+//!
+//! Besides the [`Menu`], there are [`Widget`]s such as buttons and switches to put on a
+//! [`Panel`], a [`Dialog`] to ask something over whatever is on the display, and a [`Keyboard`]
+//! to type text with the D-pad. While waiting for something slow, [`with_progress`] shows a
+//! progress bar or a spinner, and a [`Chart`] plots a reading such as the battery voltage. A
+//! [`ClockFace`] shows the time from the [`clock`](crate::clock) as a watch.
+//!
+//! Apps with more than one screen can keep them in a [`ScreenStack`], which gives every app the
+//! same behaviour for the B button and for dialogs.
+//!
+//! To hand a link or other short text to a phone, show it as a QR code with [`draw_qr`].
+//!
+//! For debugging without a laptop, messages sent with [`console::log`] can be shown on the
+//! display with a [`console::Console`]. To see how long frames take to draw and to send to the
+//! display, put a [`TimingOverlay`] on top.
+//!
+//! [`ButtonEvent`]: crate::input::ButtonEvent
+
+mod chart;
+mod clock_face;
+pub mod console;
+mod dialog;
+mod keyboard;
+mod loading;
+mod menu;
+mod qr;
+mod screen;
+mod timing;
+mod widget;
+
+pub use chart::Chart;
+pub use clock_face::{ClockFace, ClockStyle};
+pub use dialog::{Dialog, DialogEvent, MAX_DIALOG_BUTTONS};
+pub use keyboard::{DEFAULT_MAX_LEN, Keyboard, KeyboardEvent, KeyboardLayout};
+pub use loading::{Progress, with_progress};
+pub use menu::{Menu, MenuEvent, ROW_HEIGHT};
+pub use qr::{MAX_QR_BYTES, MAX_QR_VERSION, QrCode, QrError, draw_qr};
+pub use screen::{Screen, ScreenStack, Transition};
+pub use timing::TimingOverlay;
+pub use widget::{Panel, Widget, WidgetEvent};
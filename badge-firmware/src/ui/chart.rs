@@ -0,0 +1,236 @@
+//! A line chart of the latest samples of a reading, such as the battery voltage or signal
+//! strength.
+
+use alloc::format;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use crate::{display::UnicodeTextStyle, theme};
+
+/// A rolling line chart of the last `N` samples, the newest at the right. Unless given a
+/// [range](Self::with_range), the vertical axis grows and shrinks to fit the samples shown,
+/// rounded out to tidy numbers.
+///
+/// ```ignore
+/// let mut chart = Chart::<64>::new(Rectangle::new(Point::new(0, 40), Size::new(320, 130)))
+///     .with_label("Battery (V)");
+/// loop {
+///     chart.push(battery.voltage().await);
+///     chart.draw(&mut screen)?;
+///     screen.present()?;
+///     Timer::after_secs(1).await;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Chart<'a, const N: usize> {
+    area: Rectangle,
+    label: &'a str,
+    range: Option<(f32, f32)>,
+    samples: [f32; N],
+    next: usize,
+    len: usize,
+}
+
+impl<'a, const N: usize> Chart<'a, N> {
+    /// An empty chart filling `area`.
+    pub const fn new(area: Rectangle) -> Self {
+        Self {
+            area,
+            label: "",
+            range: None,
+            samples: [0.0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Show `label` and the latest sample in the top left corner.
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Always show from `min` to `max` instead of fitting the samples. Samples outside are
+    /// drawn at the edge.
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min.min(max), min.max(max)));
+        self
+    }
+
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Add a sample, dropping the oldest one once there are `N`.
+    pub fn push(&mut self, sample: f32) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The samples from the oldest to the newest.
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..self.len).map(move |index| self.samples[(self.next + N - self.len + index) % N])
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.samples().last()
+    }
+
+    /// The values at the bottom and the top of the chart, and the step between tidy numbers
+    /// within them.
+    pub fn range(&self) -> (f32, f32, f32) {
+        if let Some((min, max)) = self.range {
+            return (min, max, nice_step((max - min) / 4.0));
+        }
+
+        let (min, max) = self
+            .samples()
+            .filter(|sample| sample.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), sample| {
+                (min.min(sample), max.max(sample))
+            });
+        // Nothing to fit, or a flat line to put in the middle
+        let (min, max) = if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        };
+        let step = nice_step((max - min) / 4.0);
+        (
+            libm::floorf(min / step) * step,
+            libm::ceilf(max / step) * step,
+            step,
+        )
+    }
+
+    /// Draw the chart, with the range at the left and a line through the samples.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        target.fill_solid(&self.area, theme.background)?;
+        self.area
+            .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+            .draw(target)?;
+
+        let (min, max, step) = self.range();
+        let decimals = if step >= 1.0 {
+            0
+        } else if step >= 0.1 {
+            1
+        } else {
+            2
+        };
+        let small = UnicodeTextStyle::new(theme.small_font, theme.text_dim);
+        let font_size = theme.small_font.mono().character_size;
+        let left = TextStyleBuilder::new()
+            .alignment(Alignment::Left)
+            .baseline(Baseline::Top)
+            .build();
+        let top_left = self.area.top_left + Point::new(3, 2);
+        let bottom_left = Point::new(
+            top_left.x,
+            self.area.top_left.y + self.area.size.height as i32 - 3 - font_size.height as i32,
+        );
+        let (max_text, min_text) = (format!("{max:.decimals$}"), format!("{min:.decimals$}"));
+        Text::with_text_style(&max_text, top_left, small, left).draw(target)?;
+        Text::with_text_style(&min_text, bottom_left, small, left).draw(target)?;
+
+        // The samples are plotted right of the wider of the numbers
+        let number_width = max_text.len().max(min_text.len()) as u32 * font_size.width;
+        let plot = Rectangle::new(
+            self.area.top_left + Point::new(number_width as i32 + 6, font_size.height as i32 + 4),
+            Size::new(
+                self.area.size.width.saturating_sub(number_width + 9),
+                self.area.size.height.saturating_sub(font_size.height + 7),
+            ),
+        );
+        if let Some(latest) = self.latest() {
+            let label = if self.label.is_empty() {
+                format!("{latest:.decimals$}")
+            } else {
+                format!("{} {latest:.decimals$}", self.label)
+            };
+            let style = UnicodeTextStyle::new(theme.small_font, theme.text);
+            Text::with_text_style(&label, Point::new(plot.top_left.x, top_left.y), style, left)
+                .draw(target)?;
+        }
+
+        // A dotted line halfway up
+        let middle = plot.center().y;
+        for x in (plot.top_left.x..plot.top_left.x + plot.size.width as i32).step_by(4) {
+            Pixel(Point::new(x, middle), theme.border).draw(target)?;
+        }
+
+        let bottom = plot.top_left.y + plot.size.height as i32 - 1;
+        let height = plot.size.height.saturating_sub(1) as f32;
+        let spacing = plot.size.width.saturating_sub(1) as f32 / (N.max(2) - 1) as f32;
+        // The newest sample at the right edge, the rest to the left of it
+        let first = N - self.len;
+        let point = |index: usize, sample: f32| {
+            let fraction = ((sample - min) / (max - min)).clamp(0.0, 1.0);
+            Point::new(
+                plot.top_left.x + libm::roundf((first + index) as f32 * spacing) as i32,
+                bottom - libm::roundf(fraction * height) as i32,
+            )
+        };
+        let style = PrimitiveStyle::with_stroke(theme.widget, 1);
+        let mut previous = None;
+        for (index, sample) in self.samples().enumerate() {
+            if !sample.is_finite() {
+                previous = None;
+                continue;
+            }
+            let current = point(index, sample);
+            Line::new(previous.unwrap_or(current), current)
+                .into_styled(style)
+                .draw(target)?;
+            previous = Some(current);
+        }
+        Ok(())
+    }
+}
+
+// The tidy number of 1, 2 or 5 times a power of ten closest above `step`
+fn nice_step(step: f32) -> f32 {
+    if !step.is_finite() || step <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = libm::powf(10.0, libm::floorf(libm::log10f(step)));
+    let fraction = step / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
@@ -0,0 +1,279 @@
+//! A watch face showing the time of day with hands or large digits.
+
+use core::f32::consts::TAU;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, Line, PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use crate::{
+    clock::TimeOfDay,
+    display::UnicodeTextStyle,
+    theme::{self, Theme},
+};
+
+// The seven segments of a digit, bit 0 for the top one (a) going clockwise to the top left
+// one (f), and bit 6 for the middle one (g)
+const DIGIT_SEGMENTS: [u8; 10] = [
+    0b011_1111, 0b000_0110, 0b101_1011, 0b100_1111, 0b110_0110, 0b110_1101, 0b111_1101, 0b000_0111,
+    0b111_1111, 0b110_1111,
+];
+// A dash, for a clock that hasn't been set
+const DASH_SEGMENTS: u8 = 0b100_0000;
+
+/// How a [`ClockFace`] shows the time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum ClockStyle {
+    /// Hands on a round face.
+    #[default]
+    Analog,
+    /// Seven-segment digits as big as fit.
+    Digital,
+}
+
+impl ClockStyle {
+    pub const ALL: [ClockStyle; 2] = [ClockStyle::Analog, ClockStyle::Digital];
+}
+
+/// A clock in an area of the display, drawn in the colours of the current theme. Together with
+/// the [`clock`](crate::clock) module a watch face is a few lines:
+///
+/// ```ignore
+/// let face = ClockFace::new(Rectangle::new(Point::zero(), display::SIZE));
+/// let mut ticker = Ticker::every(Duration::from_secs(1));
+/// loop {
+///     face.draw(&mut screen, clock::now())?;
+///     screen.present()?;
+///     ticker.next().await;
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockFace {
+    area: Rectangle,
+    style: ClockStyle,
+    seconds: bool,
+}
+
+impl ClockFace {
+    /// An analog clock with a second hand, filling `area`.
+    pub const fn new(area: Rectangle) -> Self {
+        Self {
+            area,
+            style: ClockStyle::Analog,
+            seconds: true,
+        }
+    }
+
+    pub fn with_style(mut self, style: ClockStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Whether to show the seconds, as a hand or as two more digits.
+    pub fn with_seconds(mut self, seconds: bool) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    pub fn style(&self) -> ClockStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: ClockStyle) {
+        self.style = style;
+    }
+
+    /// Draw the clock showing `time`, or with no hands or dashes for digits if `None`, as
+    /// [`clock::now`](crate::clock::now) gives before the clock is set.
+    pub fn draw<D>(&self, target: &mut D, time: Option<TimeOfDay>) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        target.fill_solid(&self.area, theme.background)?;
+        match self.style {
+            ClockStyle::Analog => self.draw_analog(target, time, theme),
+            ClockStyle::Digital => self.draw_digital(target, time, theme),
+        }
+    }
+
+    fn draw_analog<D>(
+        &self,
+        target: &mut D,
+        time: Option<TimeOfDay>,
+        theme: &Theme,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let center = self.area.center();
+        let diameter = self.area.size.width.min(self.area.size.height);
+        let radius = diameter.saturating_sub(4) as f32 / 2.0;
+        // The end of a line `length` from the centre, `turns` of the way round from twelve
+        let point = |turns: f32, length: f32| {
+            let angle = turns * TAU;
+            center
+                + Point::new(
+                    libm::roundf(libm::sinf(angle) * length) as i32,
+                    -libm::roundf(libm::cosf(angle) * length) as i32,
+                )
+        };
+
+        Circle::with_center(center, diameter.saturating_sub(2))
+            .into_styled(PrimitiveStyle::with_stroke(theme.border, 2))
+            .draw(target)?;
+        for hour in 0..12 {
+            let turns = hour as f32 / 12.0;
+            let (inner, style) = if hour % 3 == 0 {
+                (0.8, PrimitiveStyle::with_stroke(theme.text, 3))
+            } else {
+                (0.88, PrimitiveStyle::with_stroke(theme.text_dim, 1))
+            };
+            Line::new(point(turns, radius * inner), point(turns, radius * 0.95))
+                .into_styled(style)
+                .draw(target)?;
+        }
+
+        let Some(time) = time else {
+            let style = UnicodeTextStyle::new(theme.font, theme.text_dim);
+            let centered = TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Middle)
+                .build();
+            Text::with_text_style("--:--", center, style, centered).draw(target)?;
+            return Ok(());
+        };
+
+        let seconds = time.seconds as f32;
+        let minutes = time.minutes as f32 + seconds / 60.0;
+        let hours = (time.hours % 12) as f32 + minutes / 60.0;
+        let hands = [
+            (hours / 12.0, 0.5, 5, theme.text),
+            (minutes / 60.0, 0.75, 3, theme.text),
+            (seconds / 60.0, 0.85, 1, theme.widget),
+        ];
+        let count = if self.seconds { 3 } else { 2 };
+        for &(turns, length, width, color) in &hands[..count] {
+            Line::new(center, point(turns, radius * length))
+                .into_styled(PrimitiveStyle::with_stroke(color, width))
+                .draw(target)?;
+        }
+        Circle::with_center(center, 7)
+            .into_styled(PrimitiveStyle::with_fill(theme.widget))
+            .draw(target)
+    }
+
+    fn draw_digital<D>(
+        &self,
+        target: &mut D,
+        time: Option<TimeOfDay>,
+        theme: &Theme,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let mut digits = [DASH_SEGMENTS; 6];
+        if let Some(time) = time {
+            for (pair, value) in digits
+                .chunks_mut(2)
+                .zip([time.hours, time.minutes, time.seconds])
+            {
+                pair[0] = DIGIT_SEGMENTS[(value / 10 % 10) as usize];
+                pair[1] = DIGIT_SEGMENTS[(value % 10) as usize];
+            }
+        }
+        let digits = if self.seconds {
+            &digits[..]
+        } else {
+            &digits[..4]
+        };
+        let colons = digits.len() as u32 / 2 - 1;
+
+        // Measured in twelfths of a digit's width, a digit takes 15 with the gap after it and a
+        // colon 7. Digits are twice as high as they're wide.
+        let units = 15 * digits.len() as u32 + 7 * colons;
+        let width = (self.area.size.width.saturating_sub(4) * 12 / units)
+            .min(self.area.size.height.saturating_sub(4) / 2);
+        if width < 4 {
+            return Ok(());
+        }
+        let height = width * 2;
+        let gap = width / 4;
+        let colon_width = width / 3;
+        let thickness = (width / 5).max(2);
+        let total = digits.len() as u32 * (width + gap) + colons * (colon_width + gap) - gap;
+        let mut x = self.area.center().x - total as i32 / 2;
+        let y = self.area.center().y - height as i32 / 2;
+
+        for (index, &segments) in digits.iter().enumerate() {
+            if index > 0 && index % 2 == 0 {
+                // The colon between the pairs
+                let dot = Size::new(thickness, thickness);
+                let dot_x = x + (colon_width as i32 - thickness as i32) / 2;
+                for dot_y in [height / 3, height * 2 / 3] {
+                    let top_left = Point::new(dot_x, y + (dot_y - thickness / 2) as i32);
+                    target.fill_solid(&Rectangle::new(top_left, dot), theme.text)?;
+                }
+                x += (colon_width + gap) as i32;
+            }
+            draw_digit(
+                target,
+                Point::new(x, y),
+                Size::new(width, height),
+                thickness,
+                segments,
+                theme,
+            )?;
+            x += (width + gap) as i32;
+        }
+        Ok(())
+    }
+}
+
+// Seven segments filling `size` at `top_left`, the ones in `segments` lit and the rest faint
+fn draw_digit<D>(
+    target: &mut D,
+    top_left: Point,
+    size: Size,
+    thickness: u32,
+    segments: u8,
+    theme: &Theme,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let (width, height) = (size.width, size.height);
+    let half = height / 2;
+    let across = Size::new(width, thickness);
+    let upper = Size::new(thickness, half);
+    let lower = Size::new(thickness, height - half);
+    let right = (width - thickness) as i32;
+    // In the order of the bits, a to g
+    let parts = [
+        (Point::new(0, 0), across),
+        (Point::new(right, 0), upper),
+        (Point::new(right, half as i32), lower),
+        (Point::new(0, (height - thickness) as i32), across),
+        (Point::new(0, half as i32), lower),
+        (Point::new(0, 0), upper),
+        (Point::new(0, (half - thickness / 2) as i32), across),
+    ];
+
+    // Unlit ones first, so lit ones are drawn over where they meet
+    for lit in [false, true] {
+        for (bit, &(offset, size)) in parts.iter().enumerate() {
+            if (segments >> bit & 1 != 0) == lit {
+                let color = if lit { theme.text } else { theme.border };
+                target.fill_solid(&Rectangle::new(top_left + offset, size), color)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! A log on the display, for debugging away from a laptop.
+//!
+//! defmt messages are only formatted on the computer reading them, so the badge can't show them
+//! itself. Messages sent with [`log`] go to defmt as usual and are also kept for a [`Console`]
+//! to draw over the app:
+//!
+//! ```ignore
+//! console::log(Level::Info, "Connected");
+//! console::log(Level::Warn, &alloc::format!("Lost {} packets", lost));
+//! ```
+//!
+//! The console is hidden until [`toggle`]d, usually with [`CONSOLE_CHORD`]:
+//!
+//! ```ignore
+//! let mut chords = ChordDetector::new(events, &[CONSOLE_CHORD]);
+//! let mut console = Console::new(Rectangle::new(Point::new(0, 100), Size::new(320, 70)));
+//! loop {
+//!     if let ChordEvent::Chord(CONSOLE_CHORD) = chords.next().await {
+//!         console::toggle();
+//!     }
+//!     // ...draw the app, then the console on top
+//!     console.draw(&mut screen)?;
+//! }
+//! ```
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+
+use crate::{
+    display::{UnicodeTextStyle, WIDTH},
+    input::{Button, ButtonSet},
+    theme::{self, Font},
+};
+
+/// How many lines the console remembers.
+pub const CONSOLE_LINES: usize = 32;
+/// The buttons to press together to show or hide the console, Start and Select.
+pub const CONSOLE_CHORD: ButtonSet = ButtonSet::of(&[Button::Start, Button::Select]);
+
+// The size of a character of `Font::Small`
+const CHAR_WIDTH: u32 = 6;
+const LINE_HEIGHT: u32 = 10;
+// As many characters as fit across the display, longer messages wrap
+const LINE_LEN: usize = (WIDTH / CHAR_WIDTH) as usize;
+
+static LINES: Mutex<CriticalSectionRawMutex, RefCell<History>> =
+    Mutex::new(RefCell::new(History::new()));
+// Goes up with every line logged, so a console knows when it's out of date
+static LOGGED: AtomicU32 = AtomicU32::new(0);
+static VISIBLE: AtomicBool = AtomicBool::new(false);
+
+/// How important a message is, as with the defmt macros.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum Level {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub const ALL: [Level; 4] = [Level::Debug, Level::Info, Level::Warn, Level::Error];
+
+    fn color(self) -> Rgb565 {
+        let theme = theme::current();
+        match self {
+            Level::Debug => theme.text_dim,
+            Level::Info => theme.text,
+            Level::Warn => theme.highlight,
+            Level::Error => theme.error,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Line {
+    level: Level,
+    text: [u8; LINE_LEN],
+    len: usize,
+}
+
+impl Line {
+    const EMPTY: Line = Line {
+        level: Level::Info,
+        text: [0; LINE_LEN],
+        len: 0,
+    };
+
+    fn as_str(&self) -> &str {
+        // Lines are only ever cut at character boundaries
+        core::str::from_utf8(&self.text[..self.len]).unwrap_or_default()
+    }
+}
+
+// The latest lines, oldest first from `next`
+struct History {
+    lines: [Line; CONSOLE_LINES],
+    next: usize,
+    len: usize,
+}
+
+impl History {
+    const fn new() -> Self {
+        Self {
+            lines: [Line::EMPTY; CONSOLE_LINES],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, level: Level, text: &str) {
+        let line = &mut self.lines[self.next];
+        line.level = level;
+        line.len = text.len();
+        line.text[..text.len()].copy_from_slice(text.as_bytes());
+        self.next = (self.next + 1) % CONSOLE_LINES;
+        self.len = (self.len + 1).min(CONSOLE_LINES);
+    }
+
+    // The line `back` lines before the latest one
+    fn get(&self, back: usize) -> Option<&Line> {
+        (back < self.len)
+            .then(|| &self.lines[(self.next + CONSOLE_LINES - 1 - back) % CONSOLE_LINES])
+    }
+}
+
+/// Send `message` to defmt at `level`, and keep it for the console. Long messages are wrapped to
+/// the width of the display.
+pub fn log(level: Level, message: &str) {
+    match level {
+        Level::Debug => defmt::debug!("{=str}", message),
+        Level::Info => defmt::info!("{=str}", message),
+        Level::Warn => defmt::warn!("{=str}", message),
+        Level::Error => defmt::error!("{=str}", message),
+    }
+
+    LINES.lock(|lines| {
+        let mut lines = lines.borrow_mut();
+        for mut rest in message.lines() {
+            loop {
+                let mut end = rest.len().min(LINE_LEN);
+                while !rest.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let (line, after) = rest.split_at(end);
+                lines.push(level, line);
+                LOGGED.fetch_add(1, Ordering::Relaxed);
+                rest = after;
+                if rest.is_empty() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Forget every line logged so far.
+pub fn clear() {
+    LINES.lock(|lines| *lines.borrow_mut() = History::new());
+    LOGGED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Show the console if it's hidden, hide it if it's shown.
+pub fn toggle() {
+    VISIBLE.fetch_xor(true, Ordering::Relaxed);
+}
+
+pub fn set_visible(visible: bool) {
+    VISIBLE.store(visible, Ordering::Relaxed);
+}
+
+pub fn is_visible() -> bool {
+    VISIBLE.load(Ordering::Relaxed)
+}
+
+/// The latest logged lines, drawn newest at the bottom of an area of the display.
+#[derive(Clone, Copy, Debug)]
+pub struct Console {
+    area: Rectangle,
+    // The lines logged when last drawn, and whether it was visible then
+    drawn: Option<(u32, bool)>,
+}
+
+impl Console {
+    pub fn new(area: Rectangle) -> Self {
+        Self { area, drawn: None }
+    }
+
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Whether something was logged, or the console shown or hidden, since the last
+    /// [`draw`](Self::draw).
+    pub fn is_changed(&self) -> bool {
+        self.drawn != Some((LOGGED.load(Ordering::Relaxed), is_visible()))
+    }
+
+    /// Draw the console over its area, or nothing while it's hidden. An app drawing on top of
+    /// its previous frame should redraw the area when the console is hidden, see
+    /// [`is_changed`](Self::is_changed).
+    pub fn draw<D>(&mut self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let visible = is_visible();
+        self.drawn = Some((LOGGED.load(Ordering::Relaxed), visible));
+        if !visible {
+            return Ok(());
+        }
+
+        let theme = theme::current();
+        self.area
+            .into_styled(PrimitiveStyle::with_fill(theme.background))
+            .draw(target)?;
+
+        let rows = (self.area.size.height / LINE_HEIGHT) as usize;
+        let bottom = self.area.top_left.y + (rows as u32 * LINE_HEIGHT) as i32;
+        // Copy the lines out so the lock isn't held while drawing
+        let mut shown = [Line::EMPTY; CONSOLE_LINES];
+        let count = LINES.lock(|lines| {
+            let lines = lines.borrow();
+            let mut count = 0;
+            while count < rows
+                && let Some(line) = lines.get(count)
+            {
+                shown[count] = *line;
+                count += 1;
+            }
+            count
+        });
+
+        for (back, line) in shown[..count].iter().enumerate() {
+            let y = bottom - ((back as u32 + 1) * LINE_HEIGHT) as i32;
+            Text::with_baseline(
+                line.as_str(),
+                Point::new(self.area.top_left.x, y),
+                UnicodeTextStyle::new(Font::Small, line.level.color()),
+                Baseline::Top,
+            )
+            .draw(target)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! A message in a box over whatever else is on the display, with buttons to answer it.
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text},
+};
+
+use super::Widget;
+use crate::{
+    display::{SIZE, TextBox, UnicodeTextStyle},
+    input::{Button, ButtonEvent, ButtonEventKind},
+    theme,
+};
+
+/// The most buttons a dialog can have.
+pub const MAX_DIALOG_BUTTONS: usize = 3;
+
+const MARGIN: u32 = 16;
+const PADDING: u32 = 8;
+const BUTTON_HEIGHT: u32 = 28;
+const BUTTON_GAP: u32 = 8;
+
+/// What the user did with a [`Dialog`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum DialogEvent {
+    /// Pressed the button at this index.
+    Chosen(usize),
+    /// Closed the dialog with B without choosing.
+    Dismissed,
+}
+
+/// A modal dialog: a title, a message wrapped to fit, and a row of buttons chosen between with
+/// left and right.
+///
+/// While it's open, feed the dialog the button events instead of the screen behind it, for
+/// example after claiming the buttons with a [`FocusSubscriber`](crate::input::FocusSubscriber).
+///
+/// ```ignore
+/// let mut dialog = Dialog::new("Delete contact?", &name, &["Cancel", "Delete"]);
+/// dialog.draw(&mut screen)?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Dialog<'t> {
+    title: &'t str,
+    message: &'t str,
+    buttons: &'t [&'t str],
+    focus: usize,
+}
+
+impl<'t> Dialog<'t> {
+    /// A dialog with up to [`MAX_DIALOG_BUTTONS`] `buttons`, the first one focused.
+    pub fn new(title: &'t str, message: &'t str, buttons: &'t [&'t str]) -> Self {
+        Self {
+            title,
+            message,
+            buttons: &buttons[..buttons.len().min(MAX_DIALOG_BUTTONS)],
+            focus: 0,
+        }
+    }
+
+    /// Focus the button at `index` instead, such as the safe choice.
+    pub fn with_focus(mut self, index: usize) -> Self {
+        self.focus = index.min(self.buttons.len().saturating_sub(1));
+        self
+    }
+
+    /// The index of the focused button.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// The area the dialog covers.
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), SIZE).offset(-(MARGIN as i32))
+    }
+
+    /// React to a button event. Returns what the user did, if anything besides moving the focus.
+    pub fn handle(&mut self, event: ButtonEvent) -> Option<DialogEvent> {
+        if !matches!(
+            event.kind,
+            ButtonEventKind::Pressed | ButtonEventKind::Repeated
+        ) {
+            return None;
+        }
+        let len = self.buttons.len();
+        match event.button {
+            Button::Left | Button::Up if len > 0 => self.focus = (self.focus + len - 1) % len,
+            Button::Right | Button::Down if len > 0 => self.focus = (self.focus + 1) % len,
+            Button::A | Button::Stick if len > 0 => return Some(DialogEvent::Chosen(self.focus)),
+            Button::B => return Some(DialogEvent::Dismissed),
+            _ => {}
+        }
+        None
+    }
+
+    /// Draw the dialog over whatever is on `target`.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        let font = theme.font.mono();
+        let area = self.area();
+        target.fill_solid(&area, theme.background)?;
+        area.into_styled(PrimitiveStyle::with_stroke(theme.border, 2))
+            .draw(target)?;
+
+        let inside = area.offset(-(PADDING as i32));
+        Text::with_baseline(
+            self.title,
+            inside.top_left,
+            UnicodeTextStyle::new(theme.font, theme.text),
+            Baseline::Top,
+        )
+        .draw(target)?;
+
+        let title_height = font.character_size.height + PADDING;
+        let message_area = Rectangle::new(
+            inside.top_left + Point::new(0, title_height as i32),
+            inside
+                .size
+                .saturating_sub(Size::new(0, title_height + BUTTON_HEIGHT + PADDING)),
+        );
+        TextBox::new(
+            self.message,
+            message_area,
+            UnicodeTextStyle::new(theme.font, theme.text_dim),
+        )
+        .with_alignment(Alignment::Left)
+        .draw(target)?;
+
+        // The buttons share the bottom row evenly
+        let count = self.buttons.len().max(1) as u32;
+        let width = (inside.size.width - BUTTON_GAP * (count - 1)) / count;
+        let y = inside.top_left.y + (inside.size.height - BUTTON_HEIGHT) as i32;
+        for (index, text) in self.buttons.iter().enumerate() {
+            let x = inside.top_left.x + (index as u32 * (width + BUTTON_GAP)) as i32;
+            let button = Rectangle::new(Point::new(x, y), Size::new(width, BUTTON_HEIGHT));
+            Widget::button(text, button).draw(target, index == self.focus)?;
+        }
+        Ok(())
+    }
+}
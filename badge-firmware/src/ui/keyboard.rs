@@ -0,0 +1,260 @@
+//! Typing text with the D-pad, for names, network names and the like.
+
+use alloc::string::String;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use crate::{
+    display::UnicodeTextStyle,
+    input::{Button, ButtonEvent, ButtonEventKind},
+    theme,
+};
+
+/// The longest text a keyboard takes, unless set.
+pub const DEFAULT_MAX_LEN: usize = 32;
+
+const COLUMNS: usize = 10;
+const ROWS: usize = 4;
+// The height of the prompt and of the text being typed
+const LINE_HEIGHT: u32 = 24;
+
+/// The keys of a [`Keyboard`], switched between with Select.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum KeyboardLayout {
+    #[default]
+    Lower,
+    Upper,
+    Symbols,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 3] = [
+        KeyboardLayout::Lower,
+        KeyboardLayout::Upper,
+        KeyboardLayout::Symbols,
+    ];
+
+    // Ten keys to a row, the space bar last
+    fn rows(self) -> [&'static str; ROWS] {
+        match self {
+            KeyboardLayout::Lower => ["1234567890", "qwertyuiop", "asdfghjklö", "zxcvbnmäå "],
+            KeyboardLayout::Upper => ["1234567890", "QWERTYUIOP", "ASDFGHJKLÖ", "ZXCVBNMÄÅ "],
+            KeyboardLayout::Symbols => ["!@#$%^&*()", "-_=+[]{};:", "'\"\\|/?<>,.", "`~€£§°¤µ× "],
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    fn key(self, column: usize, row: usize) -> char {
+        self.rows()[row].chars().nth(column).unwrap_or(' ')
+    }
+}
+
+/// What the user did with a [`Keyboard`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum KeyboardEvent {
+    /// Finished typing with Start.
+    Done,
+    /// Gave up with B while there was nothing left to delete.
+    Cancelled,
+}
+
+/// A grid of keys to type text with: the D-pad moves around the keys, A or the stick types the
+/// key, B deletes the last character, Select switches between [`KeyboardLayout`]s and Start is
+/// done.
+///
+/// ```ignore
+/// let mut keyboard = Keyboard::new("Your name?", Rectangle::new(Point::zero(), display::SIZE))
+///     .with_text(&settings.card.name);
+/// loop {
+///     keyboard.draw(&mut screen)?;
+///     screen.present()?;
+///     match keyboard.handle(events.next().await) {
+///         Some(KeyboardEvent::Done) => break Some(keyboard.into_text()),
+///         Some(KeyboardEvent::Cancelled) => break None,
+///         None => {}
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keyboard<'a> {
+    prompt: &'a str,
+    area: Rectangle,
+    text: String,
+    max_len: usize,
+    layout: KeyboardLayout,
+    column: usize,
+    row: usize,
+}
+
+impl<'a> Keyboard<'a> {
+    /// An empty keyboard filling `area`, with `prompt` above the text.
+    pub fn new(prompt: &'a str, area: Rectangle) -> Self {
+        Self {
+            prompt,
+            area,
+            text: String::new(),
+            max_len: DEFAULT_MAX_LEN,
+            layout: KeyboardLayout::Lower,
+            column: 0,
+            row: 1,
+        }
+    }
+
+    /// Start with `text` to edit instead of nothing.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.chars().take(self.max_len).collect();
+        self
+    }
+
+    /// How many characters can be typed at most, [`DEFAULT_MAX_LEN`] unless set.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self.text = self.text.chars().take(max_len).collect();
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// The key under the cursor.
+    pub fn key(&self) -> char {
+        self.layout.key(self.column, self.row)
+    }
+
+    /// React to a button event, moving round to the other side past the edges. Returns what the
+    /// user did, if anything besides typing.
+    pub fn handle(&mut self, event: ButtonEvent) -> Option<KeyboardEvent> {
+        if !matches!(
+            event.kind,
+            ButtonEventKind::Pressed | ButtonEventKind::Repeated
+        ) {
+            return None;
+        }
+
+        match event.button {
+            Button::Up => self.row = (self.row + ROWS - 1) % ROWS,
+            Button::Down => self.row = (self.row + 1) % ROWS,
+            Button::Left => self.column = (self.column + COLUMNS - 1) % COLUMNS,
+            Button::Right => self.column = (self.column + 1) % COLUMNS,
+            Button::A | Button::Stick => {
+                if self.text.chars().count() < self.max_len {
+                    self.text.push(self.key());
+                }
+            }
+            Button::B => {
+                if self.text.pop().is_none() {
+                    return Some(KeyboardEvent::Cancelled);
+                }
+            }
+            Button::Select => self.layout = self.layout.next(),
+            Button::Start => return Some(KeyboardEvent::Done),
+        }
+        None
+    }
+
+    /// Draw the prompt, the text typed so far and the keys, with the one under the cursor
+    /// highlighted.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        target.fill_solid(&self.area, theme.background)?;
+        let top_left = self.area.top_left;
+
+        let left = TextStyleBuilder::new()
+            .alignment(Alignment::Left)
+            .baseline(Baseline::Middle)
+            .build();
+        let line = |index: u32| top_left.y + (index * LINE_HEIGHT + LINE_HEIGHT / 2) as i32;
+        Text::with_text_style(
+            self.prompt,
+            Point::new(top_left.x + 4, line(0)),
+            UnicodeTextStyle::new(theme.font, theme.text_dim),
+            left,
+        )
+        .draw(target)?;
+
+        // The text so far, with a cursor after it
+        let field = Rectangle::new(
+            top_left + Point::new(0, LINE_HEIGHT as i32),
+            Size::new(self.area.size.width, LINE_HEIGHT),
+        );
+        field
+            .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+            .draw(target)?;
+        let end = Text::with_text_style(
+            &self.text,
+            Point::new(top_left.x + 4, line(1)),
+            UnicodeTextStyle::new(theme.font, theme.text),
+            left,
+        )
+        .draw(target)?;
+        let font = theme.font.mono();
+        let cursor = Rectangle::new(
+            Point::new(end.x, line(1) + font.character_size.height as i32 / 2 - 2),
+            Size::new(font.character_size.width, 2),
+        );
+        target.fill_solid(&cursor, theme.widget)?;
+
+        // The keys share what's left of the area evenly
+        let keys_top = top_left.y + 2 * LINE_HEIGHT as i32 + 4;
+        let key_size = Size::new(
+            self.area.size.width / COLUMNS as u32,
+            (self.area.size.height.saturating_sub(2 * LINE_HEIGHT + 4)) / ROWS as u32,
+        );
+        let center = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Middle)
+            .build();
+        for row in 0..ROWS {
+            for column in 0..COLUMNS {
+                let key = Rectangle::new(
+                    Point::new(
+                        top_left.x + (column as u32 * key_size.width) as i32,
+                        keys_top + (row as u32 * key_size.height) as i32,
+                    ),
+                    key_size,
+                );
+                let color = if (column, row) == (self.column, self.row) {
+                    target.fill_solid(&key, theme.highlight)?;
+                    theme.highlight_text
+                } else {
+                    theme.text
+                };
+
+                let character = self.layout.key(column, row);
+                let mut buffer = [0; 4];
+                let label = match character {
+                    ' ' => "sp",
+                    character => character.encode_utf8(&mut buffer),
+                };
+                Text::with_text_style(
+                    label,
+                    key.center(),
+                    UnicodeTextStyle::new(theme.font, color),
+                    center,
+                )
+                .draw(target)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! A screen to show while waiting for something slow, such as an update or a connection.
+
+use core::{
+    future::Future,
+    pin::pin,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use defmt::warn;
+use embassy_futures::select::{Either, select};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle, text::Alignment};
+
+use super::Widget;
+use crate::{
+    display::{Framebuffer, HEIGHT, TextBox, UnicodeTextStyle, WIDTH},
+    motion, theme,
+};
+
+const LABEL_AREA: Rectangle = Rectangle::new(Point::new(10, 40), Size::new(WIDTH - 20, 40));
+const BAR_AREA: Rectangle = Rectangle::new(Point::new(40, 100), Size::new(WIDTH - 80, 16));
+const SPINNER_AREA: Rectangle =
+    Rectangle::new(Point::new(WIDTH as i32 / 2 - 16, 92), Size::new(32, 32));
+
+/// How far along a slow operation is, updated by the operation and shown by
+/// [`with_progress`]. Can be shared between tasks, so it works as a `static` too.
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicU32,
+    total: AtomicU32,
+}
+
+impl Progress {
+    /// Nothing done of an unknown total.
+    pub const fn new() -> Self {
+        Self {
+            done: AtomicU32::new(0),
+            total: AtomicU32::new(0),
+        }
+    }
+
+    /// `done` out of `total`, in whatever unit suits, such as bytes or steps.
+    pub fn set(&self, done: u32, total: u32) {
+        self.total.store(total, Ordering::Relaxed);
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    /// How much is done and of how much, or `None` before the total is known.
+    pub fn get(&self) -> Option<(u32, u32)> {
+        let total = self.total.load(Ordering::Relaxed);
+        (total > 0).then(|| (self.done.load(Ordering::Relaxed).min(total), total))
+    }
+}
+
+/// Show `label` with a progress bar on the whole display until `operation` finishes, and
+/// return what it returned. The bar follows `progress` once it has a total, before that and
+/// without one a spinner shows instead.
+///
+/// ```ignore
+/// static PROGRESS: Progress = Progress::new();
+/// let result = ui::with_progress(&mut screen, "Updating...", Some(&PROGRESS), async {
+///     ota.write(&image, |written, total| PROGRESS.set(written, total)).await
+/// })
+/// .await;
+/// ```
+///
+/// The screen is left as it was at the end, for the app to draw over.
+pub async fn with_progress<D, F>(
+    screen: &mut Framebuffer<'_, D>,
+    label: &str,
+    progress: Option<&Progress>,
+    operation: F,
+) -> F::Output
+where
+    D: DrawTarget<Color = Rgb565>,
+    F: Future,
+{
+    let theme = theme::current();
+    screen
+        .fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT)),
+            theme.background,
+        )
+        .and_then(|_| {
+            TextBox::new(
+                label,
+                LABEL_AREA,
+                UnicodeTextStyle::new(theme.font, theme.text),
+            )
+            .with_alignment(Alignment::Center)
+            .draw(screen)
+        })
+        .unwrap_or_else(|_| {
+            warn!("Unable to draw the loading screen");
+            0
+        });
+
+    let mut operation = pin!(operation);
+    loop {
+        let widget = match progress.and_then(Progress::get) {
+            Some((done, total)) => Widget::progress(done, total, BAR_AREA),
+            None => Widget::spinner(SPINNER_AREA),
+        };
+        widget
+            .draw(screen, false)
+            .and_then(|_| screen.present())
+            .unwrap_or_else(|_| warn!("Unable to draw the loading screen"));
+
+        if let Either::First(output) = select(operation.as_mut(), motion::next_frame()).await {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! A list of items to pick one from.
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use crate::{
+    display::UnicodeTextStyle,
+    input::{Button, ButtonEvent, ButtonEventKind},
+    theme,
+};
+
+/// The height of one item in pixels.
+pub const ROW_HEIGHT: u32 = 24;
+
+// Space left of the text, and the width of the scroll bar
+const PADDING: i32 = 6;
+const SCROLL_BAR_WIDTH: u32 = 4;
+
+/// What the user did with a [`Menu`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum MenuEvent {
+    /// Picked the item at this index with A, the stick or right.
+    Selected(usize),
+    /// Left the menu with B or left.
+    Back,
+}
+
+/// A list of items with a cursor, moved with up and down. Lists longer than the menu is high
+/// scroll to keep the cursor in view.
+///
+/// Besides the [`MenuEvent`]s from [`handle`](Self::handle), a menu can call a function when
+/// an item is picked or the menu left, for apps where that's simpler.
+#[derive(Clone, Copy, Debug)]
+pub struct Menu<'a> {
+    items: &'a [&'a str],
+    area: Rectangle,
+    cursor: usize,
+    // The first item shown
+    scroll: usize,
+    on_select: Option<fn(usize)>,
+    on_back: Option<fn()>,
+}
+
+impl<'a> Menu<'a> {
+    /// A menu of `items` filling `area`, with the cursor on the first item.
+    pub fn new(items: &'a [&'a str], area: Rectangle) -> Self {
+        Self {
+            items,
+            area,
+            cursor: 0,
+            scroll: 0,
+            on_select: None,
+            on_back: None,
+        }
+    }
+
+    /// Call `on_select` with the index of every item picked.
+    pub fn with_on_select(mut self, on_select: fn(usize)) -> Self {
+        self.on_select = Some(on_select);
+        self
+    }
+
+    /// Call `on_back` whenever the menu is left.
+    pub fn with_on_back(mut self, on_back: fn()) -> Self {
+        self.on_back = Some(on_back);
+        self
+    }
+
+    pub fn items(&self) -> &'a [&'a str] {
+        self.items
+    }
+
+    /// The index of the item under the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Move the cursor to the item at `index`, or the last one if there are fewer.
+    pub fn set_cursor(&mut self, index: usize) {
+        self.cursor = index.min(self.items.len().saturating_sub(1));
+        let visible = self.visible_rows();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + visible {
+            self.scroll = self.cursor + 1 - visible;
+        }
+    }
+
+    // How many items fit, at least one
+    fn visible_rows(&self) -> usize {
+        (self.area.size.height / ROW_HEIGHT).max(1) as usize
+    }
+
+    /// React to a button event: up and down move the cursor, round to the other end past the
+    /// first and last item. Returns what the user did, if anything besides moving.
+    pub fn handle(&mut self, event: ButtonEvent) -> Option<MenuEvent> {
+        if !matches!(
+            event.kind,
+            ButtonEventKind::Pressed | ButtonEventKind::Repeated
+        ) || self.items.is_empty()
+        {
+            return None;
+        }
+
+        let len = self.items.len();
+        match event.button {
+            Button::Up => self.set_cursor((self.cursor + len - 1) % len),
+            Button::Down => self.set_cursor((self.cursor + 1) % len),
+            Button::A | Button::Stick | Button::Right => {
+                if let Some(on_select) = self.on_select {
+                    on_select(self.cursor);
+                }
+                return Some(MenuEvent::Selected(self.cursor));
+            }
+            Button::B | Button::Left => {
+                if let Some(on_back) = self.on_back {
+                    on_back();
+                }
+                return Some(MenuEvent::Back);
+            }
+            Button::Start | Button::Select => {}
+        }
+        None
+    }
+
+    /// Draw the items in view, with the one under the cursor highlighted.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        let font = theme.font.mono();
+        let mut target = target.clipped(&self.area);
+        target.fill_solid(&self.area, theme.background)?;
+
+        let visible = self.visible_rows();
+        let rows = self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(visible);
+        for (row, (index, item)) in rows.enumerate() {
+            let top_left = self.area.top_left + Point::new(0, row as i32 * ROW_HEIGHT as i32);
+            let color = if index == self.cursor {
+                let row = Rectangle::new(top_left, Size::new(self.area.size.width, ROW_HEIGHT));
+                target.fill_solid(&row, theme.highlight)?;
+                theme.highlight_text
+            } else {
+                theme.text
+            };
+            let margin = (ROW_HEIGHT - font.character_size.height) as i32 / 2;
+            let position = top_left + Point::new(PADDING, margin);
+            Text::with_baseline(
+                item,
+                position,
+                UnicodeTextStyle::new(theme.font, color),
+                Baseline::Top,
+            )
+            .draw(&mut target)?;
+        }
+
+        // A bar along the right edge, as long and as far down as the part of the list in view
+        let len = self.items.len();
+        if len > visible {
+            let height = self.area.size.height;
+            let bar = Rectangle::new(
+                self.area.top_left
+                    + Point::new(
+                        (self.area.size.width - SCROLL_BAR_WIDTH) as i32,
+                        (self.scroll as u32 * height / len as u32) as i32,
+                    ),
+                Size::new(SCROLL_BAR_WIDTH, visible as u32 * height / len as u32),
+            );
+            target.fill_solid(&bar, theme.widget)?;
+        }
+        Ok(())
+    }
+}
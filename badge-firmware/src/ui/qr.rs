@@ -0,0 +1,588 @@
+//! QR codes, for handing a link or a bit of text from the badge to a phone.
+//!
+//! The encoder covers what fits on the display: byte mode, error correction level M, and
+//! versions up to [`MAX_QR_VERSION`], which hold up to [`MAX_QR_BYTES`] bytes. The structure
+//! follows the QR code specification, ISO/IEC 18004.
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+/// The largest QR code version made, 57 modules square.
+pub const MAX_QR_VERSION: u8 = 10;
+/// The most bytes a QR code can hold here.
+pub const MAX_QR_BYTES: usize = 213;
+
+const MAX_SIZE: usize = MAX_QR_VERSION as usize * 4 + 17;
+// The light border around the code, in modules, as the specification asks for
+const QUIET_ZONE: u32 = 4;
+
+// Error correction codewords per block and number of blocks at level M, by version
+const ECC_PER_BLOCK: [usize; MAX_QR_VERSION as usize] = [10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+const BLOCKS: [usize; MAX_QR_VERSION as usize] = [1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+const MAX_CODEWORDS: usize = 346;
+const MAX_BLOCK_LEN: usize = 128;
+
+/// Reasons a QR code can't be drawn.
+#[derive(Debug, defmt::Format)]
+pub enum QrError<E> {
+    /// More data than [`MAX_QR_BYTES`].
+    TooLong,
+    /// Drawing on the display failed.
+    Display(E),
+}
+
+/// A QR code, as a square of dark and light modules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCode {
+    version: u8,
+    // One bit per module, bit x of row y, set for dark
+    rows: [u64; MAX_SIZE],
+}
+
+impl QrCode {
+    /// Encode `data` in the smallest QR code it fits, or `None` if it's longer than
+    /// [`MAX_QR_BYTES`].
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let version = (1..=MAX_QR_VERSION).find(|&version| {
+            let header = 4 + count_bits(version);
+            header + data.len() * 8 <= data_codewords(version) * 8
+        })?;
+
+        let mut codewords = [0; MAX_CODEWORDS];
+        let len = data_codewords(version);
+        let mut bits = BitWriter::new(&mut codewords[..len]);
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, count_bits(version));
+        for &byte in data {
+            bits.push(byte as u32, 8);
+        }
+        bits.finish();
+
+        let mut interleaved = [0; MAX_CODEWORDS];
+        let total = add_error_correction(version, &codewords[..len], &mut interleaved);
+
+        let mut matrix = Matrix::new(version);
+        matrix.draw_function_patterns();
+        matrix.draw_codewords(&interleaved[..total]);
+
+        // Use whichever mask leaves the fewest patterns that confuse a reader
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                let mut masked = matrix.clone();
+                masked.apply_mask(mask);
+                masked.draw_format_bits(mask);
+                masked.penalty()
+            })
+            .unwrap_or(0);
+        matrix.apply_mask(mask);
+        matrix.draw_format_bits(mask);
+
+        Some(Self {
+            version,
+            rows: matrix.modules,
+        })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The width and height in modules, without the quiet zone.
+    pub fn size(&self) -> u32 {
+        self.version as u32 * 4 + 17
+    }
+
+    /// Whether the module at `x`, `y` is dark. Anything outside the code is light.
+    pub fn module(&self, x: u32, y: u32) -> bool {
+        x < self.size() && y < self.size() && self.rows[y as usize] >> x & 1 != 0
+    }
+
+    /// Draw the code as large as fits `area`, centred, with its quiet zone. The code is black
+    /// on white whatever the theme, as some readers insist.
+    pub fn draw<D>(&self, target: &mut D, area: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let modules = self.size() + 2 * QUIET_ZONE;
+        let scale = (area.size.width.min(area.size.height) / modules).max(1);
+        let side = modules * scale;
+        let background = Rectangle::new(
+            area.center() - Point::new(side as i32 / 2, side as i32 / 2),
+            Size::new(side, side),
+        );
+        target.fill_solid(&background, Rgb565::WHITE)?;
+
+        // Runs of dark modules in a row are drawn as one rectangle
+        let origin = background.top_left + Point::new_equal((QUIET_ZONE * scale) as i32);
+        for y in 0..self.size() {
+            let mut x = 0;
+            while x < self.size() {
+                if !self.module(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while self.module(x, y) {
+                    x += 1;
+                }
+                let run = Rectangle::new(
+                    origin + Point::new((start * scale) as i32, (y * scale) as i32),
+                    Size::new((x - start) * scale, scale),
+                );
+                target.fill_solid(&run, Rgb565::BLACK)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Encode `data` as a QR code and draw it as large as fits `area`, see [`QrCode::draw`].
+///
+/// ```ignore
+/// let area = Rectangle::new(Point::new(75, 0), Size::new(170, 170));
+/// ui::draw_qr(&mut screen, b"https://disobey.fi/", area)?;
+/// ```
+pub fn draw_qr<D>(display: &mut D, data: &[u8], area: Rectangle) -> Result<(), QrError<D::Error>>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let code = QrCode::encode(data).ok_or(QrError::TooLong)?;
+    code.draw(display, area).map_err(QrError::Display)
+}
+
+// The length of the character count in byte mode
+fn count_bits(version: u8) -> usize {
+    if version < 10 { 8 } else { 16 }
+}
+
+// The modules left for data and error correction, after the function patterns
+fn raw_codewords(version: u8) -> usize {
+    let version = version as usize;
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+fn data_codewords(version: u8) -> usize {
+    let index = version as usize - 1;
+    raw_codewords(version) - ECC_PER_BLOCK[index] * BLOCKS[index]
+}
+
+// Split `data` into blocks, add error correction to each, and interleave them into `output`.
+// Returns the number of codewords written.
+fn add_error_correction(version: u8, data: &[u8], output: &mut [u8]) -> usize {
+    let index = version as usize - 1;
+    let (blocks, ecc_len) = (BLOCKS[index], ECC_PER_BLOCK[index]);
+    let raw = raw_codewords(version);
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = rs_divisor(ecc_len);
+
+    // Short blocks get a dummy byte after their data, so all blocks line up
+    let mut padded = [[0; MAX_BLOCK_LEN]; BLOCKS[MAX_QR_VERSION as usize - 1]];
+    let mut start = 0;
+    for (block, padded) in padded.iter_mut().enumerate().take(blocks) {
+        let data_len = short_len - ecc_len + usize::from(block >= short_blocks);
+        let chunk = &data[start..start + data_len];
+        start += data_len;
+        padded[..data_len].copy_from_slice(chunk);
+        let ecc = rs_remainder(chunk, &divisor[..ecc_len]);
+        padded[short_len + 1 - ecc_len..short_len + 1].copy_from_slice(&ecc[..ecc_len]);
+    }
+
+    let mut len = 0;
+    for position in 0..=short_len {
+        for (block, padded) in padded.iter().enumerate().take(blocks) {
+            if position != short_len - ecc_len || block >= short_blocks {
+                output[len] = padded[position];
+                len += 1;
+            }
+        }
+    }
+    len
+}
+
+const MAX_ECC: usize = 30;
+
+// The Reed-Solomon generator polynomial of `degree`, highest coefficient first and the leading
+// one left out
+fn rs_divisor(degree: usize) -> [u8; MAX_ECC] {
+    let mut result = [0; MAX_ECC];
+    result[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for index in 0..degree {
+            result[index] = gf_multiply(result[index], root);
+            if index + 1 < degree {
+                result[index] ^= result[index + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> [u8; MAX_ECC] {
+    let mut result = [0; MAX_ECC];
+    let degree = divisor.len();
+    for &byte in data {
+        let factor = byte ^ result[0];
+        result.copy_within(1..degree, 0);
+        result[degree - 1] = 0;
+        for (entry, &coefficient) in result.iter_mut().zip(divisor) {
+            *entry ^= gf_multiply(coefficient, factor);
+        }
+    }
+    result
+}
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut result: u16 = 0;
+    for bit in (0..8).rev() {
+        result = (result << 1) ^ ((result >> 7) * 0x11d);
+        result ^= ((y as u16 >> bit) & 1) * x as u16;
+    }
+    result as u8
+}
+
+// Packs bits into codewords, most significant bit first
+struct BitWriter<'a> {
+    codewords: &'a mut [u8],
+    bits: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(codewords: &'a mut [u8]) -> Self {
+        Self { codewords, bits: 0 }
+    }
+
+    fn push(&mut self, value: u32, len: usize) {
+        for bit in (0..len).rev() {
+            if value >> bit & 1 != 0 {
+                self.codewords[self.bits / 8] |= 0x80 >> (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+
+    // Terminate the data and fill the rest with the alternating pad bytes
+    fn finish(mut self) {
+        let capacity = self.codewords.len() * 8;
+        let terminator = (capacity - self.bits).min(4);
+        self.push(0, terminator);
+        self.push(0, (8 - self.bits % 8) % 8);
+        for pad in [0xec, 0x11].into_iter().cycle() {
+            if self.bits >= capacity {
+                break;
+            }
+            self.push(pad, 8);
+        }
+    }
+}
+
+// The modules being laid out, and which of them belong to function patterns
+#[derive(Clone)]
+struct Matrix {
+    size: usize,
+    version: u8,
+    modules: [u64; MAX_SIZE],
+    function: [u64; MAX_SIZE],
+}
+
+impl Matrix {
+    fn new(version: u8) -> Self {
+        Self {
+            size: version as usize * 4 + 17,
+            version,
+            modules: [0; MAX_SIZE],
+            function: [0; MAX_SIZE],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y] >> x & 1 != 0
+    }
+
+    fn set(&mut self, x: usize, y: usize, dark: bool) {
+        if dark {
+            self.modules[y] |= 1 << x;
+        } else {
+            self.modules[y] &= !(1 << x);
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.set(x, y, dark);
+        self.function[y] |= 1 << x;
+    }
+
+    fn is_function(&self, x: usize, y: usize) -> bool {
+        self.function[y] >> x & 1 != 0
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for index in 0..size {
+            self.set_function(6, index, index % 2 == 0);
+            self.set_function(index, 6, index % 2 == 0);
+        }
+
+        self.draw_finder(3, 3);
+        self.draw_finder(size - 4, 3);
+        self.draw_finder(3, size - 4);
+
+        let (positions, count) = self.alignment_positions();
+        let positions = &positions[..count];
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                let corner = (i == 0 && (j == 0 || j == count - 1)) || (i == count - 1 && j == 0);
+                if !corner {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+
+        // Reserve the format bits, filled in once the mask is known
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in -4..=4_i32 {
+            for dx in -4..=4_i32 {
+                let distance = dx.abs().max(dy.abs());
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, x: usize, y: usize) {
+        for dy in -2..=2_i32 {
+            for dx in -2..=2_i32 {
+                let dark = dx.abs().max(dy.abs()) != 1;
+                self.set_function((x as i32 + dx) as usize, (y as i32 + dy) as usize, dark);
+            }
+        }
+    }
+
+    // The centre coordinates of the alignment patterns along each axis, and how many there are
+    fn alignment_positions(&self) -> ([usize; 7], usize) {
+        let mut positions = [0; 7];
+        if self.version == 1 {
+            return (positions, 0);
+        }
+        let version = self.version as usize;
+        let count = version / 7 + 2;
+        let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+        positions[0] = 6;
+        for index in 1..count {
+            positions[count - index] = self.size - 7 - (index - 1) * step;
+        }
+        (positions, count)
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        // Level M is 0b00 in the format bits
+        let data = mask as u32;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |index: usize| bits >> index & 1 != 0;
+
+        for index in 0..6 {
+            self.set_function(8, index, bit(index));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for index in 9..15 {
+            self.set_function(14 - index, 8, bit(index));
+        }
+
+        let size = self.size;
+        for index in 0..8 {
+            self.set_function(size - 1 - index, 8, bit(index));
+        }
+        for index in 8..15 {
+            self.set_function(8, size - 15 + index, bit(index));
+        }
+        // Always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut remainder = self.version as u32;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+        }
+        let bits = (self.version as u32) << 12 | remainder;
+        for index in 0..18 {
+            let dark = bits >> index & 1 != 0;
+            let (a, b) = (self.size - 11 + index % 3, index / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    // Fill the modules that aren't function patterns in the zigzag order of the specification
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut bit = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for offset in 0..2 {
+                    let x = right - offset;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function(x, y) && bit < codewords.len() * 8 {
+                        let dark = codewords[bit / 8] >> (7 - bit % 8) & 1 != 0;
+                        self.set(x, y, dark);
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function(x, y) {
+                    self.modules[y] ^= 1 << x;
+                }
+            }
+        }
+    }
+
+    // The penalty score of the specification: runs, blocks, finder-like patterns and balance
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let mut result = 0;
+        for transpose in [false, true] {
+            for a in 0..size {
+                let mut run_dark = false;
+                let mut run = 0;
+                let mut history = RunHistory::new(size);
+                for b in 0..size {
+                    let dark = if transpose {
+                        self.get(a, b)
+                    } else {
+                        self.get(b, a)
+                    };
+                    if dark == run_dark {
+                        run += 1;
+                        if run == 5 {
+                            result += 3;
+                        } else if run > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        history.add(run);
+                        if !run_dark {
+                            result += history.count_patterns() * 40;
+                        }
+                        run_dark = dark;
+                        run = 1;
+                    }
+                }
+                result += history.terminate(run_dark, run) * 40;
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.get(x, y);
+                if dark == self.get(x + 1, y)
+                    && dark == self.get(x, y + 1)
+                    && dark == self.get(x + 1, y + 1)
+                {
+                    result += 3;
+                }
+            }
+        }
+
+        let dark: u32 = self.modules[..size]
+            .iter()
+            .map(|row| row.count_ones())
+            .sum();
+        let total = (size * size) as u32;
+        let k = (dark * 20).abs_diff(total * 10).div_ceil(total) - 1;
+        result + k * 10
+    }
+}
+
+// The lengths of the latest runs of modules in a line, for spotting finder-like patterns
+struct RunHistory {
+    runs: [usize; 7],
+    size: usize,
+}
+
+impl RunHistory {
+    fn new(size: usize) -> Self {
+        Self { runs: [0; 7], size }
+    }
+
+    fn add(&mut self, mut run: usize) {
+        // The light area before the line counts as part of the first run
+        if self.runs[0] == 0 {
+            run += self.size;
+        }
+        self.runs.copy_within(0..6, 1);
+        self.runs[0] = run;
+    }
+
+    fn count_patterns(&self) -> u32 {
+        let runs = &self.runs;
+        let n = runs[1];
+        let core = n > 0 && runs[2] == n && runs[3] == n * 3 && runs[4] == n && runs[5] == n;
+        u32::from(core && runs[0] >= n * 4 && runs[6] >= n)
+            + u32::from(core && runs[6] >= n * 4 && runs[0] >= n)
+    }
+
+    fn terminate(&mut self, run_dark: bool, mut run: usize) -> u32 {
+        if run_dark {
+            self.add(run);
+            run = 0;
+        }
+        // The light area after the line
+        run += self.size;
+        self.add(run);
+        self.count_patterns()
+    }
+}
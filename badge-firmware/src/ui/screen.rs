@@ -0,0 +1,130 @@
+//! A stack of screens, the top one shown and in charge of the buttons.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+use crate::input::{Button, ButtonEvent, ButtonEventKind};
+
+/// What to do with the [`ScreenStack`] after an event.
+pub enum Transition<D> {
+    /// Stay on this screen.
+    Stay,
+    /// Open another screen on top of this one.
+    Push(Box<dyn Screen<D>>),
+    /// Close this screen, back to the one below.
+    Pop,
+    /// Close this screen and open another in its place.
+    Replace(Box<dyn Screen<D>>),
+}
+
+/// One screen of an app, drawn on a `D`.
+pub trait Screen<D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// Draw the whole screen.
+    fn draw(&mut self, target: &mut D) -> Result<(), D::Error>;
+
+    /// React to a button event other than pressing B.
+    fn handle_event(&mut self, event: ButtonEvent) -> Transition<D>;
+
+    /// React to pressing B. Closes the screen unless overridden, for example to ask first.
+    fn back(&mut self) -> Transition<D> {
+        Transition::Pop
+    }
+
+    /// Whether the screen only covers part of the display, like a dialog, so the screen below
+    /// shows around it. It still takes every button event for itself.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// The screens of an app, from the first one opened to the one on top.
+///
+/// Only the top screen gets button events, so dialogs are modal. B goes to [`Screen::back`],
+/// which closes the top screen unless it says otherwise, so going back works the same in every
+/// app:
+///
+/// ```ignore
+/// let mut screens = ScreenStack::new(Box::new(MainMenu::new()));
+/// while !screens.is_empty() {
+///     screens.draw(&mut screen)?;
+///     screen.present()?;
+///     screens.handle(subscriber.next_message_pure().await);
+/// }
+/// ```
+///
+/// Closing the first screen leaves the stack empty, which is the app's cue to quit or start
+/// over.
+pub struct ScreenStack<D> {
+    screens: Vec<Box<dyn Screen<D>>>,
+}
+
+impl<D> ScreenStack<D>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    /// A stack with `first` as its only screen.
+    pub fn new(first: Box<dyn Screen<D>>) -> Self {
+        Self {
+            screens: vec![first],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+
+    /// Open `screen` on top of the others.
+    pub fn push(&mut self, screen: Box<dyn Screen<D>>) {
+        self.screens.push(screen);
+    }
+
+    /// Close the top screen and return it.
+    pub fn pop(&mut self) -> Option<Box<dyn Screen<D>>> {
+        self.screens.pop()
+    }
+
+    /// Pass `event` to the top screen and act on what it asks for.
+    pub fn handle(&mut self, event: ButtonEvent) {
+        let Some(top) = self.screens.last_mut() else {
+            return;
+        };
+        let back = event.button == Button::B && event.kind == ButtonEventKind::Pressed;
+        let transition = if back {
+            top.back()
+        } else {
+            top.handle_event(event)
+        };
+        match transition {
+            Transition::Stay => {}
+            Transition::Push(screen) => self.push(screen),
+            Transition::Pop => {
+                self.pop();
+            }
+            Transition::Replace(screen) => {
+                self.pop();
+                self.push(screen);
+            }
+        }
+    }
+
+    /// Draw the top screen, and below it as many as show around overlays.
+    pub fn draw(&mut self, target: &mut D) -> Result<(), D::Error> {
+        let first = self
+            .screens
+            .iter()
+            .rposition(|screen| !screen.is_overlay())
+            .unwrap_or(0);
+        for screen in &mut self.screens[first..] {
+            screen.draw(target)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Frame rate and timing shown over the app, for finding out where the frame time goes.
+
+use core::fmt::Write;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    geometry::AnchorPoint,
+    mono_font::{MonoTextStyle, iso_8859_1::FONT_6X10},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use crate::{display, theme};
+
+// Room for "100.0 fps 100.0 ms 100.0 ms" in a 6x10 font, with a pixel of border
+const SIZE: Size = Size::new(27 * 6 + 2, 12);
+// How often the numbers change, so they can be read at all
+const UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Frames per second, the time spent drawing a frame and the time spent sending it to the
+/// display, averaged over half a second and drawn in a corner.
+///
+/// ```ignore
+/// let mut timing = TimingOverlay::new();
+/// loop {
+///     timing.begin_frame();
+///     // ...draw the frame
+///     timing.draw(&mut screen)?;
+///     timing.measure_flush(|| screen.present())?;
+///     ticker.next().await;
+/// }
+/// ```
+///
+/// The overlay is drawn before the frame is sent, so it shows the flush time of the frames
+/// before. Drawing it takes a little time too, counted as part of the frame.
+#[derive(Clone, Debug)]
+pub struct TimingOverlay {
+    anchor: AnchorPoint,
+    frame_start: Option<Instant>,
+    // Totals since the numbers last changed
+    window_start: Instant,
+    frames: u32,
+    frame_time: Duration,
+    flush_time: Duration,
+    // What's shown
+    fps: f32,
+    frame_ms: f32,
+    flush_ms: f32,
+}
+
+impl Default for TimingOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingOverlay {
+    /// An overlay in the top right corner.
+    pub fn new() -> Self {
+        Self {
+            anchor: AnchorPoint::TopRight,
+            frame_start: None,
+            window_start: Instant::now(),
+            frames: 0,
+            frame_time: Duration::from_ticks(0),
+            flush_time: Duration::from_ticks(0),
+            fps: 0.0,
+            frame_ms: 0.0,
+            flush_ms: 0.0,
+        }
+    }
+
+    /// Where on the display the overlay goes, such as [`AnchorPoint::BottomLeft`].
+    pub fn with_anchor(mut self, anchor: AnchorPoint) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// The area the overlay draws over.
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), display::SIZE).resized(SIZE, self.anchor)
+    }
+
+    /// Mark the start of a frame, before drawing anything.
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        self.frame_start = Some(now);
+        self.frames += 1;
+
+        let elapsed = now - self.window_start;
+        if elapsed >= UPDATE_INTERVAL {
+            let frames = self.frames.max(1) as f32;
+            self.fps = self.frames as f32 * 1_000_000.0 / elapsed.as_micros() as f32;
+            self.frame_ms = self.frame_time.as_micros() as f32 / frames / 1000.0;
+            self.flush_ms = self.flush_time.as_micros() as f32 / frames / 1000.0;
+            self.window_start = now;
+            self.frames = 0;
+            self.frame_time = Duration::from_ticks(0);
+            self.flush_time = Duration::from_ticks(0);
+        }
+    }
+
+    /// Send the frame to the display with `flush`, such as
+    /// [`Framebuffer::present`](display::Framebuffer::present), and time it. This also ends the
+    /// time spent drawing the frame.
+    pub fn measure_flush<R>(&mut self, flush: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        if let Some(frame_start) = self.frame_start.take() {
+            self.frame_time += start - frame_start;
+        }
+        let result = flush();
+        self.flush_time += Instant::now() - start;
+        result
+    }
+
+    /// Draw the latest numbers over the [`area`](Self::area).
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        let area = self.area();
+        target.fill_solid(&area, theme.background)?;
+
+        let mut text = alloc::string::String::new();
+        write!(
+            text,
+            "{:5.1} fps{:6.1} ms{:6.1} ms",
+            self.fps, self.frame_ms, self.flush_ms
+        )
+        .ok();
+        Text::with_baseline(
+            &text,
+            area.top_left + Point::new(1, 1),
+            MonoTextStyle::new(&FONT_6X10, theme.text),
+            Baseline::Top,
+        )
+        .draw(target)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+//! Labels, buttons, progress bars, spinners and toggle switches, laid out on a panel.
+
+use core::f32::consts::TAU;
+
+use embassy_time::Instant;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use crate::{
+    display::UnicodeTextStyle,
+    input::{Button, ButtonEvent, ButtonEventKind},
+    theme::{self, Theme},
+};
+
+// The size of a toggle switch's track
+const TRACK_SIZE: Size = Size::new(40, 20);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind<'t> {
+    Label(&'t str),
+    Button(&'t str),
+    Progress { value: u32, max: u32 },
+    Toggle { text: &'t str, on: bool },
+    Spinner,
+}
+
+/// One element of a [`Panel`], with the area it takes up on the display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Widget<'t> {
+    kind: Kind<'t>,
+    area: Rectangle,
+}
+
+impl<'t> Widget<'t> {
+    /// Text that can't be focused.
+    pub const fn label(text: &'t str, area: Rectangle) -> Self {
+        Self {
+            kind: Kind::Label(text),
+            area,
+        }
+    }
+
+    /// A button, reported as [`WidgetEvent::Pressed`] when pressed.
+    pub const fn button(text: &'t str, area: Rectangle) -> Self {
+        Self {
+            kind: Kind::Button(text),
+            area,
+        }
+    }
+
+    /// A bar filled in proportion to `value` out of `max`, that can't be focused.
+    pub const fn progress(value: u32, max: u32, area: Rectangle) -> Self {
+        Self {
+            kind: Kind::Progress { value, max },
+            area,
+        }
+    }
+
+    /// Dots going round in a circle, for waiting on something without a known end. It moves
+    /// every time it's drawn, so it should be drawn every frame.
+    pub const fn spinner(area: Rectangle) -> Self {
+        Self {
+            kind: Kind::Spinner,
+            area,
+        }
+    }
+
+    /// A switch with `text` next to it, flipped and reported as [`WidgetEvent::Toggled`] when
+    /// pressed.
+    pub const fn toggle(text: &'t str, on: bool, area: Rectangle) -> Self {
+        Self {
+            kind: Kind::Toggle { text, on },
+            area,
+        }
+    }
+
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Whether the widget can take the focus, so buttons and switches.
+    pub fn is_focusable(&self) -> bool {
+        matches!(self.kind, Kind::Button(_) | Kind::Toggle { .. })
+    }
+
+    /// Change the text of a label, button or switch.
+    pub fn set_text(&mut self, text: &'t str) {
+        match &mut self.kind {
+            Kind::Label(old) | Kind::Button(old) | Kind::Toggle { text: old, .. } => *old = text,
+            Kind::Progress { .. } | Kind::Spinner => {}
+        }
+    }
+
+    /// Change the value of a progress bar, up to its maximum.
+    pub fn set_progress(&mut self, value: u32) {
+        if let Kind::Progress { value: old, max } = &mut self.kind {
+            *old = value.min(*max);
+        }
+    }
+
+    /// Whether a switch is on. Anything else is never on.
+    pub fn is_on(&self) -> bool {
+        matches!(self.kind, Kind::Toggle { on: true, .. })
+    }
+
+    pub fn set_on(&mut self, on: bool) {
+        if let Kind::Toggle { on: old, .. } = &mut self.kind {
+            *old = on;
+        }
+    }
+
+    /// Draw the widget, highlighted if `focused`.
+    pub fn draw<D>(&self, target: &mut D, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::current();
+        target.fill_solid(&self.area, theme.background)?;
+        match self.kind {
+            Kind::Label(text) => draw_text(target, text, self.area, Alignment::Left, theme.text),
+            Kind::Button(text) => {
+                let (fill, color) = if focused {
+                    (theme.highlight, theme.highlight_text)
+                } else {
+                    (theme.background, theme.text)
+                };
+                let style = PrimitiveStyle::with_fill(fill);
+                RoundedRectangle::with_equal_corners(self.area, Size::new(6, 6))
+                    .into_styled(style)
+                    .draw(target)?;
+                RoundedRectangle::with_equal_corners(self.area, Size::new(6, 6))
+                    .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+                    .draw(target)?;
+                draw_text(target, text, self.area, Alignment::Center, color)
+            }
+            Kind::Progress { value, max } => {
+                let width = self.area.size.width.saturating_sub(2);
+                let filled = (value.min(max) as u64 * width as u64 / max.max(1) as u64) as u32;
+                let inside = Rectangle::new(
+                    self.area.top_left + Point::new(1, 1),
+                    Size::new(filled, self.area.size.height.saturating_sub(2)),
+                );
+                target.fill_solid(&inside, theme.widget)?;
+                self.area
+                    .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+                    .draw(target)
+            }
+            Kind::Toggle { text, on } => {
+                if focused {
+                    target.fill_solid(&self.area, theme.highlight)?;
+                }
+                let color = if focused {
+                    theme.highlight_text
+                } else {
+                    theme.text
+                };
+                draw_text(target, text, self.area, Alignment::Left, color)?;
+                draw_switch(target, self.area, on, theme)
+            }
+            Kind::Spinner => draw_spinner(target, self.area, theme),
+        }
+    }
+}
+
+// `text` vertically centred in `area`, aligned as asked
+fn draw_text<D>(
+    target: &mut D,
+    text: &str,
+    area: Rectangle,
+    alignment: Alignment,
+    color: Rgb565,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let x = match alignment {
+        Alignment::Left => area.top_left.x + 4,
+        Alignment::Center => area.center().x,
+        Alignment::Right => area.top_left.x + area.size.width as i32 - 5,
+    };
+    let style = TextStyleBuilder::new()
+        .alignment(alignment)
+        .baseline(Baseline::Middle)
+        .build();
+    let character_style = UnicodeTextStyle::new(theme::current().font, color);
+    Text::with_text_style(text, Point::new(x, area.center().y), character_style, style)
+        .draw(target)?;
+    Ok(())
+}
+
+// A track at the right of `area` with a knob at the right end when on, the left when off
+fn draw_switch<D>(target: &mut D, area: Rectangle, on: bool, theme: &Theme) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let top_left = Point::new(
+        area.top_left.x + area.size.width as i32 - TRACK_SIZE.width as i32 - 4,
+        area.center().y - TRACK_SIZE.height as i32 / 2,
+    );
+    let track = Rectangle::new(top_left, TRACK_SIZE);
+    let radius = Size::new(TRACK_SIZE.height / 2, TRACK_SIZE.height / 2);
+    let (fill, knob_x) = if on {
+        (theme.widget, TRACK_SIZE.width - TRACK_SIZE.height)
+    } else {
+        (theme.background, 0)
+    };
+    let style = PrimitiveStyle::with_fill(fill);
+    RoundedRectangle::with_equal_corners(track, radius)
+        .into_styled(style)
+        .draw(target)?;
+    RoundedRectangle::with_equal_corners(track, radius)
+        .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+        .draw(target)?;
+    Circle::new(
+        top_left + Point::new(knob_x as i32 + 2, 2),
+        TRACK_SIZE.height - 4,
+    )
+    .into_styled(PrimitiveStyle::with_fill(theme.text))
+    .draw(target)
+}
+
+// Dots in a circle in `area`, one bright and the ones behind it fading, going round once a
+// second
+fn draw_spinner<D>(target: &mut D, area: Rectangle, theme: &Theme) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    const DOTS: u32 = 8;
+    let side = area.size.width.min(area.size.height);
+    let dot = (side / 5).max(2);
+    let radius = (side - dot) as f32 / 2.0;
+    let lead = (Instant::now().as_millis() * DOTS as u64 / 1000) as u32 % DOTS;
+
+    for index in 0..DOTS {
+        let angle = index as f32 / DOTS as f32 * TAU;
+        let center = area.center()
+            + Point::new(
+                libm::roundf(libm::sinf(angle) * radius) as i32,
+                -libm::roundf(libm::cosf(angle) * radius) as i32,
+            );
+        let color = match (lead + DOTS - index) % DOTS {
+            0 => theme.widget,
+            1 | 2 => theme.text_dim,
+            _ => theme.border,
+        };
+        Circle::with_center(center, dot)
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(target)?;
+    }
+    Ok(())
+}
+
+/// What the user did with a [`Panel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum WidgetEvent {
+    /// Pressed the button at this index.
+    Pressed(usize),
+    /// Flipped the switch at this index, which is now on or off.
+    Toggled(usize, bool),
+    /// Left the panel with B.
+    Back,
+}
+
+/// A set of widgets, one of them focused at a time.
+///
+/// Up and left move the focus to the previous button or switch, down and right to the next,
+/// and A or the stick press the focused one:
+///
+/// ```ignore
+/// let row = |y| Rectangle::new(Point::new(10, y), Size::new(300, 24));
+/// let mut widgets = [
+///     Widget::label("Sound", row(10)),
+///     Widget::toggle("Beep on press", true, row(40)),
+///     Widget::button("Done", Rectangle::new(Point::new(110, 120), Size::new(100, 30))),
+/// ];
+/// let mut panel = Panel::new(&mut widgets);
+/// ```
+pub struct Panel<'w, 't> {
+    widgets: &'w mut [Widget<'t>],
+    focus: Option<usize>,
+}
+
+impl<'w, 't> Panel<'w, 't> {
+    /// A panel of `widgets`, focused on the first one that can be.
+    pub fn new(widgets: &'w mut [Widget<'t>]) -> Self {
+        let focus = widgets.iter().position(Widget::is_focusable);
+        Self { widgets, focus }
+    }
+
+    pub fn widgets(&self) -> &[Widget<'t>] {
+        self.widgets
+    }
+
+    /// The widget at `index`, to change it. Redraw the panel after.
+    pub fn widget_mut(&mut self, index: usize) -> Option<&mut Widget<'t>> {
+        self.widgets.get_mut(index)
+    }
+
+    /// The index of the focused widget, if any can be focused.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    /// Focus the widget at `index`, if it can be.
+    pub fn set_focus(&mut self, index: usize) {
+        if self.widgets.get(index).is_some_and(Widget::is_focusable) {
+            self.focus = Some(index);
+        }
+    }
+
+    // Focus the next widget that can be, or the previous one, round past either end
+    fn move_focus(&mut self, forward: bool) {
+        let Some(focus) = self.focus else {
+            return;
+        };
+        let len = self.widgets.len();
+        let step = if forward { 1 } else { len - 1 };
+        let next = (1..len)
+            .map(|offset| (focus + offset * step) % len)
+            .find(|&index| self.widgets[index].is_focusable());
+        if let Some(next) = next {
+            self.focus = Some(next);
+        }
+    }
+
+    /// React to a button event. Returns what the user did, if anything besides moving the focus.
+    pub fn handle(&mut self, event: ButtonEvent) -> Option<WidgetEvent> {
+        if !matches!(
+            event.kind,
+            ButtonEventKind::Pressed | ButtonEventKind::Repeated
+        ) {
+            return None;
+        }
+        match event.button {
+            Button::Up | Button::Left => self.move_focus(false),
+            Button::Down | Button::Right => self.move_focus(true),
+            Button::A | Button::Stick => {
+                let index = self.focus?;
+                let widget = &mut self.widgets[index];
+                if let Kind::Toggle { on, .. } = &mut widget.kind {
+                    *on = !*on;
+                    return Some(WidgetEvent::Toggled(index, *on));
+                }
+                return Some(WidgetEvent::Pressed(index));
+            }
+            Button::B => return Some(WidgetEvent::Back),
+            Button::Start | Button::Select => {}
+        }
+        None
+    }
+
+    /// Draw every widget.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        for (index, widget) in self.widgets.iter().enumerate() {
+            widget.draw(target, self.focus == Some(index))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! What firmware is running, and on which badge.
+//!
+//! Most of this comes from the app descriptor that `esp_app_desc!()` puts in every binary, so
+//! pass in the `ESP_APP_DESC` it creates:
+//!
+//! ```ignore
+//! esp_bootloader_esp_idf::esp_app_desc!();
+//!
+//! let version = Version::read(&ESP_APP_DESC, peripherals.FLASH.reborrow());
+//! info!("{}", version);
+//! ```
+
+use alloc::string::String;
+
+use esp_bootloader_esp_idf::{
+    EspAppDesc,
+    ota_updater::OtaUpdater,
+    partitions::{AppPartitionSubType, PARTITION_TABLE_MAX_LEN},
+};
+use esp_hal::{efuse::Efuse, peripherals::FLASH};
+use esp_storage::FlashStorage;
+
+/// Short hash of the git commit the firmware was built from, set by the build script.
+pub const GIT_HASH: &str = match option_env!("BADGE_GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+
+/// The app partition the badge booted from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Slot {
+    Factory,
+    Ota(u8),
+    Test,
+}
+
+impl From<AppPartitionSubType> for Slot {
+    fn from(subtype: AppPartitionSubType) -> Self {
+        match subtype {
+            AppPartitionSubType::Factory => Slot::Factory,
+            AppPartitionSubType::Test => Slot::Test,
+            ota => Slot::Ota(ota as u8 - AppPartitionSubType::Ota0 as u8),
+        }
+    }
+}
+
+/// Everything worth knowing when someone asks "what firmware are you on?"
+#[derive(Clone, Debug, defmt::Format)]
+pub struct Version {
+    /// The crate version from `Cargo.toml`.
+    pub version: &'static str,
+    pub project_name: &'static str,
+    pub git_hash: &'static str,
+    pub build_date: &'static str,
+    pub build_time: &'static str,
+    pub idf_version: &'static str,
+    /// The factory MAC address, unique to every badge.
+    pub badge_id: [u8; 6],
+    /// `None` if the OTA data couldn't be read.
+    pub slot: Option<Slot>,
+}
+
+impl Version {
+    /// Collect the version details from the app descriptor, the eFuses and the OTA data.
+    pub fn read(app_desc: &'static EspAppDesc, flash: FLASH<'_>) -> Self {
+        Self {
+            version: app_desc.version(),
+            project_name: app_desc.project_name(),
+            git_hash: GIT_HASH,
+            build_date: app_desc.date(),
+            build_time: app_desc.time(),
+            idf_version: app_desc.idf_ver(),
+            badge_id: Efuse::mac_address(),
+            slot: read_slot(flash),
+        }
+    }
+
+    /// The badge ID formatted like a MAC address, e.g. `"24:0a:c4:12:34:56"`.
+    pub fn badge_id_string(&self) -> String {
+        let [a, b, c, d, e, f] = self.badge_id;
+        alloc::format!("{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{f:02x}")
+    }
+}
+
+// The selected partition is the one the bootloader picked, so the one we're running from
+fn read_slot(flash: FLASH<'_>) -> Option<Slot> {
+    let mut flash = FlashStorage::new(flash);
+    let mut buffer = [0u8; PARTITION_TABLE_MAX_LEN];
+    let mut ota = OtaUpdater::new(&mut flash, &mut buffer).ok()?;
+    ota.selected_partition().ok().map(Slot::from)
+}